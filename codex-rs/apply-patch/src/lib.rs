@@ -443,10 +443,15 @@ fn compute_replacements(
             ) {
                 line_index = idx + 1;
             } else {
+                let suggestion = near_miss_suggestion_suffix(
+                    original_lines,
+                    std::slice::from_ref(ctx_line),
+                );
                 return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
+                    "Failed to find context '{}' in {}{}",
                     ctx_line,
-                    path.display()
+                    path.display(),
+                    suggestion,
                 )));
             }
         }
@@ -500,10 +505,12 @@ fn compute_replacements(
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
         } else {
+            let suggestion = near_miss_suggestion_suffix(original_lines, pattern);
             return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
+                "Failed to find expected lines in {}:\n{}{}",
                 path.display(),
                 chunk.old_lines.join("\n"),
+                suggestion,
             )));
         }
     }
@@ -513,6 +520,18 @@ fn compute_replacements(
     Ok(replacements)
 }
 
+/// Render [`seek_sequence::closest_match_suggestion`]'s best guess as a
+/// ready-to-append suffix for a `ComputeReplacements` error message, or the
+/// empty string if no near miss cleared the similarity threshold.
+fn near_miss_suggestion_suffix(lines: &[String], pattern: &[String]) -> String {
+    match seek_sequence::closest_match_suggestion(lines, pattern) {
+        Some((start_idx, suggestion)) => {
+            format!("\nThe closest match is at line {}:\n{}", start_idx + 1, suggestion)
+        }
+        None => String::new(),
+    }
+}
+
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
 /// returning the modified file contents as a vector of lines.
 fn apply_replacements(
@@ -9,6 +9,14 @@
 ///  • Empty `pattern` → returns `Some(start)` (no-op match)
 ///  • `pattern.len() > lines.len()` → returns `None` (cannot match, avoids
 ///    out‑of‑bounds panic that occurred pre‑2025‑04‑12)
+///
+/// On a `None` here, the caller (`compute_replacements`) surfaces
+/// `ApplyPatchError::ComputeReplacements` with the unmatched context line,
+/// augmented with [`closest_match_suggestion`]'s best guess at what the
+/// author meant - there's no symbol-path locator in this crate (no `ast`
+/// module, `SymbolResolution`, or `SymbolPath` type) to resolve a typo'd
+/// context line against, so that suggestion is plain line-text resemblance
+/// rather than anything scope-aware.
 pub(crate) fn seek_sequence(
     lines: &[String],
     pattern: &[String],
@@ -109,8 +117,84 @@ pub(crate) fn seek_sequence(
     None
 }
 
+/// Best-effort guess at what the author of `pattern` meant, for use in error
+/// messages when [`seek_sequence`] returns `None`.
+///
+/// Slides a `pattern.len()`-line window over `lines` and scores each
+/// position by [`line_similarity`], averaged across the window. Returns the
+/// 0-indexed start of the best-scoring window and its joined text, or `None`
+/// if `lines`/`pattern` is empty or the best score doesn't clear
+/// `MIN_SIMILARITY` - below that, the "closest" window is just noise and
+/// printing it would mislead more than help.
+pub(crate) fn closest_match_suggestion(
+    lines: &[String],
+    pattern: &[String],
+) -> Option<(usize, String)> {
+    const MIN_SIMILARITY: f64 = 0.5;
+
+    if pattern.is_empty() || lines.is_empty() || pattern.len() > lines.len() {
+        return None;
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for i in 0..=lines.len() - pattern.len() {
+        let window = &lines[i..i + pattern.len()];
+        let score: f64 = window
+            .iter()
+            .zip(pattern.iter())
+            .map(|(line, pat)| line_similarity(line, pat))
+            .sum::<f64>()
+            / pattern.len() as f64;
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((i, score));
+        }
+    }
+
+    let (start, score) = best?;
+    if score < MIN_SIMILARITY {
+        return None;
+    }
+    Some((start, lines[start..start + pattern.len()].join("\n")))
+}
+
+/// Character-level similarity between `a` and `b` on a `0.0..=1.0` scale,
+/// where `1.0` is an exact match. Defined as `1 - (levenshtein distance /
+/// longer length)`, so the threshold in [`closest_match_suggestion`] is the
+/// fraction of the longer line that must survive unedited.
+fn line_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - (levenshtein_distance(&a, &b) as f64 / max_len as f64)
+}
+
+/// Classic dynamic-programming edit distance (insertions, deletions,
+/// substitutions all cost 1) between two character slices.
+fn levenshtein_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            curr[j + 1] = if ca == cb {
+                prev[j]
+            } else {
+                1 + prev[j].min(curr[j]).min(prev[j + 1])
+            };
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
+    use super::closest_match_suggestion;
     use super::seek_sequence;
     use std::string::ToString;
 
@@ -160,4 +244,29 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn closest_match_suggestion_finds_a_typo_d_line() {
+        let lines = to_vec(&["fn foo() {", "    let value = 1;", "    value + 1", "}"]);
+        // Typo'd "vaule" instead of "value" - not similar enough to match
+        // via `seek_sequence`'s lenient passes, but close enough to suggest.
+        let pattern = to_vec(&["    let vaule = 1;"]);
+        let (start, suggestion) = closest_match_suggestion(&lines, &pattern).expect("suggestion");
+        assert_eq!(start, 1);
+        assert_eq!(suggestion, "    let value = 1;");
+    }
+
+    #[test]
+    fn closest_match_suggestion_returns_none_when_nothing_is_close() {
+        let lines = to_vec(&["fn foo() {", "}"]);
+        let pattern = to_vec(&["completely unrelated text"]);
+        assert_eq!(closest_match_suggestion(&lines, &pattern), None);
+    }
+
+    #[test]
+    fn closest_match_suggestion_returns_none_for_empty_pattern() {
+        let lines = to_vec(&["foo", "bar"]);
+        let pattern: Vec<String> = Vec::new();
+        assert_eq!(closest_match_suggestion(&lines, &pattern), None);
+    }
 }
@@ -40,11 +40,13 @@ mod app_cmd;
 mod desktop_app;
 mod marketplace_cmd;
 mod mcp_cmd;
+mod navigator_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
 use crate::marketplace_cmd::MarketplaceCli;
 use crate::mcp_cmd::McpCli;
+use crate::navigator_cmd::NavigatorCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -110,6 +112,9 @@ enum Subcommand {
     /// Manage plugin marketplaces for Codex.
     Marketplace(MarketplaceCli),
 
+    /// Query the codebase index's symbol/reference graph.
+    Navigator(NavigatorCli),
+
     /// Start Codex as an MCP server (stdio).
     McpServer,
 
@@ -721,6 +726,9 @@ async fn cli_main(arg0_paths: Arg0DispatchPaths) -> anyhow::Result<()> {
             );
             marketplace_cli.run().await?;
         }
+        Some(Subcommand::Navigator(navigator_cli)) => {
+            navigator_cli.run().await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => {
             let AppServerCommand {
                 subcommand,
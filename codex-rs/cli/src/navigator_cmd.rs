@@ -0,0 +1,761 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::Parser;
+use codex_code_chunker::Chunker;
+use codex_codebase_indexer::CodebaseIndexer;
+use codex_codebase_indexer::IndexerConfig;
+use codex_navigator::CallGraphRequest;
+use codex_navigator::HealthRequest;
+use codex_navigator::HealthResponse;
+use codex_navigator::ImpactRequest;
+use codex_navigator::IndexCoordinator;
+use codex_navigator::NavHit;
+use codex_navigator::ReferencesDirection;
+use codex_navigator::ReferencesRequest;
+use codex_navigator::SearchRequest;
+use codex_navigator::export::GraphFormat;
+use codex_navigator::export::render;
+use codex_navigator::health::HealthStats;
+use codex_navigator::index_export::IndexExportKind;
+use codex_navigator::index_export::write_jsonl;
+use codex_vector_store::VectorStore;
+use codex_vector_store::VectorStoreConfig;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Parser)]
+pub struct NavigatorCli {
+    #[command(subcommand)]
+    subcommand: NavigatorSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum NavigatorSubcommand {
+    /// Show who calls (or is called by) a symbol.
+    Refs(RefsArgs),
+
+    /// Search indexed chunk content.
+    Search(SearchArgs),
+
+    /// Report how widely referenced a symbol is, for scoping a rename
+    /// before doing it.
+    Impact(ImpactArgs),
+
+    /// Export the caller/callee graph reachable from a symbol as Graphviz
+    /// DOT or a Mermaid flowchart.
+    Graph(GraphArgs),
+
+    /// Export the full index as newline-delimited JSON, for offline
+    /// analysis outside this crate.
+    Export(ExportArgs),
+
+    /// Report (and optionally re-index) chunks left over from an older
+    /// version of the chunker.
+    Doctor(DoctorArgs),
+}
+
+#[derive(Debug, Parser)]
+struct RefsArgs {
+    /// Symbol id, in `path#line` form (as produced by the indexer).
+    id: String,
+
+    /// Which side of the call graph to show.
+    #[arg(long, value_enum, default_value_t = Direction::Both)]
+    direction: Direction,
+
+    /// Maximum number of hits to return.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Path to the vector store's table file to query.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+}
+
+#[derive(Debug, Parser)]
+struct SearchArgs {
+    /// Text to search for, or the regular expression to match when
+    /// `--regex` is set.
+    query: String,
+
+    /// Treat `query` as a regular expression matched against chunk content,
+    /// instead of a plain substring.
+    #[arg(long)]
+    regex: bool,
+
+    /// Maximum number of hits to return.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Continuation token printed as `next page: <cursor>` by a previous
+    /// run of this command, for fetching the page of hits after it.
+    #[arg(long, value_name = "CURSOR")]
+    cursor: Option<String>,
+
+    /// Re-run this query whenever the store file changes, printing only
+    /// what changed (`+`/`-`/`~` for added/removed/moved hits) instead of
+    /// the full hit list each time. There's no daemon in this codebase to
+    /// subscribe to for index-generation change notifications, so this
+    /// polls the store file's mtime instead - see `run_search_watch`.
+    #[arg(long)]
+    watch: bool,
+
+    /// When exact matching comes up short of `limit`, fill the remainder
+    /// with nucleo fuzzy-scored hits (see
+    /// [`codex_navigator::SearchRequest::fuzzy_fallback`]) so a typo'd query
+    /// still finds the intended symbol. Off by default.
+    #[arg(long)]
+    fuzzy: bool,
+
+    /// Require the same letter case as `query` (see
+    /// [`codex_navigator::SearchRequest::case_sensitive`]). Off by default.
+    #[arg(long)]
+    case_sensitive: bool,
+
+    /// Require `query` to match at a token boundary, so `new` no longer
+    /// matches inside `renew` (see
+    /// [`codex_navigator::SearchRequest::whole_word`]). Off by default.
+    #[arg(long)]
+    whole_word: bool,
+
+    /// Print up to `reference_limit` approximate caller locations under
+    /// each hit (see [`codex_navigator::SearchRequest::include_references`]).
+    /// Off by default: computing references rescans every chunk per hit.
+    #[arg(long)]
+    references: bool,
+
+    /// Caps approximate caller locations per hit when `--references` is
+    /// set (see [`codex_navigator::SearchRequest::reference_limit`]).
+    #[arg(long, default_value_t = 5)]
+    reference_limit: usize,
+
+    /// Restrict hits to declaration sites, for "go to definition" (see
+    /// [`codex_navigator::SearchRequest::definitions_only`]). Off by
+    /// default.
+    #[arg(long)]
+    definitions_only: bool,
+
+    /// Restrict hits to chunks with this
+    /// [`codex_navigator::SearchRequest::chunk_types`] tag (e.g. `function`,
+    /// `struct`), repeatable. Matches any chunk type by default.
+    #[arg(long = "chunk-type", value_name = "TYPE", action = clap::ArgAction::Append)]
+    chunk_type: Vec<String>,
+
+    /// Path to the vector store's table file to query.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+}
+
+#[derive(Debug, Parser)]
+struct ImpactArgs {
+    /// Symbol id, in `path#line` form (as produced by the indexer).
+    id: String,
+
+    /// Include call sites inside `#[test]` functions in the report.
+    #[arg(long = "tests")]
+    include_tests: bool,
+
+    /// Maximum number of call sites to print (the file and chunk-type
+    /// counts are never truncated by this).
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Path to the vector store's table file to query.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+}
+
+#[derive(Debug, Parser)]
+struct GraphArgs {
+    /// Symbol id, in `path#line` form (as produced by the indexer).
+    id: String,
+
+    /// Output format.
+    #[arg(long = "export", value_enum, default_value_t = ExportFormat::Dot)]
+    export: ExportFormat,
+
+    /// How many hops of callers/callees to follow outward from `id`.
+    #[arg(long, default_value_t = 2)]
+    depth: usize,
+
+    /// Write the rendered graph to this file instead of stdout.
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Path to the vector store's table file to query.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    Dot,
+    Mermaid,
+}
+
+impl From<ExportFormat> for GraphFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Dot => GraphFormat::Dot,
+            ExportFormat::Mermaid => GraphFormat::Mermaid,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct ExportArgs {
+    /// Which record kinds to include, in the order they're emitted.
+    /// Comma-separated, e.g. `--include symbols,files`.
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [
+        IncludeKind::Symbols,
+        IncludeKind::Files,
+        IncludeKind::References,
+    ])]
+    include: Vec<IncludeKind>,
+
+    /// Write the export here instead of stdout.
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Path to the vector store's table file to export.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum IncludeKind {
+    Symbols,
+    Files,
+    References,
+}
+
+impl From<IncludeKind> for IndexExportKind {
+    fn from(kind: IncludeKind) -> Self {
+        match kind {
+            IncludeKind::Symbols => IndexExportKind::Symbols,
+            IncludeKind::Files => IndexExportKind::Files,
+            IncludeKind::References => IndexExportKind::References,
+        }
+    }
+}
+
+/// Lists (and, with `--fix-pending`, re-indexes) chunks whose
+/// `chunker_version` is older than [`Chunker::CURRENT_VERSION`], i.e. the
+/// ones a chunker upgrade left behind.
+///
+/// There's no daemon in this codebase tracking which files are pending
+/// re-index as they change, no wire protocol for triggering this remotely,
+/// and no health store recording past runs - this command is the local,
+/// one-shot operation those would eventually call into. Today the pending
+/// list is whatever [`VectorStore::find_stale`] can read back out of the
+/// store itself.
+#[derive(Debug, Parser)]
+struct DoctorArgs {
+    /// Path to the vector store's table file to check.
+    #[arg(long, value_name = "PATH")]
+    store: PathBuf,
+
+    /// Embedding dimension the store was created with.
+    #[arg(long, default_value_t = 768)]
+    embedding_dim: usize,
+
+    /// Root directory the stale files should be re-read from. Required
+    /// when `--fix-pending` is set.
+    #[arg(long, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// Directory holding the embedding cache for `root`. Required when
+    /// `--fix-pending` is set.
+    #[arg(long, value_name = "PATH")]
+    index_dir: Option<PathBuf>,
+
+    /// Re-index the stale files instead of just listing them.
+    #[arg(long)]
+    fix_pending: bool,
+
+    /// Re-index at most this many stale files. Ignored unless
+    /// `--fix-pending` is set.
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Print persisted per-operation timing stats instead of the stale-chunk
+    /// report. Every sample printed was written by a previous invocation of
+    /// this binary, since this command has no daemon process of its own to
+    /// hold in-memory stats across runs - see `codex_navigator::health`.
+    #[arg(long)]
+    stats: bool,
+
+    /// Print index state counts (chunks/files/symbols/reference edges) for
+    /// `store` instead of the stale-chunk report, via
+    /// `IndexCoordinator::handle_health`.
+    #[arg(long)]
+    health: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum Direction {
+    In,
+    Out,
+    Both,
+}
+
+impl From<Direction> for ReferencesDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::In => ReferencesDirection::Incoming,
+            Direction::Out => ReferencesDirection::Outgoing,
+            Direction::Both => ReferencesDirection::Both,
+        }
+    }
+}
+
+impl NavigatorCli {
+    pub async fn run(self) -> Result<()> {
+        match self.subcommand {
+            NavigatorSubcommand::Refs(args) => run_refs(args),
+            NavigatorSubcommand::Search(args) => run_search(args).await,
+            NavigatorSubcommand::Impact(args) => run_impact(args),
+            NavigatorSubcommand::Graph(args) => run_graph(args),
+            NavigatorSubcommand::Export(args) => run_export(args),
+            NavigatorSubcommand::Doctor(args) => run_doctor(args),
+        }
+    }
+}
+
+fn run_refs(args: RefsArgs) -> Result<()> {
+    let store_path = args.store.clone();
+    record_operation(&store_path, "refs", || {
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let coordinator = IndexCoordinator::new(store.chunks().cloned().collect());
+
+        let response = coordinator.handle_references(ReferencesRequest {
+            id: args.id,
+            direction: args.direction.into(),
+            limit: args.limit,
+        })?;
+
+        if response.hits.is_empty() {
+            println!("(no references found)");
+        }
+        for hit in &response.hits {
+            print_hit(hit);
+        }
+        Ok(())
+    })
+}
+
+/// Times `body`, then records the elapsed duration against `label` in the
+/// store's `.health.json` sidecar (see [`codex_navigator::health`]) before
+/// returning `body`'s result. A failure to persist the sidecar is swallowed -
+/// bookkeeping must never turn an otherwise-successful query into a failing
+/// command.
+fn record_operation<T>(store: &Path, label: &str, body: impl FnOnce() -> Result<T>) -> Result<T> {
+    let start = Instant::now();
+    let result = body();
+    let health_path = HealthStats::path_for(store);
+    let mut stats = HealthStats::load(&health_path);
+    stats.record(label, start.elapsed());
+    let _ = stats.persist(&health_path);
+    result
+}
+
+async fn run_search(args: SearchArgs) -> Result<()> {
+    if args.watch {
+        return run_search_watch(args).await;
+    }
+
+    let store_path = args.store.clone();
+    record_operation(&store_path, "search", || {
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let coordinator = IndexCoordinator::new(store.chunks().cloned().collect());
+
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: if args.regex { String::new() } else { args.query.clone() },
+                query_regex: if args.regex { Some(args.query) } else { None },
+                limit: args.limit,
+                cursor: args.cursor,
+                root: None,
+                fuzzy_fallback: args.fuzzy,
+                case_sensitive: args.case_sensitive,
+                whole_word: args.whole_word,
+                include_references: args.references,
+                reference_limit: args.reference_limit,
+                definitions_only: args.definitions_only,
+                chunk_types: args.chunk_type.clone(),
+            })
+            .map_err(|payload| anyhow::anyhow!("{:?}: {}", payload.code, payload.message))?;
+
+        if response.hits.is_empty() {
+            println!("(no matches found)");
+        }
+        for hit in &response.hits {
+            print_hit(hit);
+        }
+        if let Some(next_cursor) = response.next_cursor {
+            println!("next page: {next_cursor}");
+        }
+        Ok(())
+    })
+}
+
+/// How often `run_search_watch` checks the store file's mtime for changes.
+/// Also the debounce window: a burst of writes within one interval is seen
+/// as a single update, since only the mtime at poll time is ever read.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Re-runs `args`'s query every time the store file's mtime changes, until
+/// cancelled (by ctrl-c), printing a diff of hits against the previous run
+/// instead of the full list each time.
+///
+/// This substitutes for the index-generation push notifications a daemon
+/// would send: this codebase has no daemon and no wire protocol to receive
+/// them over, so the store file's mtime (bumped by
+/// [`codex_vector_store::VectorStore::persist`] on every mutation) stands in
+/// as the only observable signal that the index changed. Shutdown uses the
+/// same [`CancellationToken`] idiom as the rest of the workspace (see
+/// `codex_async_utils::OrCancelExt`): ctrl-c cancels the token, which is only
+/// ever observed between searches, so a search already in flight always
+/// finishes rather than being cut off mid-run.
+///
+/// Unlike `run_search`'s one-shot path, iterations here don't record timing
+/// via `record_operation` - persisting the sidecar on every poll would churn
+/// the file for no benefit when nothing changed, and "time to run this
+/// search" isn't a meaningful stat when most of it is spent idle in
+/// `wait_or_cancelled`.
+async fn run_search_watch(args: SearchArgs) -> Result<()> {
+    println!("watching {} for index updates (ctrl-c to stop)", args.store.display());
+
+    let cancellation = CancellationToken::new();
+    let ctrl_c_token = cancellation.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c_token.cancel();
+        }
+    });
+
+    let mut last_modified: Option<SystemTime> = None;
+    let mut previous: Option<Vec<NavHit>> = None;
+    loop {
+        let modified = tokio::fs::metadata(&args.store).await.ok().and_then(|m| m.modified().ok());
+        if previous.is_some() && modified == last_modified {
+            if wait_or_cancelled(&cancellation, WATCH_POLL_INTERVAL).await {
+                println!("stopped watching");
+                return Ok(());
+            }
+            continue;
+        }
+        last_modified = modified;
+
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let coordinator = IndexCoordinator::new(store.chunks().cloned().collect());
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: if args.regex { String::new() } else { args.query.clone() },
+                query_regex: if args.regex { Some(args.query.clone()) } else { None },
+                limit: args.limit,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: args.fuzzy,
+                case_sensitive: args.case_sensitive,
+                whole_word: args.whole_word,
+                include_references: args.references,
+                reference_limit: args.reference_limit,
+                definitions_only: args.definitions_only,
+                chunk_types: args.chunk_type.clone(),
+            })
+            .map_err(|payload| anyhow::anyhow!("{:?}: {}", payload.code, payload.message))?;
+
+        match &previous {
+            None => {
+                if response.hits.is_empty() {
+                    println!("(no matches found)");
+                }
+                for hit in &response.hits {
+                    print_hit(hit);
+                }
+            }
+            Some(previous_hits) => print_hit_diff(previous_hits, &response.hits),
+        }
+        previous = Some(response.hits);
+
+        if wait_or_cancelled(&cancellation, WATCH_POLL_INTERVAL).await {
+            println!("stopped watching");
+            return Ok(());
+        }
+    }
+}
+
+/// Waits out `interval`, returning `true` early if `cancellation` fires
+/// first. Split out of `run_search_watch` so the shutdown race is testable
+/// without a real store file on disk.
+async fn wait_or_cancelled(cancellation: &CancellationToken, interval: Duration) -> bool {
+    tokio::select! {
+        _ = cancellation.cancelled() => true,
+        _ = tokio::time::sleep(interval) => false,
+    }
+}
+
+/// Prints only what changed between two runs of the same watched query,
+/// keyed by [`NavHit::id`]: `+` for a newly matching hit, `-` for one that
+/// no longer matches, `~` for one still matching but at a different line.
+fn print_hit_diff(previous: &[NavHit], current: &[NavHit]) {
+    let previous_by_id: BTreeMap<&str, &NavHit> =
+        previous.iter().map(|hit| (hit.id.as_str(), hit)).collect();
+    let current_by_id: BTreeMap<&str, &NavHit> =
+        current.iter().map(|hit| (hit.id.as_str(), hit)).collect();
+
+    let mut changed = false;
+    for hit in current {
+        match previous_by_id.get(hit.id.as_str()) {
+            None => {
+                changed = true;
+                println!("+ {}:{}  {}", hit.path, hit.line, hit.preview);
+            }
+            Some(prev) if prev.line != hit.line => {
+                changed = true;
+                println!(
+                    "~ {}:{} -> {}:{}  {}",
+                    prev.path, prev.line, hit.path, hit.line, hit.preview
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    for hit in previous {
+        if !current_by_id.contains_key(hit.id.as_str()) {
+            changed = true;
+            println!("- {}:{}  {}", hit.path, hit.line, hit.preview);
+        }
+    }
+    if !changed {
+        println!("(no change)");
+    }
+}
+
+fn run_impact(args: ImpactArgs) -> Result<()> {
+    let store_path = args.store.clone();
+    record_operation(&store_path, "impact", || {
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let coordinator = IndexCoordinator::new(store.chunks().cloned().collect());
+
+        let response = coordinator.handle_impact(ImpactRequest {
+            id: args.id,
+            include_tests: args.include_tests,
+            limit: args.limit,
+        })?;
+
+        println!("{} referencing file(s)", response.referencing_files);
+        for (chunk_type, count) in &response.by_chunk_type {
+            println!("  {chunk_type}: {count}");
+        }
+        if response.top_hits.is_empty() {
+            println!("(no references found)");
+        }
+        for hit in &response.top_hits {
+            print_hit(hit);
+        }
+        Ok(())
+    })
+}
+
+fn run_graph(args: GraphArgs) -> Result<()> {
+    let store_path = args.store.clone();
+    record_operation(&store_path, "graph", || {
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let coordinator = IndexCoordinator::new(store.chunks().cloned().collect());
+
+        let response = coordinator.handle_call_graph(CallGraphRequest {
+            id: args.id,
+            max_depth: args.depth,
+        })?;
+        let rendered = render(&response, args.export.into());
+
+        match args.out {
+            Some(path) => std::fs::write(&path, rendered)
+                .with_context(|| format!("failed to write graph to {}", path.display()))?,
+            None => print!("{rendered}"),
+        }
+        Ok(())
+    })
+}
+
+fn run_export(args: ExportArgs) -> Result<()> {
+    let store_path = args.store.clone();
+    record_operation(&store_path, "export", || {
+        let store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+            .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+        let chunks: Vec<_> = store.chunks().cloned().collect();
+        let kinds: Vec<IndexExportKind> = args.include.into_iter().map(Into::into).collect();
+
+        match &args.out {
+            Some(path) => {
+                let file = File::create(path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                write_jsonl(&chunks, &kinds, &mut BufWriter::new(file))?;
+            }
+            None => write_jsonl(&chunks, &kinds, &mut std::io::stdout().lock())?,
+        }
+        Ok(())
+    })
+}
+
+fn run_doctor(args: DoctorArgs) -> Result<()> {
+    if args.stats {
+        return print_health_stats(&args.store);
+    }
+    if args.health {
+        return print_index_health(&args.store, args.embedding_dim);
+    }
+
+    let mut store = VectorStore::open(VectorStoreConfig::new(&args.store, args.embedding_dim))
+        .with_context(|| format!("failed to open vector store at {}", args.store.display()))?;
+
+    let mut stale = store.find_stale(Chunker::CURRENT_VERSION);
+    if !args.fix_pending {
+        if stale.is_empty() {
+            println!("(no stale files)");
+        }
+        for path in &stale {
+            println!("{path}");
+        }
+        return Ok(());
+    }
+
+    if let Some(limit) = args.limit {
+        stale.truncate(limit);
+    }
+    let root = args
+        .root
+        .context("--root is required with --fix-pending")?;
+    let index_dir = args
+        .index_dir
+        .context("--index-dir is required with --fix-pending")?;
+
+    let mut indexer = CodebaseIndexer::new(IndexerConfig::new(root.as_path(), index_dir.as_path()))
+        .with_context(|| format!("failed to open embedding cache at {}", index_dir.display()))?;
+    let summary = indexer.reindex_paths(&mut store, &stale)?;
+
+    println!("re-indexed {} file(s)", summary.files_indexed);
+    if summary.files_deleted > 0 {
+        println!("removed {} file(s) no longer on disk", summary.files_deleted);
+    }
+    for skipped in &summary.files_skipped {
+        println!("skipped {}: {}", skipped.path, skipped.reason);
+    }
+    Ok(())
+}
+
+/// Prints [`HealthResponse`] counts over `store`'s current snapshot.
+///
+/// This opens `store`, builds an [`IndexCoordinator`] from it, and answers
+/// [`HealthRequest`] right there in the same process - there's no daemon for
+/// a client to probe readiness from ahead of a search, so this is that
+/// probe and the search itself running back to back, not two separate
+/// round trips.
+fn print_index_health(store_path: &Path, embedding_dim: usize) -> Result<()> {
+    let store = VectorStore::open(VectorStoreConfig::new(store_path, embedding_dim))
+        .with_context(|| format!("failed to open vector store at {}", store_path.display()))?;
+    let chunks: Vec<_> = store.chunks().cloned().collect();
+    let response: HealthResponse = IndexCoordinator::new(chunks).handle_health(HealthRequest);
+
+    println!("indexed chunks: {}", response.indexed_chunks);
+    println!("indexed files: {}", response.indexed_files);
+    println!("indexed symbols: {}", response.indexed_symbols);
+    println!("reference edges: {}", response.reference_edges);
+    Ok(())
+}
+
+/// Prints the `.health.json` sidecar for `store` (see
+/// [`codex_navigator::health`]). Every hotspot printed here was written by
+/// some earlier invocation of this binary - this command has no daemon
+/// process of its own to be restarted, so there's no "this run's data" to
+/// distinguish from "pre-restart data"; all of it is pre-restart by
+/// construction, which this prints explicitly rather than leaving implicit.
+fn print_health_stats(store: &Path) -> Result<()> {
+    let stats = HealthStats::load(&HealthStats::path_for(store));
+    if stats.hotspots.is_empty() {
+        println!("(no stats recorded yet)");
+        return Ok(());
+    }
+
+    println!("all samples below predate this invocation (no daemon to carry state forward)");
+    for hotspot in &stats.hotspots {
+        let avg_micros = hotspot.total_micros / hotspot.calls.max(1);
+        println!(
+            "{}: {} call(s), {}us total, {}us avg",
+            hotspot.label, hotspot.calls, hotspot.total_micros, avg_micros
+        );
+    }
+    Ok(())
+}
+
+fn print_hit(hit: &NavHit) {
+    let fuzzy_marker = if hit.is_fuzzy_match { " (fuzzy)" } else { "" };
+    println!("{}:{}  {}{fuzzy_marker}", hit.path, hit.line, hit.preview);
+    for reference in hit.references.iter().flatten() {
+        println!("    referenced by {}:{}  {}", reference.path, reference.line, reference.preview);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_or_cancelled_returns_promptly_once_cancelled() {
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_secs(1),
+            wait_or_cancelled(&cancellation, Duration::from_secs(60)),
+        )
+        .await
+        .expect("wait_or_cancelled did not return within the timeout");
+
+        assert!(cancelled);
+    }
+
+    #[tokio::test]
+    async fn wait_or_cancelled_times_out_normally_when_not_cancelled() {
+        let cancellation = CancellationToken::new();
+
+        let cancelled = wait_or_cancelled(&cancellation, Duration::from_millis(10)).await;
+
+        assert!(!cancelled);
+    }
+}
@@ -0,0 +1,283 @@
+/// Coarse classification of a [`crate::Chunk`]'s primary content.
+///
+/// This is detected heuristically from text patterns (attributes,
+/// decorators, keyword position) by [`classify`], not a real AST parse -
+/// this crate has no parser dependency, matching its line-window chunking
+/// strategy (see [`crate::Chunker`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkType {
+    Function,
+    Method,
+    Class,
+    Test,
+    /// Content from a file whose language couldn't be detected, split by
+    /// `ChunkingStrategy::FixedWindow` rather than classified by content.
+    Other,
+}
+
+impl ChunkType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChunkType::Function => "function",
+            ChunkType::Method => "method",
+            ChunkType::Class => "class",
+            ChunkType::Test => "test",
+            ChunkType::Other => "other",
+        }
+    }
+
+    /// Relative importance when ranking chunks of otherwise equal search
+    /// score; higher sorts first. Tests rank below every other classified
+    /// type (only unclassified `Other` content ranks lower), so a caller that wants
+    /// to deprioritize (or deliberately boost) test code has a numeric
+    /// signal to key off of instead of string-matching `as_str`.
+    pub fn priority(self) -> u8 {
+        match self {
+            ChunkType::Class => 3,
+            ChunkType::Function | ChunkType::Method => 2,
+            ChunkType::Test => 1,
+            ChunkType::Other => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ChunkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Classifies a chunk's `content` by scanning for language-specific test
+/// markers and definition keywords. `language` is the same name
+/// [`crate::language::language_from_path`]/`Language::detect` produce (e.g.
+/// `"rust"`, `"python"`); any other language (or `None`) is unclassified.
+pub fn classify(language: Option<&str>, content: &str) -> Option<ChunkType> {
+    match language? {
+        "rust" => classify_rust(content),
+        "python" => classify_python(content),
+        "javascript" | "typescript" => classify_js(content),
+        _ => None,
+    }
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+fn classify_rust(content: &str) -> Option<ChunkType> {
+    if content
+        .lines()
+        .any(|line| is_rust_test_attribute(line.trim_start()))
+    {
+        return Some(ChunkType::Test);
+    }
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if is_rust_fn_decl(trimmed) {
+            return Some(if is_indented(line) {
+                ChunkType::Method
+            } else {
+                ChunkType::Function
+            });
+        }
+        if trimmed.starts_with("struct ")
+            || trimmed.starts_with("pub struct ")
+            || trimmed.starts_with("enum ")
+            || trimmed.starts_with("pub enum ")
+        {
+            return Some(ChunkType::Class);
+        }
+    }
+    None
+}
+
+/// True if `trimmed` looks like the start of a function/method/class
+/// declaration in any language this crate recognizes. Used by
+/// [`crate::doc_comments`] to avoid splitting a doc comment or docstring
+/// away from the declaration it documents, regardless of which of this
+/// crate's supported languages the chunk is in.
+pub(crate) fn is_declaration_line(trimmed: &str) -> bool {
+    is_rust_fn_decl(trimmed)
+        || trimmed.starts_with("struct ")
+        || trimmed.starts_with("pub struct ")
+        || trimmed.starts_with("enum ")
+        || trimmed.starts_with("pub enum ")
+        || trimmed.starts_with("def ")
+        || trimmed.starts_with("class ")
+        || trimmed.starts_with("function ")
+}
+
+/// Best-effort name for the symbol `declaration_line` declares - the
+/// identifier right after its `fn`/`struct`/`enum`/`def`/`class`/`function`
+/// keyword, up to the next character that isn't alphanumeric or `_`. `None`
+/// when `declaration_line` isn't one [`is_declaration_line`] recognizes, or
+/// `language` wasn't detected. Like [`classify`], this is a text heuristic,
+/// not a real parse - a keyword appearing elsewhere on the same line (in a
+/// string or comment, say) can be picked up instead of the declaring one.
+pub(crate) fn symbol_name(language: Option<&str>, declaration_line: &str) -> Option<String> {
+    language?;
+    let trimmed = declaration_line.trim_start();
+    if !is_declaration_line(trimmed) {
+        return None;
+    }
+    let keywords = ["fn ", "struct ", "enum ", "def ", "class ", "function "];
+    let rest = keywords
+        .iter()
+        .find_map(|keyword| trimmed.split_once(keyword).map(|(_, rest)| rest))?;
+    let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    let name = &rest[..end];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn is_rust_test_attribute(trimmed: &str) -> bool {
+    trimmed.starts_with("#[test]") || trimmed.starts_with("#[tokio::test]")
+}
+
+fn is_rust_fn_decl(trimmed: &str) -> bool {
+    [
+        "pub(crate) async fn ",
+        "pub async fn ",
+        "async fn ",
+        "pub(crate) fn ",
+        "pub fn ",
+        "fn ",
+    ]
+    .iter()
+    .any(|prefix| trimmed.starts_with(prefix))
+}
+
+fn classify_python(content: &str) -> Option<ChunkType> {
+    if content
+        .lines()
+        .any(|line| line.trim_start().starts_with("@pytest.mark."))
+    {
+        return Some(ChunkType::Test);
+    }
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("def ") {
+            if rest.starts_with("test_") {
+                return Some(ChunkType::Test);
+            }
+            return Some(if is_indented(line) {
+                ChunkType::Method
+            } else {
+                ChunkType::Function
+            });
+        }
+        if trimmed.starts_with("class ") {
+            return Some(ChunkType::Class);
+        }
+    }
+    None
+}
+
+fn classify_js(content: &str) -> Option<ChunkType> {
+    if content.lines().any(|line| {
+        let trimmed = line.trim_start();
+        trimmed.starts_with("describe(") || trimmed.starts_with("it(") || trimmed.starts_with("test(")
+    }) {
+        return Some(ChunkType::Test);
+    }
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("class ") {
+            return Some(ChunkType::Class);
+        }
+        if trimmed.starts_with("function ") || trimmed.contains(" function ") {
+            return Some(if is_indented(line) {
+                ChunkType::Method
+            } else {
+                ChunkType::Function
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_a_rust_test_function_via_its_attribute() {
+        let content = "#[test]\nfn adds_two_numbers() {\n    assert_eq!(1 + 1, 2);\n}";
+        assert_eq!(classify(Some("rust"), content), Some(ChunkType::Test));
+    }
+
+    #[test]
+    fn detects_a_rust_tokio_test_function() {
+        let content = "#[tokio::test]\nasync fn fetches_data() {}";
+        assert_eq!(classify(Some("rust"), content), Some(ChunkType::Test));
+    }
+
+    #[test]
+    fn detects_a_plain_rust_function_and_method() {
+        assert_eq!(
+            classify(Some("rust"), "pub fn handle_request() {}"),
+            Some(ChunkType::Function)
+        );
+        assert_eq!(
+            classify(Some("rust"), "impl Foo {\n    fn handle_request(&self) {}\n}"),
+            Some(ChunkType::Method)
+        );
+    }
+
+    #[test]
+    fn detects_a_python_test_function_by_name_and_by_pytest_marker() {
+        assert_eq!(
+            classify(Some("python"), "def test_parses_input():\n    pass"),
+            Some(ChunkType::Test)
+        );
+        assert_eq!(
+            classify(
+                Some("python"),
+                "@pytest.mark.parametrize(\"x\", [1, 2])\ndef checks_input(x):\n    pass"
+            ),
+            Some(ChunkType::Test)
+        );
+    }
+
+    #[test]
+    fn detects_a_python_class_and_function() {
+        assert_eq!(
+            classify(Some("python"), "class Parser:\n    pass"),
+            Some(ChunkType::Class)
+        );
+        assert_eq!(
+            classify(Some("python"), "def parse(text):\n    pass"),
+            Some(ChunkType::Function)
+        );
+    }
+
+    #[test]
+    fn detects_a_js_test_via_describe_and_it() {
+        let content = "describe('parser', () => {\n  it('parses input', () => {});\n});";
+        assert_eq!(classify(Some("javascript"), content), Some(ChunkType::Test));
+    }
+
+    #[test]
+    fn symbol_name_extracts_the_identifier_after_the_declaration_keyword() {
+        assert_eq!(
+            symbol_name(Some("rust"), "pub async fn handle_search(query: &str) {"),
+            Some("handle_search".to_string())
+        );
+        assert_eq!(
+            symbol_name(Some("python"), "def parse_error_handling(text):"),
+            Some("parse_error_handling".to_string())
+        );
+    }
+
+    #[test]
+    fn symbol_name_is_none_for_a_non_declaration_line_or_unrecognized_language() {
+        assert_eq!(symbol_name(Some("rust"), "    a + b"), None);
+        assert_eq!(symbol_name(None, "fn main() {}"), None);
+    }
+
+    #[test]
+    fn unclassified_for_an_unsupported_language_or_unrecognized_content() {
+        assert_eq!(classify(Some("go"), "func main() {}"), None);
+        assert_eq!(classify(Some("rust"), "const X: u32 = 1;"), None);
+        assert_eq!(classify(None, "fn main() {}"), None);
+    }
+}
@@ -0,0 +1,11 @@
+use crate::metadata::ChunkMetadata;
+
+/// A contiguous span of a source file produced by [`crate::Chunker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub path: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub metadata: ChunkMetadata,
+}
@@ -0,0 +1,835 @@
+use codex_utils_tokenizer::Tokenizer;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::ast_analyzer;
+use crate::ast_analyzer::ChunkType;
+use crate::chunk::Chunk;
+use crate::config::ChunkerConfig;
+use crate::config::ChunkingStrategy;
+use crate::config::OverlapStrategy;
+use crate::doc_comments;
+use crate::language::language_from_path;
+use crate::language::Language;
+use crate::metadata::ChunkMetadata;
+use crate::report::ChunkReport;
+
+/// Rough characters-per-token estimate used when [`Tokenizer::new`] fails to
+/// initialize.
+const FALLBACK_CHARS_PER_TOKEN: usize = 4;
+
+/// Splits source files into [`Chunk`]s for embedding and indexing.
+///
+/// The current strategy is a simple fixed-size line window: language-aware
+/// splitting on function/class boundaries is tracked separately (see the
+/// `ChunkingStrategy` work in later revisions of this crate).
+pub struct Chunker {
+    config: ChunkerConfig,
+    tokenizer: Option<Tokenizer>,
+}
+
+impl Chunker {
+    /// Bumped whenever the chunking algorithm changes in a way that makes
+    /// previously produced chunks worth recomputing (e.g. a new splitting
+    /// strategy). Stored on each chunk's metadata so stale chunks produced
+    /// by an older version can be found via `VectorStore::find_stale`.
+    pub const CURRENT_VERSION: u32 = 1;
+
+    pub fn new(config: ChunkerConfig) -> Self {
+        Self {
+            config,
+            tokenizer: Tokenizer::new().ok(),
+        }
+    }
+
+    /// `true` if this chunker is falling back to the `content.len() / 4`
+    /// token estimate because [`Tokenizer::new`] failed to initialize.
+    pub fn used_token_estimate_fallback(&self) -> bool {
+        self.tokenizer.is_none()
+    }
+
+    fn estimate_tokens(&self, content: &str) -> usize {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count_tokens(content),
+            None => content.len() / FALLBACK_CHARS_PER_TOKEN,
+        }
+    }
+
+    /// Chunks `code` per `ChunkerConfig::strategy_for` the detected language:
+    /// fixed-size, non-overlapping line windows when a language was
+    /// detected, or overlapping `ChunkingStrategy::FixedWindow`s otherwise.
+    ///
+    /// `path` is used for language detection via its extension; when that's
+    /// inconclusive (or `path` is `None`) and
+    /// `self.config.detect_language_from_content` is enabled, falls back to
+    /// sniffing `code` for a shebang or modeline via [`Language::detect`].
+    pub fn chunk_str(&self, code: &str, path: Option<&str>) -> Vec<Chunk> {
+        self.chunk_str_with_report(code, path).0
+    }
+
+    /// Like [`Chunker::chunk_str`], but also returns a [`ChunkReport`]
+    /// describing how confidently `code` was split - see that type's doc
+    /// comment for what it can and can't tell you given this crate has no
+    /// parser dependency.
+    pub fn chunk_str_with_report(&self, code: &str, path: Option<&str>) -> (Vec<Chunk>, ChunkReport) {
+        let mut report = ChunkReport {
+            bytes_covered: code.len(),
+            ..ChunkReport::default()
+        };
+
+        let language = path.and_then(language_from_path).or_else(|| {
+            if self.config.detect_language_from_content {
+                Language::detect(code, path).map(|language| language.to_string())
+            } else {
+                None
+            }
+        });
+        let lines: Vec<&str> = code.lines().collect();
+        if lines.is_empty() {
+            return (Vec::new(), report);
+        }
+
+        if let ChunkingStrategy::FixedWindow { lines: window, overlap } =
+            self.config.strategy_for(language.as_deref())
+        {
+            let chunks = self.fixed_window_chunks(&lines, path, window, overlap);
+            report.used_fallback_strategy = true;
+            report.chunks_with_unrecognized_syntax = chunks.len();
+            return (chunks, report);
+        }
+
+        let window = language
+            .as_deref()
+            .and_then(|language| self.config.per_language_max_lines.get(language))
+            .copied()
+            .unwrap_or(self.config.max_chunk_lines)
+            .max(1);
+
+        let import_header = match self.config.overlap_strategy {
+            OverlapStrategy::ImportHeader => import_header(&lines, language.as_deref()),
+            OverlapStrategy::None | OverlapStrategy::Lines(_) | OverlapStrategy::Semantic => None,
+        };
+
+        let mut start_line = 1;
+        let mut previous_window_lines: Vec<&str> = Vec::new();
+        let mut chunks = Vec::new();
+        for window_lines in doc_comments::windows(&lines, window, language.as_deref()) {
+            let end_line = start_line + window_lines.len() - 1;
+            let content = window_lines.join("\n");
+            let doc_summary = doc_comments::extract_doc_summary(language.as_deref(), &window_lines);
+            let chunk_type = ast_analyzer::classify(language.as_deref(), &content)
+                .map(|chunk_type| chunk_type.to_string());
+            if chunk_type.is_none() {
+                report.chunks_with_unrecognized_syntax += 1;
+            }
+            let content_hash = content_hash(&content);
+
+            let (overlap_prefix, overlap_lines) = match self.config.overlap_strategy {
+                OverlapStrategy::None => (None, 0),
+                OverlapStrategy::ImportHeader => {
+                    let overlap_lines =
+                        import_header.as_ref().map_or(0, |header| header.lines().count());
+                    (import_header.clone(), overlap_lines)
+                }
+                OverlapStrategy::Lines(n) => {
+                    let n = n.min(previous_window_lines.len());
+                    if n == 0 {
+                        (None, 0)
+                    } else {
+                        let borrowed = &previous_window_lines[previous_window_lines.len() - n..];
+                        (Some(borrowed.join("\n")), n)
+                    }
+                }
+                OverlapStrategy::Semantic => {
+                    let preceding = preceding_doc_or_attribute_block(
+                        &lines,
+                        start_line - 1,
+                        language.as_deref(),
+                    );
+                    match preceding {
+                        Some(block) => (Some(block.join("\n")), block.len()),
+                        None => (None, 0),
+                    }
+                }
+            };
+            let content = match &overlap_prefix {
+                Some(prefix) => format!("{prefix}\n{content}"),
+                None => content,
+            };
+            let estimated_tokens = self.estimate_tokens(&content);
+            let chunk = Chunk {
+                path: path.map(str::to_string),
+                start_line,
+                end_line,
+                metadata: ChunkMetadata {
+                    language: language.clone(),
+                    chunk_type,
+                    symbol_name: None,
+                    // Hashed before any overlap prefix is prepended, so the
+                    // hash still identifies this chunk's own content rather
+                    // than changing whenever a neighboring chunk's borrowed
+                    // lines do.
+                    content_hash: Some(content_hash),
+                    chunker_version: Some(Self::CURRENT_VERSION),
+                    doc_summary,
+                    context_imports: import_header.clone(),
+                    estimated_tokens: Some(estimated_tokens),
+                    overlap_lines,
+                    part_index: None,
+                    part_count: None,
+                },
+                content,
+            };
+            start_line = end_line + 1;
+            previous_window_lines = window_lines;
+            match self.config.max_chunk_tokens {
+                Some(max_tokens) => chunks.extend(self.split_oversized_chunk(chunk, max_tokens)),
+                None => chunks.push(chunk),
+            }
+        }
+        (chunks, report)
+    }
+
+    /// Enforces `ChunkerConfig::max_chunk_tokens`: when `chunk` exceeds it
+    /// (e.g. one function whose body ran to 1,500 lines), splits its own
+    /// lines (i.e. excluding any existing `overlap_lines` prefix) at
+    /// blank-line boundaries - the closest this text-based chunker gets to
+    /// a statement/block boundary without a real parser - into `part_count`
+    /// pieces, starting a new piece at a blank line whenever adding it would
+    /// push the current piece past `max_tokens`. Lines between two blank
+    /// lines are never separated, so a piece can still exceed `max_tokens`.
+    /// Every part after the first is
+    /// prefixed with the chunk's own first line (presumed to be the
+    /// function/method signature) as a header, so a reader opening any one
+    /// part alone still sees what it's part of; that header's line is
+    /// counted in the part's `overlap_lines` like any other duplicated
+    /// prefix. `start_line`/`end_line` on each part describe only that
+    /// part's own lines, and joining every part's own lines (dropping each
+    /// part's header and the first part's inherited prefix) reconstructs
+    /// the original chunk's own lines exactly.
+    ///
+    /// Returns `chunk` unchanged (as a single-element `Vec`) if it's within
+    /// budget, or if there's no blank line to split on.
+    fn split_oversized_chunk(&self, chunk: Chunk, max_tokens: usize) -> Vec<Chunk> {
+        if chunk.metadata.estimated_tokens.unwrap_or(0) <= max_tokens {
+            return vec![chunk];
+        }
+
+        let all_lines: Vec<&str> = chunk.content.lines().collect();
+        let prefix_len = chunk.metadata.overlap_lines.min(all_lines.len());
+        let prefix = &all_lines[..prefix_len];
+        let own_lines = &all_lines[prefix_len..];
+        let Some(&header) = own_lines.first() else {
+            return vec![chunk];
+        };
+
+        let mut parts: Vec<Vec<&str>> = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        let mut current_tokens = 0;
+        for line in own_lines {
+            let line_tokens = self.estimate_tokens(line);
+            let at_boundary = !current.is_empty() && line.trim().is_empty();
+            if at_boundary && current_tokens + line_tokens > max_tokens {
+                parts.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current.push(line);
+            current_tokens += line_tokens;
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+        if parts.len() <= 1 {
+            return vec![chunk];
+        }
+
+        let symbol_name = ast_analyzer::symbol_name(chunk.metadata.language.as_deref(), header);
+        let part_count = parts.len();
+        let mut start_line = chunk.start_line;
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(part_index, part_lines)| {
+                let end_line = start_line + part_lines.len() - 1;
+                let body = part_lines.join("\n");
+                let (content, overlap_lines) = if part_index == 0 {
+                    let mut full = prefix.to_vec();
+                    full.extend(part_lines);
+                    (full.join("\n"), prefix_len)
+                } else {
+                    (format!("{header}\n{body}"), 1)
+                };
+                let estimated_tokens = self.estimate_tokens(&content);
+                let part = Chunk {
+                    path: chunk.path.clone(),
+                    start_line,
+                    end_line,
+                    metadata: ChunkMetadata {
+                        symbol_name: symbol_name.clone(),
+                        part_index: Some(part_index),
+                        part_count: Some(part_count),
+                        // Recomputed per part, since each part's content
+                        // differs from the original unsplit chunk's.
+                        content_hash: Some(content_hash(&content)),
+                        estimated_tokens: Some(estimated_tokens),
+                        overlap_lines,
+                        ..chunk.metadata.clone()
+                    },
+                    content,
+                };
+                start_line = end_line + 1;
+                part
+            })
+            .collect()
+    }
+
+    /// Re-chunks only the part of `new_source` affected by editing
+    /// `old_source` into it, for watch-driven re-indexing that doesn't want
+    /// to pay for a full re-chunk when one function changed.
+    ///
+    /// This crate has no parser dependency (see [`ChunkType`]'s doc
+    /// comment), so there's no tree-sitter `Tree` to drive incrementally
+    /// with `Tree::edit` - this still fully re-chunks both `old_source` and
+    /// `new_source` internally. What it saves the caller is everything
+    /// downstream of that: only chunks whose content actually differs from
+    /// the old chunking are returned, rather than every chunk in the file.
+    /// That covers both chunks whose lines overlap
+    /// `changed_byte_range` and chunks that end up "structurally affected
+    /// neighbors" - e.g. a doc comment that `crate::doc_comments::windows`
+    /// now pulls into a different window than it did before the edit - since
+    /// those get a different `content_hash` too even though none of their
+    /// own lines were edited.
+    ///
+    /// Contract: `changed_byte_range` must be a valid byte range into
+    /// `new_source` describing the edited region; this crate doesn't
+    /// verify that, since checking would require the diff it's meant to
+    /// avoid computing. An incorrect range can cause an edited chunk at
+    /// the same position as an unrelated unedited one to be skipped.
+    pub fn rechunk_range(
+        &self,
+        old_source: &str,
+        new_source: &str,
+        path: Option<&str>,
+        changed_byte_range: std::ops::Range<usize>,
+    ) -> Vec<Chunk> {
+        let old_chunks = self.chunk_str(old_source, path);
+        let new_chunks = self.chunk_str(new_source, path);
+
+        let old_hashes: std::collections::HashSet<&str> = old_chunks
+            .iter()
+            .filter_map(|chunk| chunk.metadata.content_hash.as_deref())
+            .collect();
+
+        let start_line = line_of_byte(new_source, changed_byte_range.start);
+        let end_line = line_of_byte(new_source, changed_byte_range.end.min(new_source.len()));
+
+        new_chunks
+            .into_iter()
+            .filter(|chunk| {
+                let intersects_edit = chunk.start_line <= end_line && chunk.end_line >= start_line;
+                let structurally_changed = chunk
+                    .metadata
+                    .content_hash
+                    .as_deref()
+                    .is_none_or(|hash| !old_hashes.contains(hash));
+                intersects_edit || structurally_changed
+            })
+            .collect()
+    }
+
+    /// Splits `lines` per `ChunkingStrategy::FixedWindow`: fixed-size windows
+    /// that overlap by `overlap` lines, tagged `ChunkType::Other` since
+    /// there's no detected language to classify them by.
+    fn fixed_window_chunks(
+        &self,
+        lines: &[&str],
+        path: Option<&str>,
+        window: usize,
+        overlap: usize,
+    ) -> Vec<Chunk> {
+        let window = window.max(1);
+        let overlap = overlap.min(window.saturating_sub(1));
+        let step = window - overlap;
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + window).min(lines.len());
+            let content = lines[start..end].join("\n");
+            chunks.push(Chunk {
+                path: path.map(str::to_string),
+                start_line: start + 1,
+                end_line: end,
+                metadata: ChunkMetadata {
+                    language: None,
+                    chunk_type: Some(ChunkType::Other.to_string()),
+                    symbol_name: None,
+                    content_hash: Some(content_hash(&content)),
+                    chunker_version: Some(Self::CURRENT_VERSION),
+                    doc_summary: None,
+                    context_imports: None,
+                    estimated_tokens: Some(self.estimate_tokens(&content)),
+                    overlap_lines: 0,
+                    part_index: None,
+                    part_count: None,
+                },
+                content,
+            });
+            if end == lines.len() {
+                break;
+            }
+            start += step;
+        }
+        chunks
+    }
+}
+
+/// Collects the contiguous run of import/use/include statements (and blank
+/// lines between them) at the very top of `lines`, for
+/// `OverlapStrategy::ImportHeader`. Returns `None` if `language` isn't
+/// recognized or the file doesn't open with any such lines.
+fn import_header(lines: &[&str], language: Option<&str>) -> Option<String> {
+    let is_import_line: fn(&str) -> bool = match language? {
+        "rust" => |line| line.trim_start().starts_with("use "),
+        "python" => |line| {
+            let trimmed = line.trim_start();
+            trimmed.starts_with("import ") || trimmed.starts_with("from ")
+        }
+        "javascript" | "typescript" => |line| {
+            let trimmed = line.trim_start();
+            trimmed.starts_with("import ")
+                || (trimmed.starts_with("const ") && trimmed.contains("require("))
+        }
+        "go" => |line| line.trim_start().starts_with("import"),
+        "c" | "cpp" => |line| line.trim_start().starts_with("#include"),
+        _ => return None,
+    };
+
+    let mut header_lines = Vec::new();
+    for line in lines.iter().copied() {
+        if is_import_line(line) || (!header_lines.is_empty() && line.trim().is_empty()) {
+            header_lines.push(line);
+        } else {
+            break;
+        }
+    }
+    while header_lines.last().is_some_and(|line| line.trim().is_empty()) {
+        header_lines.pop();
+    }
+    if header_lines.is_empty() {
+        None
+    } else {
+        Some(header_lines.join("\n"))
+    }
+}
+
+/// For `OverlapStrategy::Semantic`: the contiguous run of doc-comment or
+/// attribute lines immediately preceding `lines[start_idx]`, if any. Returns
+/// `None` when `language` isn't recognized, `start_idx` is the first line of
+/// the file, or the line right above `start_idx` isn't part of such a run.
+fn preceding_doc_or_attribute_block<'a>(
+    lines: &[&'a str],
+    start_idx: usize,
+    language: Option<&str>,
+) -> Option<Vec<&'a str>> {
+    let is_block_line: fn(&str) -> bool = match language? {
+        "rust" => |line| {
+            let trimmed = line.trim_start();
+            trimmed.starts_with("///") || trimmed.starts_with("//!") || trimmed.starts_with("#[")
+        }
+        "javascript" | "typescript" => |line| {
+            let trimmed = line.trim_start();
+            trimmed.starts_with("///") || trimmed.starts_with("//!") || trimmed.starts_with('@')
+        }
+        "python" => |line| line.trim_start().starts_with('@'),
+        _ => return None,
+    };
+    if start_idx == 0 || !is_block_line(lines[start_idx - 1]) {
+        return None;
+    }
+    let mut begin = start_idx;
+    while begin > 0 && is_block_line(lines[begin - 1]) {
+        begin -= 1;
+    }
+    Some(lines[begin..start_idx].to_vec())
+}
+
+fn content_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 1-based line number containing byte offset `byte` of `source`, matching
+/// the `start_line`/`end_line` convention [`Chunk`] uses.
+fn line_of_byte(source: &str, byte: usize) -> usize {
+    source[..byte.min(source.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn chunk_str_splits_into_fixed_windows_and_detects_language() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 2,
+            ..ChunkerConfig::default()
+        });
+        let code = "fn a() {}\nfn b() {}\nfn c() {}";
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].start_line, 1);
+        assert_eq!(chunks[0].end_line, 2);
+        assert_eq!(chunks[1].start_line, 3);
+        assert_eq!(chunks[1].end_line, 3);
+        assert_eq!(chunks[0].metadata.language, Some("rust".to_string()));
+    }
+
+    #[test]
+    fn chunk_str_populates_content_hash_and_chunker_version() {
+        let chunker = Chunker::new(ChunkerConfig::default());
+        let chunks = chunker.chunk_str("fn a() {}", Some("lib.rs"));
+
+        assert_eq!(chunks[0].metadata.chunker_version, Some(Chunker::CURRENT_VERSION));
+        let expected_hash = content_hash(&chunks[0].content);
+        assert_eq!(chunks[0].metadata.content_hash, Some(expected_hash));
+    }
+
+    #[test]
+    fn chunk_str_detects_language_from_shebang_when_path_is_inconclusive() {
+        let chunker = Chunker::new(ChunkerConfig::default());
+        let code = "#!/usr/bin/env python3\nprint('hi')";
+
+        let chunks = chunker.chunk_str(code, None);
+        assert_eq!(chunks[0].metadata.language, Some("python".to_string()));
+    }
+
+    #[test]
+    fn per_language_max_lines_overrides_the_global_budget_for_that_language() {
+        let code = "line one\nline two\nline three\nline four";
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 4,
+            per_language_max_lines: HashMap::from([("python".to_string(), 2)]),
+            ..ChunkerConfig::default()
+        });
+
+        let rust_chunks = chunker.chunk_str(code, Some("lib.rs"));
+        let python_chunks = chunker.chunk_str(code, Some("lib.py"));
+
+        assert_eq!(rust_chunks.len(), 1);
+        assert_eq!(python_chunks.len(), 2);
+        assert_eq!(python_chunks[0].end_line, 2);
+    }
+
+    #[test]
+    fn chunk_str_tags_a_rust_test_function_with_chunk_type_test() {
+        let chunker = Chunker::new(ChunkerConfig::default());
+        let code = "#[test]\nfn adds_two_numbers() {\n    assert_eq!(1 + 1, 2);\n}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+        assert_eq!(chunks[0].metadata.chunk_type, Some("test".to_string()));
+    }
+
+    #[test]
+    fn a_rust_doc_comment_stays_in_the_same_chunk_as_the_function_it_documents() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 2,
+            ..ChunkerConfig::default()
+        });
+        let code = "// filler\n/// Adds two numbers.\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        let doc_chunk = chunks
+            .iter()
+            .find(|chunk| chunk.content.contains("fn add"))
+            .expect("a chunk containing the function declaration");
+        assert!(
+            doc_chunk.content.contains("/// Adds two numbers."),
+            "doc comment should not be split into its own chunk"
+        );
+        assert_eq!(
+            doc_chunk.metadata.doc_summary,
+            Some("Adds two numbers.".to_string())
+        );
+    }
+
+    #[test]
+    fn a_python_docstring_stays_in_the_same_chunk_as_the_function_it_documents() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 2,
+            ..ChunkerConfig::default()
+        });
+        let code = "x = 1\ndef parse(text):\n    \"\"\"Parses input.\"\"\"\n    return text";
+
+        let chunks = chunker.chunk_str(code, Some("lib.py"));
+
+        let doc_chunk = chunks
+            .iter()
+            .find(|chunk| chunk.content.contains("def parse"))
+            .expect("a chunk containing the function definition");
+        assert!(
+            doc_chunk.content.contains("\"\"\"Parses input.\"\"\""),
+            "docstring should not be split into its own chunk"
+        );
+        assert_eq!(
+            doc_chunk.metadata.doc_summary,
+            Some("Parses input.".to_string())
+        );
+    }
+
+    #[test]
+    fn chunk_str_falls_back_to_overlapping_fixed_windows_for_an_undetected_language() {
+        let chunker = Chunker::new(ChunkerConfig {
+            fallback_chunk_lines: 4,
+            fallback_chunk_overlap: 1,
+            ..ChunkerConfig::default()
+        });
+        let code = (1..=10)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let chunks = chunker.chunk_str(&code, Some("notes.txt"));
+
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.metadata.chunk_type == Some(ChunkType::Other.to_string())));
+        assert!(chunks.iter().all(|chunk| chunk.metadata.language.is_none()));
+
+        let windows: Vec<(usize, usize)> =
+            chunks.iter().map(|chunk| (chunk.start_line, chunk.end_line)).collect();
+        assert_eq!(windows, vec![(1, 4), (4, 7), (7, 10)]);
+
+        // Consecutive windows overlap by exactly `fallback_chunk_overlap` lines.
+        assert_eq!(chunks[0].end_line, chunks[1].start_line);
+        assert_eq!(chunks[1].end_line, chunks[2].start_line);
+    }
+
+    #[test]
+    fn chunk_str_skips_content_detection_when_disabled() {
+        let chunker = Chunker::new(ChunkerConfig {
+            detect_language_from_content: false,
+            ..ChunkerConfig::default()
+        });
+        let code = "#!/usr/bin/env python3\nprint('hi')";
+
+        let chunks = chunker.chunk_str(code, None);
+        assert_eq!(chunks[0].metadata.language, None);
+    }
+
+    #[test]
+    fn import_header_strategy_prepends_imports_without_shifting_line_numbers() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 2,
+            overlap_strategy: OverlapStrategy::ImportHeader,
+            ..ChunkerConfig::default()
+        });
+        let code = "use std::fs;\nuse std::io;\n\nfn a() {}\nfn b() {}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        let last_chunk = chunks.last().unwrap();
+        assert!(last_chunk.content.starts_with("use std::fs;\nuse std::io;\n"));
+        assert!(last_chunk.content.contains("fn b() {}"));
+        assert_eq!(
+            last_chunk.metadata.context_imports,
+            Some("use std::fs;\nuse std::io;".to_string())
+        );
+        // The header is prepended to content, but line numbers still refer
+        // to the chunk's own lines in the original file.
+        assert_eq!(last_chunk.start_line, 5);
+        assert_eq!(last_chunk.end_line, 5);
+    }
+
+    #[test]
+    fn lines_overlap_strategy_prepends_the_previous_chunks_tail() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 2,
+            overlap_strategy: OverlapStrategy::Lines(1),
+            ..ChunkerConfig::default()
+        });
+        let code = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert_eq!(chunks[0].metadata.overlap_lines, 0, "no previous chunk to borrow from");
+        assert_eq!(chunks[1].content, "fn b() {}\nfn c() {}\nfn d() {}");
+        assert_eq!(chunks[1].metadata.overlap_lines, 1);
+        // The borrowed line doesn't shift start_line/end_line.
+        assert_eq!(chunks[1].start_line, 3);
+        assert_eq!(chunks[1].end_line, 4);
+    }
+
+    #[test]
+    fn lines_overlap_strategy_caps_at_the_previous_chunks_length() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 1,
+            overlap_strategy: OverlapStrategy::Lines(5),
+            ..ChunkerConfig::default()
+        });
+        let code = "fn a() {}\nfn b() {}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert_eq!(chunks[1].content, "fn a() {}\nfn b() {}");
+        assert_eq!(chunks[1].metadata.overlap_lines, 1);
+    }
+
+    #[test]
+    fn semantic_overlap_strategy_prepends_the_nearest_preceding_attribute_block() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 1,
+            overlap_strategy: OverlapStrategy::Semantic,
+            ..ChunkerConfig::default()
+        });
+        let code = "#[test]\nfn adds() {}\nfn other() {}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert_eq!(chunks[1].content, "#[test]\nfn adds() {}");
+        assert_eq!(chunks[1].metadata.overlap_lines, 1);
+        // The borrowed attribute line doesn't shift start_line/end_line.
+        assert_eq!(chunks[1].start_line, 2);
+        assert_eq!(chunks[1].end_line, 2);
+
+        assert_eq!(chunks[2].metadata.overlap_lines, 0, "nothing precedes this chunk");
+    }
+
+    #[test]
+    fn a_chunk_within_the_token_budget_is_not_split() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_tokens: Some(10_000),
+            ..ChunkerConfig::default()
+        });
+        let code = "fn small() {}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].metadata.part_index, None);
+        assert_eq!(chunks[0].metadata.part_count, None);
+    }
+
+    #[test]
+    fn an_oversized_chunk_is_split_at_blank_lines_with_a_repeated_header() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 100,
+            max_chunk_tokens: Some(5),
+            ..ChunkerConfig::default()
+        });
+        let code = "fn big_function() {\n    let a = 1;\n\n    let b = 2;\n\n    let c = 3;\n}";
+
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        assert!(chunks.len() > 1, "expected the oversized chunk to be split");
+        let part_count = chunks[0].metadata.part_count.unwrap();
+        assert_eq!(chunks.len(), part_count);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.metadata.part_index, Some(i));
+            assert_eq!(chunk.metadata.part_count, Some(part_count));
+            assert_eq!(chunk.metadata.symbol_name, Some("big_function".to_string()));
+        }
+        for chunk in &chunks[1..] {
+            assert!(chunk.content.starts_with("fn big_function() {"));
+            assert_eq!(chunk.metadata.overlap_lines, 1);
+        }
+
+        // Re-joining each part's own lines (dropping every part-after-the-
+        // first's repeated header) reconstructs the original body exactly.
+        let mut reconstructed = chunks[0].content.clone();
+        for chunk in &chunks[1..] {
+            let body = chunk.content.splitn(2, '\n').nth(1).unwrap_or("");
+            reconstructed.push('\n');
+            reconstructed.push_str(body);
+        }
+        assert_eq!(reconstructed, code);
+    }
+
+    #[test]
+    fn estimated_tokens_uses_the_tokenizer_when_available_rather_than_the_heuristic() {
+        let chunker = Chunker::new(ChunkerConfig::default());
+        assert!(
+            !chunker.used_token_estimate_fallback(),
+            "the tokenizer is expected to initialize successfully in tests"
+        );
+
+        let code = "fn parse_error(code: u32) -> bool { code != 0 }";
+        let chunks = chunker.chunk_str(code, Some("lib.rs"));
+
+        let tokenizer_count = Tokenizer::new().unwrap().count_tokens(&chunks[0].content);
+        let heuristic_count = chunks[0].content.len() / FALLBACK_CHARS_PER_TOKEN;
+
+        assert_eq!(chunks[0].metadata.estimated_tokens, Some(tokenizer_count));
+        assert_ne!(tokenizer_count, heuristic_count);
+    }
+
+    #[test]
+    fn chunk_str_with_report_flags_fallback_strategy_and_covers_all_bytes() {
+        let chunker = Chunker::new(ChunkerConfig {
+            fallback_chunk_lines: 2,
+            fallback_chunk_overlap: 0,
+            detect_language_from_content: false,
+            ..ChunkerConfig::default()
+        });
+        let code = "some text\nmore text\neven more";
+
+        let (chunks, report) = chunker.chunk_str_with_report(code, None);
+
+        assert!(report.used_fallback_strategy);
+        assert_eq!(report.chunks_with_unrecognized_syntax, chunks.len());
+        assert_eq!(report.bytes_covered, code.len());
+        assert_eq!(report.bytes_skipped, 0);
+    }
+
+    #[test]
+    fn chunk_str_with_report_counts_chunks_the_classifier_could_not_recognize() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 1,
+            ..ChunkerConfig::default()
+        });
+        let code = "fn a() {}\nnot a function at all\nfn c() {}";
+
+        let (chunks, report) = chunker.chunk_str_with_report(code, Some("lib.rs"));
+
+        assert!(!report.used_fallback_strategy);
+        assert!(report.chunks_with_unrecognized_syntax >= 1);
+        assert!(report.chunks_with_unrecognized_syntax < chunks.len());
+    }
+
+    #[test]
+    fn rechunk_range_skips_chunks_unaffected_by_the_edit() {
+        let chunker = Chunker::new(ChunkerConfig {
+            max_chunk_lines: 1,
+            ..ChunkerConfig::default()
+        });
+        let old_source = "fn a() {}\nfn b() {}\nfn c() {}";
+        let new_source = "fn a() {}\nfn b_edited() {}\nfn c() {}";
+        let edit_start = new_source.find("fn b_edited").unwrap();
+        let edit_end = edit_start + "fn b_edited() {}".len();
+
+        let rechunked = chunker.rechunk_range(
+            old_source,
+            new_source,
+            Some("lib.rs"),
+            edit_start..edit_end,
+        );
+        let full_rechunk = chunker.chunk_str(new_source, Some("lib.rs"));
+
+        // Only the edited chunk comes back, and it matches what a full
+        // re-chunk of `new_source` would have produced for that line.
+        assert_eq!(rechunked.len(), 1);
+        assert_eq!(rechunked[0].content, "fn b_edited() {}");
+        assert_eq!(rechunked[0], full_rechunk[1]);
+
+        // The untouched neighbors are byte-identical to the full re-chunk,
+        // just not part of the returned set.
+        assert_ne!(full_rechunk[0].content, rechunked[0].content);
+        assert_eq!(full_rechunk[0].content, "fn a() {}");
+        assert_eq!(full_rechunk[2].content, "fn c() {}");
+    }
+}
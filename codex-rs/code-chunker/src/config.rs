@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+/// Configuration for [`crate::Chunker`].
+#[derive(Debug, Clone)]
+pub struct ChunkerConfig {
+    /// Maximum number of lines per chunk when no finer-grained strategy
+    /// applies and the detected language has no entry in
+    /// `per_language_max_lines`.
+    pub max_chunk_lines: usize,
+    /// Per-language override of `max_chunk_lines`, keyed by the same
+    /// language name `Language::to_string`/`Language::detect` produce (e.g.
+    /// `"python"`, `"rust"`). Dense languages like Python pack more meaning
+    /// per line than Rust, so a single global budget either oversizes their
+    /// chunks or undersizes everyone else's; a language missing from this
+    /// map falls back to `max_chunk_lines`.
+    pub per_language_max_lines: HashMap<String, usize>,
+    /// Whether to fall back to shebang/modeline sniffing (via
+    /// `Language::detect`) when the path's extension doesn't identify a
+    /// language. Disable for performance when every input is known to have
+    /// a conclusive extension.
+    pub detect_language_from_content: bool,
+    /// Window size used by `ChunkingStrategy::FixedWindow`, the strategy
+    /// `strategy_for` selects when a file's language can't be detected at
+    /// all (e.g. `.toml`, `.sql`, plain text).
+    pub fallback_chunk_lines: usize,
+    /// Number of lines consecutive `ChunkingStrategy::FixedWindow` windows
+    /// overlap by, so content near a window boundary isn't lost entirely to
+    /// whichever side of the boundary a chunk happens to fall on.
+    pub fallback_chunk_overlap: usize,
+    /// How much of a file's surrounding context gets attached to each of
+    /// its chunks, beyond the chunk's own lines.
+    pub overlap_strategy: OverlapStrategy,
+    /// Maximum `estimated_tokens` a single chunk is allowed to reach before
+    /// `Chunker::chunk_str` splits it into `ChunkMetadata::part_index`/
+    /// `part_count` parts - e.g. a 1,500-line function that would otherwise
+    /// be emitted as one chunk far past any embedding model's input limit.
+    /// `None` (the default) disables the pass, matching the prior behavior
+    /// of emitting a chunk exactly as big as `max_chunk_lines` produced.
+    pub max_chunk_tokens: Option<usize>,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        Self {
+            max_chunk_lines: 200,
+            per_language_max_lines: HashMap::new(),
+            detect_language_from_content: true,
+            fallback_chunk_lines: 200,
+            fallback_chunk_overlap: 20,
+            overlap_strategy: OverlapStrategy::None,
+            max_chunk_tokens: None,
+        }
+    }
+}
+
+impl ChunkerConfig {
+    /// The strategy `Chunker::chunk_str` should use for a file whose
+    /// language was detected as `language` (`None` if detection failed).
+    pub fn strategy_for(&self, language: Option<&str>) -> ChunkingStrategy {
+        match language {
+            Some(_) => ChunkingStrategy::LineWindow,
+            None => ChunkingStrategy::FixedWindow {
+                lines: self.fallback_chunk_lines,
+                overlap: self.fallback_chunk_overlap,
+            },
+        }
+    }
+}
+
+/// Strategy used by [`crate::Chunker`] to split a file into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkingStrategy {
+    /// Split on fixed-size, non-overlapping line windows sized from
+    /// `ChunkerConfig::max_chunk_lines`/`per_language_max_lines`, with
+    /// boundaries adjusted to keep doc comments with their declaration
+    /// (see `crate::doc_comments`). Used whenever the file's language was
+    /// detected.
+    LineWindow,
+    /// Split into fixed-size line windows that overlap by `overlap` lines,
+    /// so context near a window boundary survives in both neighboring
+    /// chunks. Selected automatically when the file's language can't be
+    /// detected at all; these chunks get `ChunkType::Other` rather than a
+    /// language-specific classification.
+    FixedWindow { lines: usize, overlap: usize },
+}
+
+/// How much of a file's surrounding context [`crate::Chunker::chunk_str`]
+/// attaches to each chunk, beyond the chunk's own lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlapStrategy {
+    /// Chunks carry only their own lines (the default).
+    #[default]
+    None,
+    /// Collect the file's top-of-file import block once and prepend it to
+    /// every chunk's content, so retrieval over a single chunk still sees
+    /// what it depends on. The header is stored separately in
+    /// `ChunkMetadata::context_imports` as well, and doesn't shift
+    /// `start_line`/`end_line`, which keep referencing the chunk's own
+    /// lines in the original file.
+    ImportHeader,
+    /// Prepend the last `n` lines of the previous chunk to this chunk's
+    /// content, so context that trails off at a window boundary (a
+    /// docstring that windowing still split away, a partial block) survives
+    /// in both neighboring chunks. Capped at however many lines the
+    /// previous chunk actually has (the first chunk in a file has none to
+    /// borrow). The duplicated line count is recorded in
+    /// [`crate::ChunkMetadata::overlap_lines`]; `start_line`/`end_line`
+    /// still describe only this chunk's own lines.
+    Lines(usize),
+    /// Prepend the nearest preceding doc comment/attribute block (a Rust
+    /// `///`/`//!` run or `#[...]` run, immediately above this chunk's first
+    /// line) to this chunk's content, rather than a fixed line count. `None`
+    /// when no such block sits directly above the chunk. Like
+    /// [`Self::Lines`], the prepended line count is recorded in
+    /// [`crate::ChunkMetadata::overlap_lines`] and doesn't shift
+    /// `start_line`/`end_line`.
+    Semantic,
+}
@@ -0,0 +1,186 @@
+use crate::ast_analyzer;
+
+/// Splits `lines` into windows of at most `max_len` lines, adjusting
+/// boundaries so a leading Rust/JS doc comment (`///`, `//!`) stays in the
+/// same chunk as the declaration it documents, and so a Python declaration
+/// line stays with the docstring immediately following it - rather than a
+/// window boundary splitting either pair across two chunks.
+pub(crate) fn windows<'a>(
+    lines: &[&'a str],
+    max_len: usize,
+    language: Option<&str>,
+) -> Vec<Vec<&'a str>> {
+    let max_len = max_len.max(1);
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let mut end = (start + max_len).min(lines.len());
+        match language {
+            Some("rust") | Some("javascript") | Some("typescript") => {
+                end = pull_doc_comment_into_next_window(lines, start, end);
+            }
+            Some("python") => {
+                end = pull_declaration_into_next_window(lines, start, end);
+            }
+            _ => {}
+        }
+        windows.push(lines[start..end].to_vec());
+        start = end;
+    }
+    windows
+}
+
+/// If the line right after the window would start a declaration, and the
+/// window's own trailing lines are a doc-comment run documenting it, moves
+/// that run out of the window so it starts the next one alongside the
+/// declaration instead.
+fn pull_doc_comment_into_next_window(lines: &[&str], start: usize, end: usize) -> usize {
+    if end >= lines.len() || !ast_analyzer::is_declaration_line(lines[end].trim_start()) {
+        return end;
+    }
+    let mut run_start = end;
+    while run_start > start && is_doc_comment_line(lines[run_start - 1].trim_start()) {
+        run_start -= 1;
+    }
+    if run_start > start { run_start } else { end }
+}
+
+/// If the window would end right after a declaration line whose immediately
+/// following line opens a docstring, shrinks the window to exclude the
+/// declaration too, so it starts the next window together with its
+/// docstring instead of being separated from it.
+fn pull_declaration_into_next_window(lines: &[&str], start: usize, end: usize) -> usize {
+    if end <= start || end >= lines.len() {
+        return end;
+    }
+    if python_docstring_quote(lines[end].trim_start()).is_none() {
+        return end;
+    }
+    if !ast_analyzer::is_declaration_line(lines[end - 1].trim_start()) {
+        return end;
+    }
+    end - 1
+}
+
+fn is_doc_comment_line(trimmed: &str) -> bool {
+    trimmed.starts_with("///") || trimmed.starts_with("//!")
+}
+
+fn python_docstring_quote(trimmed: &str) -> Option<&'static str> {
+    if trimmed.starts_with("\"\"\"") {
+        Some("\"\"\"")
+    } else if trimmed.starts_with("'''") {
+        Some("'''")
+    } else {
+        None
+    }
+}
+
+/// Extracts the doc comment or docstring documenting the declaration in
+/// `lines` (a chunk's content, already split into lines), if any.
+pub(crate) fn extract_doc_summary(language: Option<&str>, lines: &[&str]) -> Option<String> {
+    match language {
+        Some("rust") | Some("javascript") | Some("typescript") => extract_leading_comment(lines),
+        Some("python") => extract_docstring(lines),
+        _ => None,
+    }
+}
+
+fn extract_leading_comment(lines: &[&str]) -> Option<String> {
+    let mut end = 0;
+    while end < lines.len() && is_doc_comment_line(lines[end].trim_start()) {
+        end += 1;
+    }
+    if end == 0 || end >= lines.len() || !ast_analyzer::is_declaration_line(lines[end].trim_start()) {
+        return None;
+    }
+    let summary = lines[..end]
+        .iter()
+        .map(|line| strip_doc_comment_marker(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_string();
+    (!summary.is_empty()).then_some(summary)
+}
+
+fn strip_doc_comment_marker(trimmed: &str) -> &str {
+    trimmed.trim_start_matches("///").trim_start_matches("//!").trim()
+}
+
+fn extract_docstring(lines: &[&str]) -> Option<String> {
+    let decl_idx = lines
+        .iter()
+        .position(|line| ast_analyzer::is_declaration_line(line.trim_start()))?;
+    let doc_line = lines.get(decl_idx + 1)?.trim_start();
+    let quote = python_docstring_quote(doc_line)?;
+    let rest = &doc_line[quote.len()..];
+    if rest.trim_end().ends_with(quote) {
+        let text = rest.trim_end().trim_end_matches(quote).trim().to_string();
+        return (!text.is_empty()).then_some(text);
+    }
+
+    let mut text_lines = vec![rest.to_string()];
+    for line in &lines[decl_idx + 2..] {
+        if line.trim_end().ends_with(quote) {
+            let last = line.trim_end().trim_end_matches(quote);
+            if !last.trim().is_empty() {
+                text_lines.push(last.to_string());
+            }
+            let summary = text_lines.join(" ").trim().to_string();
+            return (!summary.is_empty()).then_some(summary);
+        }
+        text_lines.push(line.to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_rust_doc_comment_stays_with_the_function_it_documents() {
+        let lines: Vec<&str> = vec!["fn a() {}", "/// Parses input.", "fn parse() {}"];
+        let windows = windows(&lines, 2, Some("rust"));
+
+        assert_eq!(windows[0], vec!["fn a() {}"]);
+        assert_eq!(windows[1], vec!["/// Parses input.", "fn parse() {}"]);
+    }
+
+    #[test]
+    fn a_python_docstring_stays_with_the_function_it_documents() {
+        let lines: Vec<&str> = vec!["x = 1", "def parse():", "    \"\"\"Parses input.\"\"\""];
+        let windows = windows(&lines, 2, Some("python"));
+
+        assert_eq!(windows[0], vec!["x = 1"]);
+        assert_eq!(
+            windows[1],
+            vec!["def parse():", "    \"\"\"Parses input.\"\"\""]
+        );
+    }
+
+    #[test]
+    fn extract_doc_summary_strips_rust_doc_markers() {
+        let lines: Vec<&str> = vec!["/// Parses input.", "fn parse() {}"];
+        assert_eq!(
+            extract_doc_summary(Some("rust"), &lines),
+            Some("Parses input.".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_doc_summary_strips_python_docstring_quotes() {
+        let lines: Vec<&str> = vec!["def parse():", "    \"\"\"Parses input.\"\"\""];
+        assert_eq!(
+            extract_doc_summary(Some("python"), &lines),
+            Some("Parses input.".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_doc_summary_is_none_without_a_documented_declaration() {
+        let lines: Vec<&str> = vec!["let x = 1;"];
+        assert_eq!(extract_doc_summary(Some("rust"), &lines), None);
+    }
+}
@@ -0,0 +1,190 @@
+/// Best-effort language detection based on a file path's extension (or, for
+/// extensionless conventions like `Dockerfile`, its basename).
+pub fn language_from_path(path: &str) -> Option<String> {
+    let basename = path.rsplit('/').next().unwrap_or(path);
+    if basename.eq_ignore_ascii_case("dockerfile") {
+        return Some("dockerfile".to_string());
+    }
+    let ext = path.rsplit('.').next()?;
+    let language = match ext {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" => "cpp",
+        "rb" => "ruby",
+        "sh" | "bash" => "shell",
+        _ => return None,
+    };
+    Some(language.to_string())
+}
+
+/// A source language recognized by [`Language::detect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+    C,
+    Cpp,
+    Ruby,
+    Shell,
+    Dockerfile,
+}
+
+impl Language {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Ruby => "ruby",
+            Language::Shell => "shell",
+            Language::Dockerfile => "dockerfile",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Language> {
+        Some(match name {
+            "rust" => Language::Rust,
+            "python" => Language::Python,
+            "javascript" => Language::JavaScript,
+            "typescript" => Language::TypeScript,
+            "go" => Language::Go,
+            "java" => Language::Java,
+            "c" => Language::C,
+            "cpp" => Language::Cpp,
+            "ruby" => Language::Ruby,
+            "shell" => Language::Shell,
+            "dockerfile" => Language::Dockerfile,
+            _ => return None,
+        })
+    }
+
+    /// Detects the language of `content`, preferring `path` (extension or
+    /// basename) when it's available and conclusive, and otherwise falling
+    /// back to a shebang or modeline found in `content`.
+    ///
+    /// This is intentionally limited to a few common conventions rather than
+    /// attempting full content sniffing: a shebang interpreter, and emacs-
+    /// or vim-style modelines within the first few lines.
+    pub fn detect(content: &str, path: Option<&str>) -> Option<Language> {
+        path.and_then(language_from_path)
+            .and_then(|name| Language::parse(&name))
+            .or_else(|| Language::from_shebang(content))
+            .or_else(|| Language::from_modeline(content))
+    }
+
+    fn from_shebang(content: &str) -> Option<Language> {
+        let first_line = content.lines().next()?;
+        if !first_line.starts_with("#!") {
+            return None;
+        }
+        if first_line.contains("python") {
+            Some(Language::Python)
+        } else if first_line.contains("bash") || first_line.ends_with("sh") {
+            Some(Language::Shell)
+        } else if first_line.contains("node") {
+            Some(Language::JavaScript)
+        } else if first_line.contains("ruby") {
+            Some(Language::Ruby)
+        } else {
+            None
+        }
+    }
+
+    fn from_modeline(content: &str) -> Option<Language> {
+        const MODELINE_SCAN_LINES: usize = 5;
+        content
+            .lines()
+            .take(MODELINE_SCAN_LINES)
+            .find_map(Language::parse_modeline)
+    }
+
+    fn parse_modeline(line: &str) -> Option<Language> {
+        // Vim: `# vim: set ft=python:` / `# vim: ft=python`
+        if let Some(rest) = line.split("vim:").nth(1) {
+            for token in rest.split([' ', ':', ',']) {
+                if let Some(name) = token.strip_prefix("ft=").or_else(|| token.strip_prefix("filetype=")) {
+                    return Language::parse(name);
+                }
+            }
+        }
+        // Emacs: `-*- mode: python -*-`
+        if let Some(rest) = line.split("-*-").nth(1) {
+            for part in rest.split(';') {
+                if let Some(name) = part.split("mode:").nth(1) {
+                    return Language::parse(name.trim().to_lowercase().as_str());
+                }
+            }
+        }
+        None
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_prefers_path_extension() {
+        assert_eq!(
+            Language::detect("print('hi')", Some("script.py")),
+            Some(Language::Python)
+        );
+    }
+
+    #[test]
+    fn detect_recognizes_dockerfile_by_basename() {
+        assert_eq!(
+            Language::detect("FROM rust:1.93", Some("docker/Dockerfile")),
+            Some(Language::Dockerfile)
+        );
+    }
+
+    #[test]
+    fn detect_falls_back_to_shebang_for_extensionless_scripts() {
+        assert_eq!(
+            Language::detect("#!/usr/bin/env python3\nprint('hi')", None),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::detect("#!/bin/bash\necho hi", None),
+            Some(Language::Shell)
+        );
+    }
+
+    #[test]
+    fn detect_falls_back_to_modeline() {
+        assert_eq!(
+            Language::detect("# vim: set ft=ruby:\nputs 'hi'", None),
+            Some(Language::Ruby)
+        );
+        assert_eq!(
+            Language::detect("# -*- mode: python -*-\nprint('hi')", None),
+            Some(Language::Python)
+        );
+    }
+
+    #[test]
+    fn detect_returns_none_when_nothing_matches() {
+        assert_eq!(Language::detect("just some text", None), None);
+    }
+}
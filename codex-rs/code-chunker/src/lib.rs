@@ -0,0 +1,20 @@
+//! Splits source files into chunks suitable for embedding and indexing.
+
+mod ast_analyzer;
+mod chunk;
+mod chunker;
+mod config;
+mod doc_comments;
+mod language;
+mod metadata;
+mod report;
+
+pub use ast_analyzer::ChunkType;
+pub use chunk::Chunk;
+pub use chunker::Chunker;
+pub use config::ChunkerConfig;
+pub use config::ChunkingStrategy;
+pub use config::OverlapStrategy;
+pub use language::language_from_path;
+pub use metadata::ChunkMetadata;
+pub use report::ChunkReport;
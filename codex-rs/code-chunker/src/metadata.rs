@@ -0,0 +1,57 @@
+/// Metadata attached to a [`crate::Chunk`].
+///
+/// This is `codex-code-chunker`'s own copy of the information also mirrored
+/// (as flattened fields) on `codex_vector_store::CodeChunk`; the two crates
+/// intentionally don't share a type so that the vector store has no
+/// compile-time dependency on the chunking implementation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChunkMetadata {
+    pub language: Option<String>,
+    pub chunk_type: Option<String>,
+    pub symbol_name: Option<String>,
+    /// `sha256(content)`, hex-encoded. Lets a caller tell whether a stored
+    /// chunk is stale relative to the file's current contents without
+    /// re-chunking the whole file.
+    pub content_hash: Option<String>,
+    /// [`crate::Chunker::CURRENT_VERSION`] at the time this chunk was
+    /// produced. Lets a caller find chunks produced by an older chunking
+    /// algorithm via `VectorStore::find_stale`.
+    pub chunker_version: Option<u32>,
+    /// The doc comment (Rust `///`/`//!`) or docstring (Python
+    /// `"""..."""`/`'''...'''`) documenting this chunk's declaration, if the
+    /// chunker recognized one attached to it. `None` doesn't mean the
+    /// declaration is undocumented - only that no doc comment/docstring was
+    /// detected by these text heuristics.
+    pub doc_summary: Option<String>,
+    /// The file's top-of-file import block, prepended to this chunk's
+    /// content when `ChunkerConfig::overlap_strategy` is
+    /// `OverlapStrategy::ImportHeader`. `None` when that strategy isn't
+    /// active, or no import block was found.
+    pub context_imports: Option<String>,
+    /// Approximate token count of this chunk's content, from
+    /// `codex-utils-tokenizer` when available, or `content.len() / 4` if
+    /// the tokenizer failed to initialize (see
+    /// `crate::Chunker::used_token_estimate_fallback`).
+    pub estimated_tokens: Option<usize>,
+    /// How many lines at the start of `content` are duplicated from a
+    /// neighboring chunk rather than unique to this one - the import header
+    /// under `OverlapStrategy::ImportHeader`, the borrowed tail under
+    /// `OverlapStrategy::Lines`, the doc/attribute block under
+    /// `OverlapStrategy::Semantic`, or (for a part after the first) the
+    /// repeated declaration-line header `Chunker::chunk_str` prepends when
+    /// splitting a chunk that exceeded `ChunkerConfig::max_chunk_tokens`.
+    /// `0` when none of the above applied to this chunk. Lets an indexer
+    /// skip re-counting these lines' tokens when it already counted them in
+    /// the chunk they came from.
+    pub overlap_lines: usize,
+    /// This part's zero-based index among the parts
+    /// `ChunkerConfig::max_chunk_tokens` enforcement split its original
+    /// chunk into. `None` for a chunk that wasn't split (including every
+    /// chunk when `max_chunk_tokens` is `None`).
+    pub part_index: Option<usize>,
+    /// The total number of parts the original oversized chunk was split
+    /// into. Always `Some` exactly when `part_index` is, always
+    /// `> part_index`, and the same value across every part of the same
+    /// original chunk.
+    pub part_count: Option<usize>,
+}
@@ -0,0 +1,27 @@
+/// Signal about how confidently [`crate::Chunker::chunk_str_with_report`]
+/// was able to split a file, returned alongside the chunks themselves.
+///
+/// This crate has no parser dependency (see [`crate::ChunkType`]'s doc
+/// comment), so this can't report true parse errors the way a tree-sitter
+/// error node would. It reports the closest honest proxies available from
+/// the existing text heuristics: how many chunks the language-aware
+/// classifier couldn't recognize anything in, and whether chunking fell back
+/// to `ChunkingStrategy::FixedWindow` for lack of a detected language.
+/// `bytes_skipped` is always `0` - unlike a tree-sitter-based chunker, this
+/// one never drops input; every byte of `code` ends up in some chunk's
+/// content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkReport {
+    /// Total bytes of the chunked input.
+    pub bytes_covered: usize,
+    /// Always `0` for this chunker; kept so the shape matches what a future
+    /// parser-backed chunker would report.
+    pub bytes_skipped: usize,
+    /// Number of chunks for which [`crate::ChunkType`] classification found
+    /// nothing recognizable - either the language wasn't detected, or the
+    /// content didn't match any of `ast_analyzer`'s heuristics.
+    pub chunks_with_unrecognized_syntax: usize,
+    /// `true` if `ChunkingStrategy::FixedWindow` was used because no
+    /// language could be detected for this file.
+    pub used_fallback_strategy: bool,
+}
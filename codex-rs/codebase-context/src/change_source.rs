@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+/// A source of file-change notifications an indexer can hand to
+/// [`crate::ContextProvider::subscribe_to_changes`] for automatic cache
+/// invalidation, so a caller doesn't have to remember to call
+/// [`crate::ContextProvider::invalidate_paths`] itself after every reindex.
+///
+/// This is deliberately minimal rather than wrapping a specific file-watcher
+/// implementation: `codex-codebase-context` has no opinion on how changes
+/// are detected (polling, `notify`, an editor protocol), only on what to do
+/// once they're reported. Implement this trait over whatever change feed the
+/// indexer already has.
+#[async_trait]
+pub trait ChangeSource: Send {
+    /// Blocks until the next batch of changed paths is available, or
+    /// returns `None` once no more changes will ever arrive (e.g. the
+    /// underlying watcher was dropped).
+    async fn next_change(&mut self) -> Option<Vec<String>>;
+}
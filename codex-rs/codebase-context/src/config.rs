@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::ranking::RankingStrategy;
+
+/// Configuration for [`crate::ContextProvider`].
+#[derive(Debug, Clone)]
+pub struct ContextConfig {
+    /// How long a cached [`crate::ProvidedContext`] remains valid before
+    /// being evicted on its next lookup.
+    pub cache_ttl: Duration,
+    /// How many ranked candidates [`crate::ContextProvider::provide_context`]
+    /// pulls from retrieval before trimming them down to the token budget.
+    pub search_limit: usize,
+    /// How many of the most recent messages
+    /// [`crate::ContextProvider::provide_context_with_history`] folds into
+    /// the query before running retrieval, so a pronoun-heavy follow-up
+    /// ("and how is it cancelled?") still surfaces the entity a prior turn
+    /// named explicitly. Only the last `history_turns` messages (the
+    /// follow-up itself included) are considered; earlier turns are
+    /// dropped rather than allowed to accumulate forever.
+    pub history_turns: usize,
+    /// Overlap fraction above which [`crate::ContextProvider::provide_context`]
+    /// merges two same-file hits via
+    /// [`codex_codebase_retrieval::SearchResults::dedupe_overlapping`] before
+    /// spending token budget rendering both - e.g. a function indexed both
+    /// standalone and again inside its parent `impl` block. Results from
+    /// different files are never merged, regardless of this threshold.
+    pub dedupe_overlap_threshold: f32,
+    /// How [`crate::ContextProvider::provide_context`] orders ranked
+    /// candidates via [`crate::ChunkRanker`] before trimming them down to
+    /// the token budget. [`RankingStrategy::Relevance`] (the default) keeps
+    /// retrieval's own order; [`RankingStrategy::MaximalMarginalRelevance`]
+    /// spreads picks across files instead of letting the budget fill up
+    /// with near-identical hits from one.
+    pub ranking_strategy: RankingStrategy,
+    /// Whether [`crate::ContextProvider::provide_context`] appends a
+    /// "Related definitions" section listing the types and functions the
+    /// kept chunks reference but don't themselves define - see
+    /// [`crate::related_definitions::find_related_definitions`]. Defaults to
+    /// `false`, since it costs an extra retrieval call per chunk kept.
+    pub related_definitions_enabled: bool,
+    /// How many related definitions [`crate::ContextProvider::provide_context`]
+    /// looks up per kept chunk. Ignored unless `related_definitions_enabled`
+    /// is set. Definitions are still subject to `token_budget` on top of
+    /// this - the lowest-relevance ones are dropped first if there isn't
+    /// room for all of them.
+    pub related_definitions_limit: usize,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl: Duration::from_secs(60),
+            search_limit: 20,
+            history_turns: 3,
+            dedupe_overlap_threshold: 0.8,
+            ranking_strategy: RankingStrategy::Relevance,
+            related_definitions_enabled: false,
+            related_definitions_limit: 3,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Caches [`codex_codebase_retrieval::HybridRetrieval`] lookups so the same
+//! area of the codebase isn't re-searched on every conversation turn.
+
+mod change_source;
+mod config;
+mod provider;
+mod query_analyzer;
+mod ranking;
+mod related_definitions;
+mod stats;
+
+pub use change_source::ChangeSource;
+pub use config::ContextConfig;
+pub use provider::ContextProvider;
+pub use provider::ContextSearchMetadata;
+pub use provider::ProvidedContext;
+pub use query_analyzer::FileRef;
+pub use query_analyzer::QueryAnalyzer;
+pub use query_analyzer::SearchIntent;
+pub use ranking::ChunkRanker;
+pub use ranking::RankingStrategy;
+pub use related_definitions::RelatedDefinition;
+pub use stats::CacheStats;
@@ -0,0 +1,674 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use codex_codebase_retrieval::HybridRetrieval;
+use codex_utils_tokenizer::Tokenizer;
+use codex_vector_store::CodeChunk;
+
+use crate::change_source::ChangeSource;
+use crate::config::ContextConfig;
+use crate::query_analyzer::QueryAnalyzer;
+use crate::ranking::ChunkRanker;
+use crate::ranking::RankingStrategy;
+use crate::related_definitions::find_related_definitions;
+use crate::stats::CacheStats;
+
+/// A rough characters-per-token estimate used when [`Tokenizer::new`] fails
+/// to initialize (see [`ContextSearchMetadata::used_token_estimate_fallback`]).
+const FALLBACK_CHARS_PER_TOKEN: usize = 4;
+
+/// Per-call detail about how [`ProvidedContext`] was produced, beyond the
+/// content itself.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ContextSearchMetadata {
+    /// `true` if [`Tokenizer::new`] failed to initialize and token counts
+    /// were estimated as `chars / 4` instead of measured directly.
+    pub used_token_estimate_fallback: bool,
+    /// How many of the ranked candidates didn't fit in the requested token
+    /// budget and were left out of [`ProvidedContext`] entirely. A chunk is
+    /// only ever kept whole or dropped - see [`render_chunk`] for why a
+    /// partial chunk isn't a safe thing to inject into a prompt.
+    pub chunks_dropped: usize,
+    /// Which [`ContextConfig::ranking_strategy`] ordered the candidates
+    /// before budget trimming.
+    pub ranking_strategy: RankingStrategy,
+}
+
+/// Retrieved context ready to inject into a conversation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvidedContext {
+    pub chunks: Vec<CodeChunk>,
+    /// `chunks`, each rendered via [`render_chunk`] and joined with a blank
+    /// line, for direct inclusion in a prompt. `tokens_used` measures this
+    /// string, not the sum of each chunk's own `estimated_tokens` - the
+    /// header/fence [`render_chunk`] adds, and the blank-line separators
+    /// between chunks, cost tokens too.
+    pub text: String,
+    /// Compact one-line signatures of types and functions `chunks`
+    /// reference but don't themselves define, appended to `text` as a
+    /// "Related definitions" section - see
+    /// [`crate::related_definitions::find_related_definitions`]. Always
+    /// empty unless [`ContextConfig::related_definitions_enabled`] is set.
+    pub related_definitions: Vec<String>,
+    pub tokens_used: usize,
+    pub tokens_budget: usize,
+    pub metadata: ContextSearchMetadata,
+}
+
+struct CacheEntry {
+    context: ProvidedContext,
+    paths: HashSet<String>,
+    inserted_at: Instant,
+}
+
+/// Caches [`HybridRetrieval`] lookups by `(query, token budget)`, so repeated
+/// questions about the same area of the codebase don't re-run fuzzy/semantic
+/// search every turn.
+///
+/// Entries expire after `ContextConfig::cache_ttl`, and can be dropped early
+/// via [`Self::invalidate_paths`] when the indexer reports that one of their
+/// underlying files changed.
+pub struct ContextProvider {
+    config: ContextConfig,
+    retrieval: HybridRetrieval,
+    tokenizer: Option<Tokenizer>,
+    query_analyzer: QueryAnalyzer,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+    stats: Mutex<CacheStats>,
+}
+
+impl ContextProvider {
+    pub fn new(retrieval: HybridRetrieval) -> Self {
+        Self::with_config(retrieval, ContextConfig::default())
+    }
+
+    pub fn with_config(retrieval: HybridRetrieval, config: ContextConfig) -> Self {
+        Self {
+            config,
+            retrieval,
+            tokenizer: Tokenizer::new().ok(),
+            query_analyzer: QueryAnalyzer::new(),
+            cache: Mutex::new(HashMap::new()),
+            stats: Mutex::new(CacheStats::default()),
+        }
+    }
+
+    /// Returns cached context for `(message, token_budget)` if present and
+    /// unexpired, otherwise searches and keeps as many ranked chunks as fit.
+    ///
+    /// The message is first run through a [`QueryAnalyzer`] so that an
+    /// exactly-named symbol (e.g. `` `kill_process` `` or
+    /// `BackgroundShellManager::kill_process`), or a plain verb form that
+    /// stems to one (e.g. "flushed" naming `flush`), outranks chunks that
+    /// only match fuzzily or semantically.
+    ///
+    /// `token_budget` is measured against the fully rendered `text` -
+    /// every chunk's [`render_chunk`] header/fence and the separators
+    /// between chunks, not just their own content - so the assembled
+    /// prompt can't overflow the model's window once it actually leaves
+    /// this function. A chunk is kept in ranked order only if the text with
+    /// it added still fits; ranking stops at the first one that doesn't,
+    /// since every chunk after it is no more likely to fit. Chunks are
+    /// never split mid-way to squeeze in - see [`render_chunk`].
+    pub async fn provide_context(&self, message: &str, token_budget: usize) -> ProvidedContext {
+        self.evict_expired();
+
+        let cache_key = format!("{token_budget}:{message}");
+        if let Some(entry) = self.cache.lock().unwrap().get(&cache_key) {
+            self.stats.lock().unwrap().hits += 1;
+            return entry.context.clone();
+        }
+        self.stats.lock().unwrap().misses += 1;
+
+        let intent = self.query_analyzer.analyze(message);
+        let results = self
+            .retrieval
+            .search_prioritizing_symbols(
+                message,
+                self.config.search_limit,
+                &intent.candidate_symbols,
+            )
+            .await;
+        let results = results.dedupe_overlapping(self.config.dedupe_overlap_threshold);
+        let ranking_strategy = self.config.ranking_strategy;
+        let ranked = ChunkRanker.rank(results.as_slice(), ranking_strategy);
+        let used_fallback = self.tokenizer.is_none();
+        let mut chunks = Vec::new();
+        let mut rendered = Vec::new();
+        let mut tokens_used = 0usize;
+        let mut chunks_dropped = 0usize;
+
+        for result in &ranked {
+            let mut candidate_rendered = rendered.clone();
+            candidate_rendered.push(render_chunk(&result.chunk));
+            let candidate_tokens = self.count_tokens(&candidate_rendered.join("\n\n"));
+            if candidate_tokens > token_budget {
+                chunks_dropped = ranked.len() - chunks.len();
+                break;
+            }
+            rendered = candidate_rendered;
+            tokens_used = candidate_tokens;
+            chunks.push(result.chunk.clone());
+        }
+
+        let mut related_definitions: Vec<String> = Vec::new();
+        if self.config.related_definitions_enabled && !chunks.is_empty() {
+            let candidates = find_related_definitions(
+                &self.retrieval,
+                &self.query_analyzer,
+                &chunks,
+                self.config.related_definitions_limit,
+            )
+            .await;
+            for candidate in candidates {
+                let mut candidate_related = related_definitions.clone();
+                candidate_related.push(candidate.signature);
+                let candidate_tokens =
+                    self.count_tokens(&render_with_related(&rendered, &candidate_related));
+                if candidate_tokens > token_budget {
+                    break;
+                }
+                related_definitions = candidate_related;
+                tokens_used = candidate_tokens;
+            }
+        }
+
+        let text = render_with_related(&rendered, &related_definitions);
+        let paths: HashSet<String> = chunks.iter().map(|chunk| chunk.path.clone()).collect();
+        let context = ProvidedContext {
+            chunks,
+            text,
+            related_definitions,
+            tokens_used,
+            tokens_budget: token_budget,
+            metadata: ContextSearchMetadata {
+                used_token_estimate_fallback: used_fallback,
+                chunks_dropped,
+                ranking_strategy,
+            },
+        };
+
+        self.cache.lock().unwrap().insert(
+            cache_key,
+            CacheEntry {
+                context: context.clone(),
+                paths,
+                inserted_at: Instant::now(),
+            },
+        );
+        context
+    }
+
+    /// Like [`Self::provide_context`], but for a multi-turn conversation:
+    /// `messages` is the conversation so far, oldest first, with the
+    /// follow-up being retrieved for last. The last `ContextConfig::history_turns`
+    /// of them (including the follow-up) are joined into a single query
+    /// before analysis and retrieval, so an entity named a few turns back
+    /// (`` `kill_process` ``) still gets picked up by a pronoun-heavy
+    /// follow-up ("and how is it cancelled?") that doesn't name it again.
+    ///
+    /// Earlier turns than `history_turns` are dropped rather than
+    /// considered, both to bound how much irrelevant history dilutes the
+    /// query and because [`QueryAnalyzer`]'s exact-symbol detection gets
+    /// noisier the more unrelated prose it's run over.
+    pub async fn provide_context_with_history(
+        &self,
+        messages: &[&str],
+        token_budget: usize,
+    ) -> ProvidedContext {
+        let considered = recent_turns(messages, self.config.history_turns);
+        let expanded_query = considered.join(" ");
+        self.provide_context(&expanded_query, token_budget).await
+    }
+
+    /// Drops any cached contexts containing a chunk from any of `paths`.
+    ///
+    /// A cache entry that's only partially stale (some of its chunks
+    /// changed, some didn't) is dropped entirely rather than patched, since
+    /// there's no way to re-rank a partial hit without re-querying.
+    pub fn invalidate_paths(&self, paths: &[String]) {
+        let stale_paths: HashSet<&str> = paths.iter().map(String::as_str).collect();
+        let mut cache = self.cache.lock().unwrap();
+        let mut stats = self.stats.lock().unwrap();
+        cache.retain(|_, entry| {
+            let is_stale = entry.paths.iter().any(|path| stale_paths.contains(path.as_str()));
+            if is_stale {
+                stats.evictions_invalidation += 1;
+            }
+            !is_stale
+        });
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Runs `source` to completion, calling [`Self::invalidate_paths`] for
+    /// every batch of changed paths it reports - an optional alternative to
+    /// a caller invalidating paths itself after every reindex.
+    ///
+    /// `self` is only ever read through `&self` methods here, so this can
+    /// run concurrently with ordinary [`Self::provide_context`] calls;
+    /// typical usage is `tokio::spawn`ing it behind an `Arc<ContextProvider>`
+    /// alongside the indexer. Returns once `source` reports no more changes
+    /// will ever arrive.
+    pub async fn subscribe_to_changes(&self, mut source: impl ChangeSource) {
+        while let Some(paths) = source.next_change().await {
+            self.invalidate_paths(&paths);
+        }
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count_tokens(text),
+            None => text.len().div_ceil(FALLBACK_CHARS_PER_TOKEN),
+        }
+    }
+
+    fn evict_expired(&self) {
+        let ttl = self.config.cache_ttl;
+        let mut cache = self.cache.lock().unwrap();
+        let mut stats = self.stats.lock().unwrap();
+        cache.retain(|_, entry| {
+            let is_expired = entry.inserted_at.elapsed() >= ttl;
+            if is_expired {
+                stats.evictions_ttl += 1;
+            }
+            !is_expired
+        });
+    }
+}
+
+/// Renders `chunk` as a fenced code block labeled with its path, so the
+/// model sees where the snippet came from instead of bare, unattributed
+/// content. This is the unit [`ContextProvider::provide_context`] keeps or
+/// drops as a whole - the header makes a lone trailing line of a truncated
+/// chunk ambiguous (is `}` the end of the function or a mid-cut?), so a
+/// chunk that doesn't fit in the remaining budget is left out entirely
+/// rather than cut down to fit.
+fn render_chunk(chunk: &CodeChunk) -> String {
+    let language = chunk.language.as_deref().unwrap_or("");
+    format!("```{language}\n// {}\n{}\n```", chunk.path, chunk.content)
+}
+
+/// Joins `rendered` chunks exactly as [`ContextProvider::provide_context`]
+/// always has, then appends `related` (if any) as a trailing "Related
+/// definitions" section - kept as a free function so the one place that
+/// measures this text with [`ContextProvider::count_tokens`] and the one
+/// place that builds [`ProvidedContext::text`] can't drift apart.
+fn render_with_related(rendered: &[String], related: &[String]) -> String {
+    let mut text = rendered.join("\n\n");
+    if !related.is_empty() {
+        text.push_str("\n\nRelated definitions:\n");
+        text.push_str(&related.join("\n"));
+    }
+    text
+}
+
+/// The last `max_turns` of `messages` (including the last one), oldest
+/// first. Returns all of `messages` unchanged when `max_turns` is at least
+/// `messages.len()`.
+fn recent_turns<'a>(messages: &[&'a str], max_turns: usize) -> Vec<&'a str> {
+    let start = messages.len().saturating_sub(max_turns);
+    messages[start..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use codex_codebase_retrieval::HybridRetrieval;
+    use codex_embeddings::EmbeddingService;
+
+    use super::*;
+
+    fn chunk(path: &str, content: &str) -> CodeChunk {
+        chunk_with_symbol(path, content, None)
+    }
+
+    fn chunk_with_symbol(path: &str, content: &str, symbol_name: Option<&str>) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 1,
+            content: content.to_string(),
+            language: None,
+            chunk_type: None,
+            symbol_name: symbol_name.map(str::to_string),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    fn retrieval(chunks: Vec<CodeChunk>) -> HybridRetrieval {
+        let embeddings = EmbeddingService::new().embed(
+            &chunks.iter().map(|c| c.content.clone()).collect::<Vec<_>>(),
+        );
+        HybridRetrieval::new(chunks, embeddings)
+    }
+
+    #[tokio::test]
+    async fn repeated_queries_at_the_same_budget_are_served_from_the_cache() {
+        let provider = ContextProvider::new(retrieval(vec![chunk(
+            "a.rs",
+            "fn parse_error_handling() {}",
+        )]));
+
+        let first = provider.provide_context("parse_error_handling", 2_000).await;
+        let second = provider.provide_context("parse_error_handling", 2_000).await;
+
+        assert_eq!(first, second);
+        assert_eq!(provider.cache_stats().hits, 1);
+        assert_eq!(provider.cache_stats().misses, 1);
+    }
+
+    #[tokio::test]
+    async fn a_different_token_budget_is_not_served_from_the_other_budgets_cache_entry() {
+        let provider = ContextProvider::new(retrieval(vec![chunk(
+            "a.rs",
+            "fn parse_error_handling() {}",
+        )]));
+
+        provider.provide_context("parse_error_handling", 2_000).await;
+        provider.provide_context("parse_error_handling", 50).await;
+
+        assert_eq!(provider.cache_stats().misses, 2);
+        assert_eq!(provider.cache_stats().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn reports_tokens_used_and_the_requested_budget() {
+        let provider = ContextProvider::new(retrieval(vec![chunk(
+            "a.rs",
+            "fn parse_error_handling() {}",
+        )]));
+
+        let context = provider.provide_context("parse_error_handling", 2_000).await;
+        assert_eq!(context.tokens_budget, 2_000);
+        assert!(context.tokens_used > 0);
+        assert!(!context.metadata.used_token_estimate_fallback);
+    }
+
+    #[tokio::test]
+    async fn a_chunk_that_does_not_fit_once_rendered_is_dropped_whole_not_trimmed() {
+        let content = "fn parse_error_one() {}\nfn parse_error_two() {}\nfn parse_error_three() {}";
+        let provider = ContextProvider::new(retrieval(vec![chunk("a.rs", content)]));
+        let tokenizer = Tokenizer::new().unwrap();
+        let raw_content_tokens = tokenizer.count_tokens(content);
+
+        // Fits the chunk's raw content, but not once render_chunk's
+        // header/fence are counted in too.
+        let context = provider.provide_context("parse_error", raw_content_tokens).await;
+
+        assert_eq!(context.chunks.len(), 0);
+        assert_eq!(context.metadata.chunks_dropped, 1);
+        assert!(context.text.is_empty());
+        assert!(context.tokens_used <= raw_content_tokens);
+    }
+
+    #[tokio::test]
+    async fn a_tiny_budget_keeps_the_rendered_output_under_it() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_one() {}"),
+            chunk("b.rs", "fn parse_error_two() {}\nfn parse_error_three() {}"),
+        ];
+        let provider = ContextProvider::new(retrieval(chunks));
+
+        let budget = 8;
+        let context = provider.provide_context("parse_error", budget).await;
+
+        assert!(context.tokens_used <= budget);
+        assert_eq!(context.metadata.chunks_dropped, 2 - context.chunks.len());
+    }
+
+    #[tokio::test]
+    async fn an_exactly_named_symbol_in_the_message_is_prioritized_over_a_fuzzier_match() {
+        let chunks = vec![
+            chunk_with_symbol("a.rs", "fn kill_process(pid: u32) {}", Some("kill_process")),
+            chunk_with_symbol(
+                "b.rs",
+                "fn shutdown_background_shell_manager() {}",
+                Some("shutdown_background_shell_manager"),
+            ),
+        ];
+        let provider = ContextProvider::new(retrieval(chunks));
+
+        let context = provider
+            .provide_context(
+                "why does `kill_process` in the background shell manager return NotFound?",
+                2_000,
+            )
+            .await;
+
+        assert_eq!(context.chunks[0].path, "a.rs");
+    }
+
+    #[tokio::test]
+    async fn a_pronoun_heavy_follow_up_only_retrieves_the_right_chunk_with_history() {
+        let chunks = vec![
+            chunk_with_symbol("a.rs", "fn kill_process(pid: u32) {}", Some("kill_process")),
+            chunk_with_symbol(
+                "b.rs",
+                "fn cancel_pending_request() {}",
+                Some("cancel_pending_request"),
+            ),
+        ];
+        let provider = ContextProvider::new(retrieval(chunks));
+
+        let without_history = provider.provide_context("and how is it cancelled?", 2_000).await;
+        assert_eq!(without_history.chunks[0].path, "b.rs");
+
+        let with_history = provider
+            .provide_context_with_history(
+                &[
+                    "why does `kill_process` fail sometimes?",
+                    "and how is it cancelled?",
+                ],
+                2_000,
+            )
+            .await;
+        assert_eq!(with_history.chunks[0].path, "a.rs");
+    }
+
+    #[tokio::test]
+    async fn history_older_than_the_configured_cap_is_dropped() {
+        let chunks = vec![
+            chunk_with_symbol("a.rs", "fn kill_process(pid: u32) {}", Some("kill_process")),
+            chunk_with_symbol(
+                "b.rs",
+                "fn cancel_pending_request() {}",
+                Some("cancel_pending_request"),
+            ),
+        ];
+        let provider = ContextProvider::with_config(
+            retrieval(chunks),
+            ContextConfig {
+                history_turns: 1,
+                ..ContextConfig::default()
+            },
+        );
+
+        let context = provider
+            .provide_context_with_history(
+                &[
+                    "why does `kill_process` fail sometimes?",
+                    "and how is it cancelled?",
+                ],
+                2_000,
+            )
+            .await;
+
+        // `history_turns: 1` drops the first turn, so the backticked symbol
+        // never reaches the analyzer and the follow-up ranks like a plain,
+        // history-free query would.
+        assert_eq!(context.chunks[0].path, "b.rs");
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_the_configured_ttl() {
+        let provider = ContextProvider::with_config(
+            retrieval(vec![chunk("a.rs", "fn parse_error_handling() {}")]),
+            ContextConfig {
+                cache_ttl: Duration::from_millis(5),
+                ..ContextConfig::default()
+            },
+        );
+
+        provider.provide_context("parse_error_handling", 2_000).await;
+        sleep(Duration::from_millis(20));
+        provider.provide_context("parse_error_handling", 2_000).await;
+
+        let stats = provider.cache_stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.evictions_ttl, 1);
+    }
+
+    #[tokio::test]
+    async fn invalidate_paths_drops_entries_touching_any_of_the_given_paths() {
+        let provider = ContextProvider::new(retrieval(vec![chunk(
+            "a.rs",
+            "fn parse_error_handling() {}",
+        )]));
+
+        provider.provide_context("parse_error_handling", 2_000).await;
+        provider.invalidate_paths(&["a.rs".to_string()]);
+        provider.provide_context("parse_error_handling", 2_000).await;
+
+        let stats = provider.cache_stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.evictions_invalidation, 1);
+    }
+
+    struct OneShotChanges {
+        batches: std::vec::IntoIter<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ChangeSource for OneShotChanges {
+        async fn next_change(&mut self) -> Option<Vec<String>> {
+            self.batches.next()
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribe_to_changes_invalidates_as_each_batch_arrives() {
+        let provider = ContextProvider::new(retrieval(vec![chunk(
+            "a.rs",
+            "fn parse_error_handling() {}",
+        )]));
+
+        provider.provide_context("parse_error_handling", 2_000).await;
+        provider
+            .subscribe_to_changes(OneShotChanges {
+                batches: vec![vec!["a.rs".to_string()]].into_iter(),
+            })
+            .await;
+        provider.provide_context("parse_error_handling", 2_000).await;
+
+        let stats = provider.cache_stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.evictions_invalidation, 1);
+    }
+
+    fn chunk_with_lines(
+        path: &str,
+        start_line: usize,
+        end_line: usize,
+        content: &str,
+    ) -> CodeChunk {
+        CodeChunk { start_line, end_line, ..chunk(path, content) }
+    }
+
+    #[tokio::test]
+    async fn provide_context_merges_overlapping_hits_from_the_same_file_by_default() {
+        // A function indexed both standalone and again as part of its
+        // surrounding `impl` block - the two chunks' content differs only in
+        // how much of the file they span, which is exactly the case
+        // `dedupe_overlap_threshold` exists to catch.
+        let standalone = chunk_with_lines("a.rs", 10, 12, "fn parse_error_handling() {}");
+        let inside_impl = chunk_with_lines(
+            "a.rs",
+            9,
+            13,
+            "impl Parser {\n    fn parse_error_handling() {}\n}",
+        );
+        let provider = ContextProvider::new(retrieval(vec![standalone, inside_impl]));
+
+        let context = provider.provide_context("parse_error_handling", 2_000).await;
+
+        assert_eq!(context.chunks.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn related_definitions_are_included_when_enabled_and_omitted_by_default() {
+        let chunks = vec![
+            chunk_with_symbol(
+                "handler.rs",
+                "fn handle_request(req: RequestContext) {}",
+                Some("handle_request"),
+            ),
+            chunk_with_symbol(
+                "context.rs",
+                "pub struct RequestContext {\n    pub id: u64,\n}",
+                Some("RequestContext"),
+            ),
+        ];
+
+        let without = ContextProvider::new(retrieval(chunks.clone()));
+        let context = without.provide_context("handle_request", 2_000).await;
+        assert!(context.related_definitions.is_empty());
+        assert!(!context.text.contains("Related definitions"));
+
+        let with = ContextProvider::with_config(
+            retrieval(chunks),
+            ContextConfig {
+                related_definitions_enabled: true,
+                ..ContextConfig::default()
+            },
+        );
+        let context = with.provide_context("handle_request", 2_000).await;
+        assert_eq!(context.related_definitions.len(), 1);
+        assert!(context.related_definitions[0].contains("RequestContext"));
+        assert!(context.text.contains("Related definitions"));
+    }
+
+    #[tokio::test]
+    async fn related_definitions_are_dropped_first_when_the_budget_is_tight() {
+        let chunks = vec![
+            chunk_with_symbol(
+                "handler.rs",
+                "fn handle_request(req: RequestContext) {}",
+                Some("handle_request"),
+            ),
+            chunk_with_symbol(
+                "context.rs",
+                "pub struct RequestContext {\n    pub id: u64,\n}",
+                Some("RequestContext"),
+            ),
+        ];
+        let provider = ContextProvider::with_config(
+            retrieval(chunks),
+            ContextConfig {
+                related_definitions_enabled: true,
+                ..ContextConfig::default()
+            },
+        );
+
+        let tight_budget = provider.count_tokens(&render_chunk(&chunk_with_symbol(
+            "handler.rs",
+            "fn handle_request(req: RequestContext) {}",
+            Some("handle_request"),
+        )));
+        let context = provider.provide_context("handle_request", tight_budget).await;
+
+        assert_eq!(context.chunks.len(), 1);
+        assert!(context.related_definitions.is_empty());
+        assert!(context.tokens_used <= tight_budget);
+    }
+}
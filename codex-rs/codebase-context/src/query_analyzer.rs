@@ -0,0 +1,235 @@
+use regex_lite::Regex;
+
+/// A `path:line` reference extracted from a user message, e.g.
+/// `src/shell.rs:123`. `line` is `None` when only a path was given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRef {
+    pub path: String,
+    pub line: Option<usize>,
+}
+
+/// The result of [`QueryAnalyzer::analyze`]ing a user message before running
+/// retrieval.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchIntent {
+    /// Symbol names the message names exactly, via backticks, a
+    /// `Type::method` path, or an unambiguous CamelCase/snake_case
+    /// identifier — strong enough signals that retrieval should prioritize
+    /// an exact match over a fuzzy or semantic one.
+    pub exact_symbols: Vec<String>,
+    /// `path[:line]` references mentioned in the message.
+    pub file_references: Vec<FileRef>,
+    /// Identifier-shaped tokens extracted from the message: every
+    /// `exact_symbols` entry, plus a plain lowercase word with a common verb
+    /// suffix stripped (`"flushed"` becomes a candidate for `"flush"`) —
+    /// weaker evidence than `exact_symbols`, since a stemmed word is a guess
+    /// at a symbol name rather than an unambiguous mention of one. Meant for
+    /// [`crate::ContextProvider`] to prioritize exact-symbol retrieval even
+    /// when the message only describes the symbol in prose.
+    pub candidate_symbols: Vec<String>,
+}
+
+/// Extracts [`SearchIntent`] from a user message so retrieval can prioritize
+/// exact symbol and file hits ahead of fuzzy/semantic matches.
+///
+/// This is pattern-based, not a real parser: it recognizes backticked
+/// identifiers, `path/to/file.rs:123` references, `Type::method` paths, and
+/// bare identifiers that are unambiguously CamelCase, snake_case (contain
+/// `_`), or contain `::`. Plain lowercase words are never treated as exact
+/// symbols, since a single lowercase word like "parse" reads the same
+/// whether it's a description or a symbol name without `_`, `::` or a
+/// backtick.
+pub struct QueryAnalyzer {
+    backtick: Regex,
+    file_ref: Regex,
+    qualified_symbol: Regex,
+    camel_case: Regex,
+    snake_case: Regex,
+    plain_word: Regex,
+}
+
+impl QueryAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            backtick: Regex::new(r"`([^`]+)`").expect("static pattern"),
+            file_ref: Regex::new(r"\b[A-Za-z0-9_./-]+\.[A-Za-z0-9]{1,8}(?::(\d+))?\b")
+                .expect("static pattern"),
+            qualified_symbol: Regex::new(
+                r"\b[A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)+\b",
+            )
+            .expect("static pattern"),
+            camel_case: Regex::new(r"\b[A-Z][a-z0-9]*(?:[A-Z][a-z0-9]*)+\b")
+                .expect("static pattern"),
+            snake_case: Regex::new(r"\b[a-z][a-z0-9]*_[a-z0-9_]*[a-z0-9]\b")
+                .expect("static pattern"),
+            plain_word: Regex::new(r"\b[a-z]{3,}\b").expect("static pattern"),
+        }
+    }
+
+    pub fn analyze(&self, message: &str) -> SearchIntent {
+        let mut exact_symbols = Vec::new();
+        let mut file_references = Vec::new();
+
+        for m in self.backtick.captures_iter(message) {
+            let inner = m.get(1).expect("group 1 always matches").as_str();
+            if let Some(file_ref) = self.parse_file_ref(inner) {
+                file_references.push(file_ref);
+            } else {
+                exact_symbols.push(inner.to_string());
+            }
+        }
+
+        for m in self.file_ref.find_iter(message) {
+            if let Some(file_ref) = self.parse_file_ref(m.as_str()) {
+                file_references.push(file_ref);
+            }
+        }
+
+        for pattern in [&self.qualified_symbol, &self.camel_case, &self.snake_case] {
+            for m in pattern.find_iter(message) {
+                exact_symbols.push(m.as_str().to_string());
+            }
+        }
+
+        exact_symbols.sort();
+        exact_symbols.dedup();
+        file_references.sort_by(|a, b| (a.path.as_str(), a.line).cmp(&(b.path.as_str(), b.line)));
+        file_references.dedup();
+
+        let mut candidate_symbols = exact_symbols.clone();
+        for m in self.plain_word.find_iter(message) {
+            if let Some(stemmed) = Self::stem(m.as_str()) {
+                candidate_symbols.push(stemmed);
+            }
+        }
+        candidate_symbols.sort();
+        candidate_symbols.dedup();
+
+        SearchIntent {
+            exact_symbols,
+            file_references,
+            candidate_symbols,
+        }
+    }
+
+    /// Strips a common English verb suffix (past tense `-ed`, progressive
+    /// `-ing`) from `word`, returning `None` when it has neither - most
+    /// lowercase words in a question ("where", "does", "the") aren't verb
+    /// forms naming a symbol, so leaving them unchanged and excluding them
+    /// keeps `candidate_symbols` from filling up with ordinary prose.
+    ///
+    /// This is a suffix strip, not a real stemmer: `"flushed"` correctly
+    /// becomes `"flush"`, but `"parsing"` becomes `"pars"` rather than
+    /// `"parse"`. Good enough to pair a verb with the snake_case/CamelCase
+    /// symbol a developer would actually name, not a substitute for
+    /// morphological analysis.
+    fn stem(word: &str) -> Option<String> {
+        for suffix in ["ed", "ing"] {
+            if let Some(stripped) = word.strip_suffix(suffix)
+                && stripped.len() >= 3
+            {
+                return Some(stripped.to_string());
+            }
+        }
+        None
+    }
+
+    /// Parses `path:line` or a bare `path`, requiring a `.` somewhere in the
+    /// path to distinguish it from an ordinary word.
+    fn parse_file_ref(&self, text: &str) -> Option<FileRef> {
+        let (path, line) = match text.split_once(':') {
+            Some((path, line)) => (path, line.parse::<usize>().ok()),
+            None => (text, None),
+        };
+        if path.contains('.') && !path.starts_with('.') {
+            Some(FileRef {
+                path: path.to_string(),
+                line,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for QueryAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_backticked_identifiers() {
+        let intent = QueryAnalyzer::new().analyze("what does `kill_process` do?");
+        assert_eq!(intent.exact_symbols, vec!["kill_process".to_string()]);
+    }
+
+    #[test]
+    fn recognizes_file_line_references() {
+        let intent = QueryAnalyzer::new().analyze("see src/shell.rs:123 for the fix");
+        assert_eq!(
+            intent.file_references,
+            vec![FileRef {
+                path: "src/shell.rs".to_string(),
+                line: Some(123),
+            }]
+        );
+    }
+
+    #[test]
+    fn recognizes_qualified_type_method_patterns() {
+        let intent = QueryAnalyzer::new()
+            .analyze("why does BackgroundShellManager::kill_process return NotFound?");
+        assert!(intent
+            .exact_symbols
+            .contains(&"BackgroundShellManager::kill_process".to_string()));
+    }
+
+    #[test]
+    fn recognizes_bare_camel_case_and_snake_case_identifiers_without_backticks() {
+        let intent =
+            QueryAnalyzer::new().analyze("the ChunkerConfig struct and parse_error_handling fn");
+        assert!(intent.exact_symbols.contains(&"ChunkerConfig".to_string()));
+        assert!(intent
+            .exact_symbols
+            .contains(&"parse_error_handling".to_string()));
+    }
+
+    #[test]
+    fn plain_lowercase_words_are_not_treated_as_symbols() {
+        let intent = QueryAnalyzer::new().analyze("why does this return an error");
+        assert!(intent.exact_symbols.is_empty());
+    }
+
+    #[test]
+    fn candidate_symbols_includes_a_camel_case_mention_and_a_stemmed_verb() {
+        let intent = QueryAnalyzer::new().analyze("where is ShellLogBuffer flushed?");
+        assert!(intent.candidate_symbols.contains(&"ShellLogBuffer".to_string()));
+        assert!(intent.candidate_symbols.contains(&"flush".to_string()));
+    }
+
+    #[test]
+    fn candidate_symbols_excludes_ordinary_words_with_no_verb_suffix() {
+        let intent = QueryAnalyzer::new().analyze("why does this return an error");
+        assert!(!intent.candidate_symbols.contains(&"why".to_string()));
+        assert!(!intent.candidate_symbols.contains(&"this".to_string()));
+        assert!(!intent.candidate_symbols.contains(&"error".to_string()));
+    }
+
+    #[test]
+    fn a_backticked_path_is_a_file_reference_not_a_symbol() {
+        let intent = QueryAnalyzer::new().analyze("look at `src/shell.rs:42`");
+        assert!(intent.exact_symbols.is_empty());
+        assert_eq!(
+            intent.file_references,
+            vec![FileRef {
+                path: "src/shell.rs".to_string(),
+                line: Some(42),
+            }]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use codex_codebase_retrieval::SearchResult;
+
+/// How [`crate::ContextProvider::provide_context`] orders ranked candidates
+/// before trimming them down to the token budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankingStrategy {
+    /// Keep [`codex_codebase_retrieval::HybridRetrieval`]'s fused/reranked
+    /// order unchanged.
+    Relevance,
+    /// Re-order by Maximal Marginal Relevance: after each pick, a
+    /// candidate's score is penalized by how similar it is to chunks
+    /// already selected, so a question about "error handling" doesn't
+    /// return several near-identical chunks all from `error.rs` while
+    /// equally relevant code elsewhere is left out.
+    ///
+    /// `lambda` trades off relevance (`1.0`) against diversity (`0.0`).
+    /// At `lambda = 1.0` this produces exactly
+    /// [`Self::Relevance`]'s ordering.
+    MaximalMarginalRelevance { lambda: f32 },
+}
+
+impl Default for RankingStrategy {
+    fn default() -> Self {
+        Self::Relevance
+    }
+}
+
+/// Orders [`SearchResult`]s for [`crate::ContextProvider::provide_context`]
+/// according to a [`RankingStrategy`].
+///
+/// Unlike [`codex_codebase_retrieval::HybridRetrieval`]'s own
+/// `RerankStrategy::Mmr`, which penalizes redundancy by embedding cosine
+/// similarity, this only ever sees a [`SearchResult`] - no chunk
+/// embeddings are available at this layer - so similarity is approximated
+/// by same-file/same-symbol heuristics instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChunkRanker;
+
+impl ChunkRanker {
+    pub fn rank(&self, results: &[SearchResult], strategy: RankingStrategy) -> Vec<SearchResult> {
+        match strategy {
+            RankingStrategy::Relevance => results.to_vec(),
+            RankingStrategy::MaximalMarginalRelevance { lambda } => mmr_select(results, lambda),
+        }
+    }
+}
+
+fn mmr_select(results: &[SearchResult], lambda: f32) -> Vec<SearchResult> {
+    let mut remaining: Vec<&SearchResult> = results.iter().collect();
+    let mut selected: Vec<SearchResult> = Vec::with_capacity(results.len());
+
+    while !remaining.is_empty() {
+        // The first (lowest-index) candidate with the highest adjusted
+        // score wins ties, so that at `lambda = 1.0` - where the penalty
+        // term always drops out - this reduces to a stable pick of the
+        // already-sorted input in its original order.
+        let mut best_idx = 0;
+        let mut best_adjusted = f32::MIN;
+        for (idx, candidate) in remaining.iter().enumerate() {
+            let redundancy = selected
+                .iter()
+                .map(|chosen| file_symbol_similarity(chosen, candidate))
+                .fold(0.0f32, f32::max);
+            let adjusted = lambda * candidate.normalized_score - (1.0 - lambda) * redundancy;
+            if adjusted > best_adjusted {
+                best_idx = idx;
+                best_adjusted = adjusted;
+            }
+        }
+        selected.push(remaining.remove(best_idx).clone());
+    }
+
+    selected
+}
+
+/// A cheap stand-in for embedding cosine similarity when only
+/// [`SearchResult`]s, not raw chunk embeddings, are available: `1.0` if `a`
+/// and `b` come from the same file and name the same symbol, `0.5` if only
+/// the file matches, `0.0` otherwise.
+fn file_symbol_similarity(a: &SearchResult, b: &SearchResult) -> f32 {
+    if a.chunk.path != b.chunk.path {
+        return 0.0;
+    }
+    match (&a.chunk.symbol_name, &b.chunk.symbol_name) {
+        (Some(a_symbol), Some(b_symbol)) if a_symbol == b_symbol => 1.0,
+        _ => 0.5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codex_codebase_retrieval::SearchSource;
+    use codex_vector_store::CodeChunk;
+
+    use super::*;
+
+    fn result(path: &str, symbol_name: Option<&str>, score: f32) -> SearchResult {
+        SearchResult {
+            chunk: CodeChunk {
+                path: path.to_string(),
+                start_line: 1,
+                end_line: 1,
+                content: String::new(),
+                language: None,
+                chunk_type: None,
+                symbol_name: symbol_name.map(str::to_string),
+                content_hash: None,
+                chunker_version: None,
+                doc_summary: None,
+                context_imports: None,
+                estimated_tokens: None,
+            },
+            score,
+            normalized_score: score,
+            source: SearchSource::Both,
+            fuzzy_score: None,
+            semantic_score: None,
+            fuzzy_rank: None,
+            semantic_rank: None,
+            breakdown: None,
+            merged_from: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lambda_one_matches_pure_relevance_ordering() {
+        let results = vec![
+            result("a.rs", None, 0.9),
+            result("a.rs", None, 0.8),
+            result("b.rs", None, 0.7),
+        ];
+
+        let ranked =
+            ChunkRanker.rank(&results, RankingStrategy::MaximalMarginalRelevance { lambda: 1.0 });
+
+        assert_eq!(ranked, results);
+    }
+
+    #[test]
+    fn diversity_promotes_a_different_file_over_a_near_tied_same_file_hit() {
+        let results = vec![
+            result("error.rs", Some("handle_error"), 0.95),
+            result("error.rs", Some("log_error"), 0.94),
+            result("retry.rs", Some("retry_on_error"), 0.80),
+        ];
+
+        let ranked =
+            ChunkRanker.rank(&results, RankingStrategy::MaximalMarginalRelevance { lambda: 0.5 });
+
+        assert_eq!(ranked[0].chunk.path, "error.rs");
+        assert_eq!(ranked[1].chunk.path, "retry.rs");
+        assert_eq!(ranked[2].chunk.path, "error.rs");
+    }
+}
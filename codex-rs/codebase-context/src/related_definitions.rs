@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use codex_codebase_retrieval::HybridRetrieval;
+use codex_vector_store::CodeChunk;
+
+use crate::query_analyzer::QueryAnalyzer;
+
+/// A compact, one-line stand-in for a type or function an injected chunk
+/// references but doesn't itself define - enough for a model to know its
+/// shape without hallucinating one, without paying the token cost of the
+/// whole definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedDefinition {
+    pub path: String,
+    pub symbol_name: String,
+    /// `{path}:{start_line} {first non-blank line of content}`.
+    pub signature: String,
+    /// The defining chunk's [`codex_codebase_retrieval::SearchResult::normalized_score`]
+    /// against the referencing chunk's own content, used to decide which
+    /// definitions survive budget trimming when there isn't room for all of
+    /// them.
+    pub relevance: f32,
+}
+
+/// For each of `chunks`, extracts the identifiers it references (via
+/// [`QueryAnalyzer`]'s exact-symbol patterns - a code chunk is at least as
+/// identifier-shaped as a user message) and looks up, through `retrieval`'s
+/// exact-symbol search, the chunk elsewhere in the index that defines each
+/// one. At most `limit_per_chunk` definitions are kept per entry in
+/// `chunks`.
+///
+/// A hit is dropped if it's the chunk it came from, or doesn't actually
+/// define one of the referenced names - `HybridRetrieval::search_prioritizing_symbols`
+/// only boosts a matching symbol's rank, it doesn't filter to it. Results
+/// are deduplicated by `(path, symbol_name)`, keeping the higher-scored
+/// hit, and returned sorted by descending `relevance` so a caller can take
+/// a budget-bounded prefix and know it dropped the lowest-relevance entries
+/// first.
+pub async fn find_related_definitions(
+    retrieval: &HybridRetrieval,
+    query_analyzer: &QueryAnalyzer,
+    chunks: &[CodeChunk],
+    limit_per_chunk: usize,
+) -> Vec<RelatedDefinition> {
+    let mut by_key: HashMap<(String, String), RelatedDefinition> = HashMap::new();
+
+    for chunk in chunks {
+        let referenced: Vec<String> = query_analyzer
+            .analyze(&chunk.content)
+            .exact_symbols
+            .into_iter()
+            .filter(|symbol| chunk.symbol_name.as_deref() != Some(symbol.as_str()))
+            .collect();
+        if referenced.is_empty() {
+            continue;
+        }
+
+        let results = retrieval
+            .search_prioritizing_symbols(&chunk.content, limit_per_chunk * 4, &referenced)
+            .await;
+
+        let mut kept_for_chunk = 0usize;
+        for result in results.as_slice() {
+            if kept_for_chunk >= limit_per_chunk {
+                break;
+            }
+            if result.chunk.path == chunk.path {
+                continue;
+            }
+            let Some(symbol_name) = result.chunk.symbol_name.as_deref() else {
+                continue;
+            };
+            if !referenced.iter().any(|name| name == symbol_name) {
+                continue;
+            }
+
+            let key = (result.chunk.path.clone(), symbol_name.to_string());
+            let candidate = RelatedDefinition {
+                path: result.chunk.path.clone(),
+                symbol_name: symbol_name.to_string(),
+                signature: signature_line(&result.chunk),
+                relevance: result.normalized_score,
+            };
+            by_key
+                .entry(key)
+                .and_modify(|existing| {
+                    if candidate.relevance > existing.relevance {
+                        *existing = candidate.clone();
+                    }
+                })
+                .or_insert(candidate);
+            kept_for_chunk += 1;
+        }
+    }
+
+    let mut definitions: Vec<RelatedDefinition> = by_key.into_values().collect();
+    definitions.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+    definitions
+}
+
+fn signature_line(chunk: &CodeChunk) -> String {
+    let first_line = chunk
+        .content
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    format!("{}:{} {first_line}", chunk.path, chunk.start_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use codex_embeddings::EmbeddingService;
+
+    use super::*;
+
+    fn chunk(path: &str, symbol_name: &str, content: &str) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 1,
+            content: content.to_string(),
+            language: None,
+            chunk_type: None,
+            symbol_name: Some(symbol_name.to_string()),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    fn retrieval(chunks: Vec<CodeChunk>) -> HybridRetrieval {
+        let contents: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+        let embeddings = EmbeddingService::new().embed(&contents);
+        HybridRetrieval::new(chunks, embeddings)
+    }
+
+    #[tokio::test]
+    async fn finds_the_definition_of_a_type_a_chunk_references() {
+        let referencing = chunk(
+            "handler.rs",
+            "handle_request",
+            "fn handle_request(req: RequestContext) {}",
+        );
+        let definition = chunk(
+            "context.rs",
+            "RequestContext",
+            "pub struct RequestContext {\n    pub id: u64,\n}",
+        );
+        let index = retrieval(vec![referencing.clone(), definition]);
+        let analyzer = QueryAnalyzer::new();
+
+        let found = find_related_definitions(&index, &analyzer, &[referencing], 3).await;
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "context.rs");
+        assert_eq!(found[0].symbol_name, "RequestContext");
+        assert_eq!(found[0].signature, "context.rs:1 pub struct RequestContext {");
+    }
+
+    #[tokio::test]
+    async fn returns_nothing_when_no_chunk_defines_the_referenced_symbol() {
+        let referencing = chunk("a.rs", "uses_something", "fn uses_something(x: ArcWrapper) {}");
+        let index = retrieval(vec![referencing.clone()]);
+        let analyzer = QueryAnalyzer::new();
+
+        let found = find_related_definitions(&index, &analyzer, &[referencing], 3).await;
+
+        assert!(found.is_empty());
+    }
+}
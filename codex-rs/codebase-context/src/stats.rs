@@ -0,0 +1,11 @@
+/// Counters describing [`crate::ContextProvider`]'s cache behavior.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// Entries evicted because their TTL expired before being reused.
+    pub evictions_ttl: usize,
+    /// Entries evicted by [`crate::ContextProvider::invalidate_paths`]
+    /// because one of their underlying chunks' paths changed.
+    pub evictions_invalidation: usize,
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::error::IndexerError;
+
+const CACHE_FILE_NAME: &str = "embedding_cache.json";
+
+/// Schema version of the on-disk cache file. Bump this whenever
+/// `CacheFile`'s shape changes, and add a migration arm to `load` rather
+/// than breaking old caches outright.
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<String, Vec<f32>>,
+}
+
+/// A content-hash -> embedding cache, persisted under the indexer's `index_dir`.
+///
+/// Keys are `sha256(content || "\0" || model_name || "\0" || dim)`, so
+/// changing the embedding model or requested dimension invalidates the cache
+/// automatically rather than silently returning stale vectors.
+pub struct EmbeddingCache {
+    path: PathBuf,
+    entries: HashMap<String, Vec<f32>>,
+    was_reset: bool,
+}
+
+impl EmbeddingCache {
+    /// Loads the cache from `index_dir`, discarding it in favor of an empty
+    /// one if the file is missing, corrupt/truncated, or was written by a
+    /// `CACHE_VERSION` this build doesn't know how to migrate - a mismatch
+    /// just means every chunk gets re-embedded on the next run rather than
+    /// `CodebaseIndexer::new` failing outright. [`Self::take_reset_flag`]
+    /// reports which of these happened.
+    pub fn load(index_dir: &Path) -> Result<Self, IndexerError> {
+        let path = index_dir.join(CACHE_FILE_NAME);
+        let (entries, was_reset) = if path.exists() {
+            let contents = fs::read_to_string(&path).map_err(|source| IndexerError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            match serde_json::from_str::<CacheFile>(&contents) {
+                Ok(cache_file) if cache_file.version == CACHE_VERSION => {
+                    (cache_file.entries, false)
+                }
+                Ok(_) | Err(_) => (HashMap::new(), true),
+            }
+        } else {
+            (HashMap::new(), false)
+        };
+        Ok(Self {
+            path,
+            entries,
+            was_reset,
+        })
+    }
+
+    /// Returns whether [`Self::load`] discarded an incompatible or corrupt
+    /// cache file, clearing the flag so only the first caller observes it -
+    /// `CodebaseIndexer::index`/`index_stream` surface this as
+    /// `IndexStats::embedding_cache_reset` on their first run.
+    pub fn take_reset_flag(&mut self) -> bool {
+        std::mem::take(&mut self.was_reset)
+    }
+
+    pub fn key(content: &str, model_name: &str, dim: usize) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(model_name.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(dim.to_le_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Vec<f32>> {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, embedding: Vec<f32>) {
+        self.entries.insert(key, embedding);
+    }
+
+    pub fn persist(&self) -> Result<(), IndexerError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|source| IndexerError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let cache_file = CacheFile {
+            version: CACHE_VERSION,
+            entries: self.entries.clone(),
+        };
+        let contents = serde_json::to_string(&cache_file)?;
+        fs::write(&self.path, contents).map_err(|source| IndexerError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    pub fn clear(&mut self) -> Result<(), IndexerError> {
+        self.entries.clear();
+        if self.path.exists() {
+            fs::remove_file(&self.path).map_err(|source| IndexerError::Io {
+                path: self.path.clone(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+/// Configuration for [`crate::CodebaseIndexer`].
+#[derive(Debug, Clone)]
+pub struct IndexerConfig {
+    /// Root of the repository to index.
+    pub root_dir: PathBuf,
+    /// Directory used to persist indexer state (the embedding cache, etc.).
+    pub index_dir: PathBuf,
+    /// Whether nested `.gitignore` files and global git excludes are honored
+    /// while walking `root_dir`. Defaults to `true`.
+    pub respect_gitignore: bool,
+    /// Glob patterns (relative to `root_dir`) that are always skipped, in
+    /// addition to whatever `.gitignore` already excludes - the place to put
+    /// a custom ignore rule (e.g. a vendored directory with no `.gitignore`
+    /// of its own) without editing the repository.
+    pub exclude_globs: Vec<String>,
+    /// Glob patterns that are indexed even if they would otherwise be
+    /// excluded by `.gitignore` or `exclude_globs`.
+    pub include_globs: Vec<String>,
+    /// When `true`, [`crate::CodebaseIndexer::index`] walks and chunks files
+    /// to compute [`crate::IndexStats`] as usual, but never calls the
+    /// embedding service, writes to the [`codex_vector_store::VectorStore`],
+    /// or persists the embedding cache - so a caller can see how much work a
+    /// full reindex would be before committing to it. Defaults to `false`.
+    pub dry_run: bool,
+    /// Upper bound on how many chunks [`crate::CodebaseIndexer::index`]
+    /// embeds at once, passed through as
+    /// [`codex_embeddings::EmbeddingConfig::max_parallel_batches`] - the
+    /// indexer embeds every file's chunks in a single batch per run rather
+    /// than one call per chunk, so this is what actually keeps the
+    /// embedding service's thread pool bounded and busy. Defaults to the
+    /// number of available cores (falling back to `1` if that can't be
+    /// determined).
+    pub max_concurrency: usize,
+    /// Upper bound on how many files [`crate::CodebaseIndexer::index`] reads
+    /// and chunks at once, bounded by a dedicated rayon thread pool separate
+    /// from `max_concurrency`'s embedding-batch pool - so a caller squeezed
+    /// for CPU (e.g. because the embedding model is also running on this
+    /// machine) can turn this down independently instead of only having one
+    /// combined knob. `None` defaults to `max_concurrency`. `Some(1)`
+    /// serializes file processing entirely.
+    pub max_concurrent_files: Option<usize>,
+}
+
+impl IndexerConfig {
+    pub fn new(root_dir: impl Into<PathBuf>, index_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            index_dir: index_dir.into(),
+            respect_gitignore: true,
+            exclude_globs: Vec::new(),
+            include_globs: Vec::new(),
+            dry_run: false,
+            max_concurrency: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            max_concurrent_files: None,
+        }
+    }
+
+    pub fn with_respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+        self.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    pub fn with_exclude_globs(mut self, exclude_globs: Vec<String>) -> Self {
+        self.exclude_globs = exclude_globs;
+        self
+    }
+
+    pub fn with_include_globs(mut self, include_globs: Vec<String>) -> Self {
+        self.include_globs = include_globs;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    pub fn with_max_concurrent_files(mut self, max_concurrent_files: usize) -> Self {
+        self.max_concurrent_files = Some(max_concurrent_files);
+        self
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Coalesces a burst of file-change paths into a single batch, flushed once
+/// no new change has arrived for `quiet_window`.
+///
+/// There's no file-watching loop in this crate today - callers wire this up
+/// to whatever notices the changes (e.g. a `notify` watcher, as
+/// `codex_core::file_watcher::FileWatcher` does for its own subscribers).
+/// Unlike [`codex_core::file_watcher::ThrottledWatchReceiver`], which throttles
+/// on a leading edge (fire immediately, then suppress for an interval), this
+/// debounces on the trailing edge: a caller that calls [`record_change`] ten
+/// times in quick succession while saving ten files gets one batch back, not
+/// ten, because each call pushes the flush deadline out instead of starting
+/// its own independent one.
+///
+/// [`record_change`]: ChangeBatcher::record_change
+pub struct ChangeBatcher {
+    quiet_window: Duration,
+    pending: BTreeSet<String>,
+    flush_at: Option<Instant>,
+}
+
+impl ChangeBatcher {
+    pub fn new(quiet_window: Duration) -> Self {
+        Self {
+            quiet_window,
+            pending: BTreeSet::new(),
+            flush_at: None,
+        }
+    }
+
+    /// Records a changed path at `now`, pushing the flush deadline out by
+    /// `quiet_window` from this call.
+    pub fn record_change(&mut self, path: String, now: Instant) {
+        self.pending.insert(path);
+        self.flush_at = Some(now + self.quiet_window);
+    }
+
+    /// Whether `now` is at or past the flush deadline set by the most recent
+    /// [`record_change`](Self::record_change) call. `false` while nothing is
+    /// pending.
+    pub fn ready_to_flush(&self, now: Instant) -> bool {
+        self.flush_at.is_some_and(|flush_at| now >= flush_at)
+    }
+
+    /// Drains and returns the pending paths in sorted, deduplicated order,
+    /// clearing the flush deadline. Prefer feeding this straight into
+    /// [`crate::CodebaseIndexer::reindex_paths`] over a full re-index - this
+    /// type only tells you *when* to flush, not whether a full rebuild is
+    /// warranted.
+    pub fn flush(&mut self) -> Vec<String> {
+        self.flush_at = None;
+        std::mem::take(&mut self.pending).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_single_burst_of_rapid_changes_flushes_once_as_one_batch() {
+        let mut batcher = ChangeBatcher::new(Duration::from_millis(500));
+        let base = Instant::now();
+
+        for (offset_millis, path) in [(0, "a.rs"), (10, "b.rs"), (20, "c.rs")] {
+            batcher.record_change(path.to_string(), base + Duration::from_millis(offset_millis));
+        }
+
+        // Still within the quiet window measured from the last change (20ms).
+        assert!(!batcher.ready_to_flush(base + Duration::from_millis(400)));
+
+        let flush_time = base + Duration::from_millis(20) + Duration::from_millis(500);
+        assert!(batcher.ready_to_flush(flush_time));
+        let mut flushed = batcher.flush();
+        flushed.sort();
+        assert_eq!(flushed, vec!["a.rs", "b.rs", "c.rs"]);
+
+        // One flush drains everything; a second is a no-op until something
+        // new is recorded.
+        assert!(!batcher.ready_to_flush(flush_time + Duration::from_secs(1)));
+        assert!(batcher.flush().is_empty());
+    }
+
+    #[test]
+    fn repeated_changes_to_the_same_path_only_flush_it_once() {
+        let mut batcher = ChangeBatcher::new(Duration::from_millis(100));
+        let base = Instant::now();
+        batcher.record_change("a.rs".to_string(), base);
+        batcher.record_change("a.rs".to_string(), base + Duration::from_millis(10));
+
+        let flushed = batcher.flush();
+        assert_eq!(flushed, vec!["a.rs"]);
+    }
+
+    #[test]
+    fn nothing_is_ready_to_flush_before_any_change_is_recorded() {
+        let batcher = ChangeBatcher::new(Duration::from_millis(100));
+        assert!(!batcher.ready_to_flush(Instant::now()));
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("failed to read or write {path:?}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+    #[error(transparent)]
+    VectorStore(#[from] codex_vector_store::VectorStoreError),
+    #[error("failed to walk {path:?}")]
+    Walk {
+        path: PathBuf,
+        #[source]
+        source: ignore::Error,
+    },
+    #[error("invalid glob pattern {glob:?}")]
+    InvalidGlob {
+        glob: String,
+        #[source]
+        source: ignore::Error,
+    },
+}
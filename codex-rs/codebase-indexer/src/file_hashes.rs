@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::error::IndexerError;
+
+const FILE_HASHES_FILE_NAME: &str = "file_hashes.json";
+const FILE_HASHES_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct FileHashesFile {
+    version: u32,
+    hashes: HashMap<String, String>,
+}
+
+/// A relative-path -> content-hash map, persisted under the indexer's
+/// `index_dir`, so [`crate::CodebaseIndexer::index`] can skip a file whose
+/// content hasn't changed since the last run entirely - no re-chunking, not
+/// just re-using an already-cached embedding.
+pub struct FileHashes {
+    path: PathBuf,
+    hashes: HashMap<String, String>,
+}
+
+impl FileHashes {
+    /// Loads the map from `index_dir`, starting empty if the file is
+    /// missing, corrupt/truncated, or was written by an incompatible
+    /// version - mirrors [`crate::cache::EmbeddingCache::load`], but every
+    /// file simply gets re-chunked once rather than failing outright.
+    pub fn load(index_dir: &Path) -> Result<Self, IndexerError> {
+        let path = index_dir.join(FILE_HASHES_FILE_NAME);
+        let hashes = if path.exists() {
+            let contents = fs::read_to_string(&path).map_err(|source| IndexerError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            match serde_json::from_str::<FileHashesFile>(&contents) {
+                Ok(file) if file.version == FILE_HASHES_VERSION => file.hashes,
+                Ok(_) | Err(_) => HashMap::new(),
+            }
+        } else {
+            HashMap::new()
+        };
+        Ok(Self { path, hashes })
+    }
+
+    pub fn hash_of(content: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&self, relative_path: &str) -> Option<&String> {
+        self.hashes.get(relative_path)
+    }
+
+    pub fn insert(&mut self, relative_path: String, hash: String) {
+        self.hashes.insert(relative_path, hash);
+    }
+
+    pub fn persist(&self) -> Result<(), IndexerError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|source| IndexerError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let file = FileHashesFile {
+            version: FILE_HASHES_VERSION,
+            hashes: self.hashes.clone(),
+        };
+        let contents = serde_json::to_string(&file)?;
+        fs::write(&self.path, contents).map_err(|source| IndexerError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    pub fn clear(&mut self) -> Result<(), IndexerError> {
+        self.hashes.clear();
+        if self.path.exists() {
+            fs::remove_file(&self.path).map_err(|source| IndexerError::Io {
+                path: self.path.clone(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+}
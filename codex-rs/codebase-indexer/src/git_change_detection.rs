@@ -0,0 +1,208 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::IndexerError;
+
+const GIT_STATE_FILE_NAME: &str = "git_state.json";
+const GIT_STATE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct GitStateFile {
+    version: u32,
+    last_indexed_commit: Option<String>,
+}
+
+/// The commit [`crate::CodebaseIndexer::index`] last indexed at, persisted
+/// under the indexer's `index_dir` so the next run can ask git what
+/// changed since then instead of content-hashing every file in the repo.
+pub struct GitState {
+    path: PathBuf,
+    last_indexed_commit: Option<String>,
+}
+
+impl GitState {
+    /// Loads the state from `index_dir`, starting with no known commit if
+    /// the file is missing, corrupt/truncated, or was written by an
+    /// incompatible version - mirrors [`crate::cache::EmbeddingCache::load`],
+    /// the next run just falls back to a full content-hash comparison.
+    pub fn load(index_dir: &Path) -> Result<Self, IndexerError> {
+        let path = index_dir.join(GIT_STATE_FILE_NAME);
+        let last_indexed_commit = if path.exists() {
+            let contents = fs::read_to_string(&path).map_err(|source| IndexerError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            match serde_json::from_str::<GitStateFile>(&contents) {
+                Ok(file) if file.version == GIT_STATE_VERSION => file.last_indexed_commit,
+                Ok(_) | Err(_) => None,
+            }
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            last_indexed_commit,
+        })
+    }
+
+    pub fn last_indexed_commit(&self) -> Option<&str> {
+        self.last_indexed_commit.as_deref()
+    }
+
+    pub fn set_last_indexed_commit(&mut self, commit: String) {
+        self.last_indexed_commit = Some(commit);
+    }
+
+    pub fn persist(&self) -> Result<(), IndexerError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|source| IndexerError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let file = GitStateFile {
+            version: GIT_STATE_VERSION,
+            last_indexed_commit: self.last_indexed_commit.clone(),
+        };
+        let contents = serde_json::to_string(&file)?;
+        fs::write(&self.path, contents).map_err(|source| IndexerError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+}
+
+/// Paths to re-index and paths to drop from the store, per
+/// `git diff --name-status` plus any uncommitted (`git status --porcelain`)
+/// changes.
+pub struct ChangedPaths {
+    pub added_or_modified: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+/// The repository's current `HEAD` commit, or `None` if `root_dir` isn't a
+/// git work tree (or `git` isn't on `PATH`) - callers silently fall back to
+/// a full content-hash comparison in that case.
+pub fn current_head(root_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let head = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if head.is_empty() { None } else { Some(head) }
+}
+
+/// Everything that changed between `old_commit` and `new_commit`, including
+/// the current uncommitted working-tree state. `None` if either underlying
+/// git command fails - e.g. `old_commit` is no longer reachable after a
+/// rebase or `git gc` - so the caller can fall back to a full content-hash
+/// comparison rather than silently missing a change it couldn't see.
+pub fn changed_paths_since(
+    root_dir: &Path,
+    old_commit: &str,
+    new_commit: &str,
+) -> Option<ChangedPaths> {
+    let mut changed = name_status(root_dir, old_commit, new_commit)?;
+    let (dirty_added, dirty_deleted) = dirty_paths(root_dir)?;
+    changed.added_or_modified.extend(dirty_added);
+    changed.deleted.extend(dirty_deleted);
+    Some(changed)
+}
+
+fn name_status(root_dir: &Path, old_commit: &str, new_commit: &str) -> Option<ChangedPaths> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_dir)
+        .args(["diff", "--name-status", old_commit, new_commit])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let mut added_or_modified = Vec::new();
+    let mut deleted = Vec::new();
+    for line in stdout.lines() {
+        let mut columns = line.splitn(2, '\t');
+        let status = columns.next().unwrap_or("");
+        let Some(rest) = columns.next() else {
+            continue;
+        };
+        if status.starts_with('R') || status.starts_with('C') {
+            // Rename/copy lines are `R100\told/path\tnew/path`: `rest`
+            // above only peeled the status column off, so it still holds
+            // both paths tab-separated. Re-index the new path, and drop
+            // the old path's chunks like a delete so they don't linger
+            // under a name the tree no longer has.
+            let mut paths = rest.splitn(2, '\t');
+            let Some(old_path) = paths.next() else {
+                continue;
+            };
+            let Some(new_path) = paths.next() else {
+                continue;
+            };
+            deleted.push(old_path.to_string());
+            added_or_modified.push(new_path.to_string());
+        } else if status.starts_with('D') {
+            deleted.push(rest.to_string());
+        } else {
+            // `A`/`M` need re-indexing.
+            added_or_modified.push(rest.to_string());
+        }
+    }
+    Some(ChangedPaths {
+        added_or_modified,
+        deleted,
+    })
+}
+
+/// Paths to re-index and paths to drop, per the current uncommitted
+/// (`git status --porcelain`) working-tree state.
+fn dirty_paths(root_dir: &Path) -> Option<(Vec<String>, Vec<String>)> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_dir)
+        .args(["status", "--porcelain"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let mut added_or_modified = Vec::new();
+    let mut deleted = Vec::new();
+    for line in stdout.lines() {
+        let Some(status) = line.get(0..2) else {
+            continue;
+        };
+        let Some(rest) = line.get(3..) else {
+            continue;
+        };
+        if status.contains('R') {
+            // Rename lines are `R  old/path -> new/path`; without this,
+            // the whole `"old/path -> new/path"` string got treated as one
+            // bogus path.
+            if let Some((old_path, new_path)) = rest.split_once(" -> ") {
+                deleted.push(old_path.to_string());
+                added_or_modified.push(new_path.to_string());
+                continue;
+            }
+        }
+        if status.contains('D') {
+            deleted.push(rest.to_string());
+            continue;
+        }
+        added_or_modified.push(rest.to_string());
+    }
+    Some((added_or_modified, deleted))
+}
@@ -0,0 +1,1323 @@
+use std::fs;
+use std::time::Instant;
+
+use codex_code_chunker::Chunk;
+use codex_code_chunker::Chunker;
+use codex_code_chunker::ChunkerConfig;
+use codex_embeddings::EmbeddingConfig;
+use codex_embeddings::EmbeddingService;
+use codex_vector_store::CodeChunk;
+use codex_vector_store::VectorStore;
+use futures::Stream;
+use rayon::ThreadPool;
+use rayon::ThreadPoolBuilder;
+use rayon::iter::IntoParallelRefIterator;
+use rayon::iter::ParallelIterator;
+
+use crate::cache::EmbeddingCache;
+use crate::config::IndexerConfig;
+use crate::error::IndexerError;
+use crate::file_hashes::FileHashes;
+use crate::git_change_detection;
+use crate::git_change_detection::GitState;
+use crate::progress::IndexPhase;
+use crate::progress::IndexProgress;
+use crate::progress::ProgressCallback;
+use crate::stats::ChangeDetection;
+use crate::stats::FailedFile;
+use crate::stats::IndexStats;
+use crate::stats::IngestSummary;
+use crate::stats::SkippedFile;
+
+const MODEL_NAME: &str = "feature-hash-v1";
+
+/// Walks a repository, chunks its files, embeds the chunks, and stores them
+/// in a [`VectorStore`] - reusing embeddings for content that hasn't changed.
+pub struct CodebaseIndexer {
+    config: IndexerConfig,
+    chunker: Chunker,
+    embeddings: EmbeddingService,
+    cache: EmbeddingCache,
+    file_hashes: FileHashes,
+    git_state: GitState,
+    /// Bounds how many files [`Self::index`] reads and chunks at once - see
+    /// [`IndexerConfig::max_concurrent_files`]. `None` when that bound is
+    /// `1`, so the read/chunk pass simply runs sequentially rather than
+    /// paying for a one-thread pool.
+    file_pool: Option<ThreadPool>,
+}
+
+impl CodebaseIndexer {
+    pub fn new(config: IndexerConfig) -> Result<Self, IndexerError> {
+        let cache = EmbeddingCache::load(&config.index_dir)?;
+        let file_hashes = FileHashes::load(&config.index_dir)?;
+        let git_state = GitState::load(&config.index_dir)?;
+        let embeddings = EmbeddingService::with_config(EmbeddingConfig {
+            max_parallel_batches: config.max_concurrency,
+            ..EmbeddingConfig::default()
+        });
+        let max_concurrent_files = config.max_concurrent_files.unwrap_or(config.max_concurrency);
+        let file_pool = (max_concurrent_files > 1).then(|| {
+            ThreadPoolBuilder::new()
+                .num_threads(max_concurrent_files)
+                .build()
+                .expect("building a bounded rayon pool")
+        });
+        Ok(Self {
+            config,
+            chunker: Chunker::new(ChunkerConfig::default()),
+            embeddings,
+            cache,
+            file_hashes,
+            git_state,
+            file_pool,
+        })
+    }
+
+    /// Indexes every regular file under `root_dir`, writing chunks and
+    /// embeddings into `store`. Returns a summary of the work performed.
+    ///
+    /// In a git work tree, the walk itself is narrowed to whatever
+    /// `git diff --name-status` (plus the dirty working tree) reports as
+    /// changed since the commit the last run indexed at, rather than every
+    /// file under `root_dir` - see [`IndexStats::change_detection`]. A repo
+    /// with no `.git`, no prior run, or an old commit git can no longer
+    /// diff against (e.g. after a rebase or `git gc`) falls back to walking
+    /// everything.
+    ///
+    /// A file whose content hash matches the last run's is read only to hash
+    /// it - not re-chunked or re-embedded - and counted in
+    /// [`IndexStats::files_unchanged`] instead of [`IndexStats::files_indexed`].
+    ///
+    /// Every other file is read and chunked up front - up to
+    /// [`IndexerConfig::max_concurrent_files`] of them at once - then
+    /// embedded in a single batched call to the `EmbeddingService` (bounded
+    /// separately by [`IndexerConfig::max_concurrency`]) instead of one call
+    /// per chunk, so the embedding service's thread pool stays busy across
+    /// files rather than idling between tiny per-chunk calls. Chunk order
+    /// within each file is preserved regardless of how either pass is
+    /// parallelized internally.
+    ///
+    /// A file whose chunks fail to write to `store` is recorded in
+    /// [`IndexStats::files_failed`] rather than aborting the run - every
+    /// other file is still indexed.
+    ///
+    /// `file_hashes.json` is persisted right after each file's chunks are
+    /// durably written to `store` (which persists itself on every
+    /// mutation), rather than once in bulk at the end of the run. A process
+    /// that crashes partway through therefore leaves the two in sync: on
+    /// restart, every file committed before the crash is seen as unchanged
+    /// and skipped, and every file after it is indexed exactly once - no
+    /// file is silently left out, and none is upserted twice.
+    ///
+    /// Under [`IndexerConfig::dry_run`], files are still walked and chunked
+    /// so [`IndexStats`] reflects the real scope of the work, but the
+    /// embedding service is never called and neither `store` nor the
+    /// on-disk embedding cache is written to.
+    pub fn index(
+        &mut self,
+        store: &mut VectorStore,
+        progress: Option<ProgressCallback<'_>>,
+    ) -> Result<IndexStats, IndexerError> {
+        if let Some(callback) = progress {
+            callback(IndexPhase::Scanning);
+        }
+
+        let mut stats = IndexStats::default();
+        stats.embedding_cache_reset = self.cache.take_reset_flag();
+        stats.active_embedding_provider = self.embeddings.active_provider();
+        let scan_started = Instant::now();
+        let (mut files, files_skipped) = walk_files(&self.config)?;
+        stats.files_skipped_ignored = files_skipped.len();
+        stats.files_skipped = files_skipped;
+
+        // A repository with no `.git` (or no `git` binary on `PATH`) simply
+        // never gets a baseline commit, so this never fires there - every
+        // run falls back to `ChangeDetection::Full` as a result.
+        let head = git_change_detection::current_head(&self.config.root_dir);
+        if let Some(head) = &head {
+            if let Some(old) = self.git_state.last_indexed_commit() {
+                if let Some(changed) = git_change_detection::changed_paths_since(
+                    &self.config.root_dir,
+                    old,
+                    head,
+                ) {
+                    let wanted: std::collections::HashSet<String> =
+                        changed.added_or_modified.into_iter().collect();
+                    files.retain(|path| {
+                        let relative = path
+                            .strip_prefix(&self.config.root_dir)
+                            .unwrap_or(path)
+                            .to_string_lossy()
+                            .to_string();
+                        wanted.contains(&relative)
+                    });
+                    if !self.config.dry_run {
+                        for deleted in &changed.deleted {
+                            store.delete_by_path(deleted)?;
+                        }
+                    }
+                    stats.change_detection = ChangeDetection::GitDiff;
+                }
+            }
+        }
+        stats.scanning_duration = scan_started.elapsed();
+
+        // One entry per file with at least one chunk: the file's relative
+        // path, its content hash, its chunks, and (once embedded) one
+        // embedding per chunk, `None` until filled in by the batched
+        // embedding pass below.
+        let mut pending: Vec<(String, String, Vec<Chunk>, Vec<Option<Vec<f32>>>)> = Vec::new();
+        // Chunks not already in the cache, queued for the single batched
+        // `embed` call, alongside where their result belongs in `pending`.
+        let mut to_embed_content: Vec<String> = Vec::new();
+        let mut to_embed_targets: Vec<(usize, usize)> = Vec::new();
+
+        let chunking_started = Instant::now();
+        // Reading and chunking is the CPU/IO-bound part of this pass, and
+        // each file's outcome only depends on shared, read-only state
+        // (`self.config`, `self.chunker`, `self.file_hashes`), so it's safe
+        // to run up to `IndexerConfig::max_concurrent_files` of them at
+        // once. Everything that mutates `self` or `stats` happens
+        // afterwards, in the sequential loop below, in file order.
+        let config = &self.config;
+        let chunker = &self.chunker;
+        let file_hashes = &self.file_hashes;
+        let reads: Vec<FileRead> = run_bounded(self.file_pool.as_ref(), &files, |entry| {
+            let relative = entry
+                .strip_prefix(&config.root_dir)
+                .unwrap_or(entry)
+                .to_string_lossy()
+                .to_string();
+            let Ok(content) = fs::read_to_string(entry) else {
+                // Skip files that aren't valid UTF-8 (e.g. binaries).
+                return FileRead::NotUtf8;
+            };
+            let file_hash = FileHashes::hash_of(&content);
+            if file_hashes.get(&relative) == Some(&file_hash) {
+                return FileRead::Unchanged;
+            }
+            let chunks = chunker.chunk_str(&content, Some(&relative));
+            FileRead::Parsed { file_hash, chunks }
+        });
+
+        for (entry, read) in files.iter().zip(reads) {
+            let relative = entry
+                .strip_prefix(&self.config.root_dir)
+                .unwrap_or(entry)
+                .to_string_lossy()
+                .to_string();
+            let (file_hash, chunks) = match read {
+                FileRead::NotUtf8 => continue,
+                FileRead::Unchanged => {
+                    stats.files_unchanged += 1;
+                    continue;
+                }
+                FileRead::Parsed { file_hash, chunks } => (file_hash, chunks),
+            };
+
+            if let Some(callback) = progress {
+                callback(IndexPhase::Chunking {
+                    path: relative.clone(),
+                });
+            }
+            if chunks.is_empty() {
+                if !self.config.dry_run {
+                    self.file_hashes.insert(relative, file_hash);
+                    self.file_hashes.persist()?;
+                }
+                continue;
+            }
+            stats.chunks_indexed += chunks.len();
+            stats.estimated_tokens += chunks
+                .iter()
+                .filter_map(|chunk| chunk.metadata.estimated_tokens)
+                .sum::<usize>();
+
+            if self.config.dry_run {
+                stats.files_indexed += 1;
+                continue;
+            }
+
+            let file_idx = pending.len();
+            let mut embeddings = Vec::with_capacity(chunks.len());
+            for (chunk_idx, chunk) in chunks.iter().enumerate() {
+                let key =
+                    EmbeddingCache::key(&chunk.content, MODEL_NAME, self.embeddings.dimension());
+                if let Some(cached) = self.cache.get(&key) {
+                    embeddings.push(Some(cached.clone()));
+                    stats.embeddings_reused += 1;
+                } else {
+                    embeddings.push(None);
+                    to_embed_content.push(chunk.content.clone());
+                    to_embed_targets.push((file_idx, chunk_idx));
+                }
+            }
+            pending.push((relative.clone(), file_hash, chunks, embeddings));
+
+            if let Some(callback) = progress {
+                callback(IndexPhase::Embedding { path: relative });
+            }
+        }
+        stats.chunking_duration = chunking_started.elapsed();
+
+        if self.config.dry_run {
+            if let Some(callback) = progress {
+                callback(IndexPhase::Done);
+            }
+            return Ok(stats);
+        }
+
+        let embedding_started = Instant::now();
+        if !to_embed_content.is_empty() {
+            let computed = self.embeddings.embed(&to_embed_content);
+            for ((file_idx, chunk_idx), embedding) in to_embed_targets.into_iter().zip(computed) {
+                let key = EmbeddingCache::key(
+                    &pending[file_idx].2[chunk_idx].content,
+                    MODEL_NAME,
+                    self.embeddings.dimension(),
+                );
+                self.cache.insert(key, embedding.clone());
+                pending[file_idx].3[chunk_idx] = Some(embedding);
+                stats.embeddings_computed += 1;
+            }
+        }
+        stats.embedding_duration = embedding_started.elapsed();
+
+        let storing_started = Instant::now();
+        for (relative, file_hash, chunks, embeddings) in pending {
+            let embeddings: Vec<Vec<f32>> = embeddings
+                .into_iter()
+                .map(|embedding| embedding.expect("every chunk was either cached or just embedded"))
+                .collect();
+            let code_chunks: Vec<CodeChunk> = chunks
+                .into_iter()
+                .map(|chunk| CodeChunk {
+                    path: relative.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    content: chunk.content,
+                    language: chunk.metadata.language,
+                    chunk_type: chunk.metadata.chunk_type,
+                    symbol_name: chunk.metadata.symbol_name,
+                    content_hash: chunk.metadata.content_hash,
+                    chunker_version: chunk.metadata.chunker_version,
+                    doc_summary: chunk.metadata.doc_summary,
+                    context_imports: chunk.metadata.context_imports,
+                    estimated_tokens: chunk.metadata.estimated_tokens,
+                })
+                .collect();
+            match store.upsert_chunks(code_chunks, embeddings) {
+                Ok(()) => {
+                    stats.files_indexed += 1;
+                    // Committed to `store` (which persists itself on every
+                    // mutation) and to `file_hashes` in the same breath, so a
+                    // crash right after this line still leaves the two
+                    // durably in sync: a restart sees this file as unchanged
+                    // and moves on instead of re-embedding or, worse,
+                    // re-upserting it.
+                    self.file_hashes.insert(relative, file_hash);
+                    self.file_hashes.persist()?;
+                }
+                Err(err) => stats.files_failed.push(FailedFile {
+                    path: relative,
+                    error: err.to_string(),
+                }),
+            }
+        }
+        stats.storing_duration = storing_started.elapsed();
+
+        self.cache.persist()?;
+        if let Some(head) = head {
+            self.git_state.set_last_indexed_commit(head);
+            self.git_state.persist()?;
+        }
+        if let Some(callback) = progress {
+            callback(IndexPhase::Done);
+        }
+        Ok(stats)
+    }
+
+    /// Like [`Self::index`], but emits one [`IndexProgress`] event per file
+    /// (and per failure) instead of blocking until the whole repository is
+    /// indexed. The final item is always `IndexProgress::Done`.
+    ///
+    /// Dropping the returned stream before it's exhausted stops indexing
+    /// promptly: each file is only queued up for processing once the
+    /// previous item has been consumed, so no work happens ahead of the
+    /// caller's next poll.
+    pub fn index_stream<'a>(
+        &'a mut self,
+        store: &'a mut VectorStore,
+    ) -> impl Stream<Item = IndexProgress> + 'a {
+        async_stream::stream! {
+            let mut stats = IndexStats::default();
+            stats.embedding_cache_reset = self.cache.take_reset_flag();
+            stats.active_embedding_provider = self.embeddings.active_provider();
+            let files = match walk_files(&self.config) {
+                Ok((files, files_skipped)) => {
+                    stats.files_skipped_ignored = files_skipped.len();
+                    stats.files_skipped = files_skipped;
+                    files
+                }
+                Err(err) => {
+                    yield IndexProgress::FileFailed {
+                        path: self.config.root_dir.to_string_lossy().to_string(),
+                        error: err.to_string(),
+                    };
+                    yield IndexProgress::Done(stats);
+                    return;
+                }
+            };
+            let files_total = files.len();
+            let mut files_done = 0usize;
+
+            for entry in files {
+                let relative = entry
+                    .strip_prefix(&self.config.root_dir)
+                    .unwrap_or(&entry)
+                    .to_string_lossy()
+                    .to_string();
+
+                yield IndexProgress::FileStarted { path: relative.clone() };
+
+                let Ok(content) = fs::read_to_string(&entry) else {
+                    // Skip files that aren't valid UTF-8 (e.g. binaries).
+                    files_done += 1;
+                    yield IndexProgress::FileCompleted {
+                        current_file: Some(relative),
+                        files_done,
+                        files_total,
+                        chunks_emitted: stats.chunks_indexed,
+                    };
+                    continue;
+                };
+
+                let chunks = self.chunker.chunk_str(&content, Some(&relative));
+                if chunks.is_empty() {
+                    files_done += 1;
+                    yield IndexProgress::FileCompleted {
+                        current_file: Some(relative),
+                        files_done,
+                        files_total,
+                        chunks_emitted: stats.chunks_indexed,
+                    };
+                    continue;
+                }
+                yield IndexProgress::FileChunked {
+                    path: relative.clone(),
+                    chunk_count: chunks.len(),
+                };
+
+                let mut embeddings = Vec::with_capacity(chunks.len());
+                for chunk in &chunks {
+                    let key =
+                        EmbeddingCache::key(&chunk.content, MODEL_NAME, self.embeddings.dimension());
+                    if let Some(cached) = self.cache.get(&key) {
+                        embeddings.push(cached.clone());
+                        stats.embeddings_reused += 1;
+                    } else {
+                        let embedding = self.embeddings.embed(&[chunk.content.clone()]).remove(0);
+                        self.cache.insert(key, embedding.clone());
+                        embeddings.push(embedding);
+                        stats.embeddings_computed += 1;
+                    }
+                }
+
+                let code_chunks: Vec<CodeChunk> = chunks
+                    .into_iter()
+                    .map(|chunk| CodeChunk {
+                        path: relative.clone(),
+                        start_line: chunk.start_line,
+                        end_line: chunk.end_line,
+                        content: chunk.content,
+                        language: chunk.metadata.language,
+                        chunk_type: chunk.metadata.chunk_type,
+                        symbol_name: chunk.metadata.symbol_name,
+                        content_hash: chunk.metadata.content_hash,
+                        chunker_version: chunk.metadata.chunker_version,
+                        doc_summary: chunk.metadata.doc_summary,
+                    context_imports: chunk.metadata.context_imports,
+                    estimated_tokens: chunk.metadata.estimated_tokens,
+                    })
+                    .collect();
+                stats.chunks_indexed += code_chunks.len();
+                if let Err(err) = store.upsert_chunks(code_chunks, embeddings) {
+                    files_done += 1;
+                    yield IndexProgress::FileFailed {
+                        path: relative.clone(),
+                        error: err.to_string(),
+                    };
+                    yield IndexProgress::FileCompleted {
+                        current_file: Some(relative),
+                        files_done,
+                        files_total,
+                        chunks_emitted: stats.chunks_indexed,
+                    };
+                    continue;
+                }
+                stats.files_indexed += 1;
+                files_done += 1;
+                yield IndexProgress::FileEmbedded { path: relative.clone() };
+                yield IndexProgress::FileCompleted {
+                    current_file: Some(relative),
+                    files_done,
+                    files_total,
+                    chunks_emitted: stats.chunks_indexed,
+                };
+            }
+
+            if let Err(err) = self.cache.persist() {
+                yield IndexProgress::FileFailed {
+                    path: self.config.index_dir.to_string_lossy().to_string(),
+                    error: err.to_string(),
+                };
+            }
+            yield IndexProgress::Done(stats);
+        }
+    }
+
+    /// Drops every cached embedding and the per-file content hashes used to
+    /// skip unchanged files, e.g. after switching embedding models - so the
+    /// next [`Self::index`] fully reprocesses every file instead of the
+    /// content-hash skip short-circuiting before the cache is even consulted.
+    pub fn clear_embedding_cache(&mut self) -> Result<(), IndexerError> {
+        self.file_hashes.clear()?;
+        self.cache.clear()
+    }
+
+    /// Re-indexes exactly the files in `paths` (relative to
+    /// `self.config.root_dir`), instead of walking the whole repository like
+    /// [`Self::index`]. `paths` should already be in the priority order the
+    /// caller wants them processed in (e.g. most-recently-modified first);
+    /// this method doesn't reorder them.
+    ///
+    /// A path that no longer exists on disk is treated as a deletion - its
+    /// chunks are removed from `store` via [`VectorStore::delete_by_path`]
+    /// rather than being left stale - which is what makes this suited to CI
+    /// integrations that pass the exact set of files a PR touched, additions
+    /// and removals alike.
+    ///
+    /// This is the building block for "reindex pending files" workflows,
+    /// e.g. re-indexing [`VectorStore::find_stale`]'s output after a chunker
+    /// upgrade. A daemon that watches the filesystem and maintains its own
+    /// prioritized pending list, a wire protocol for triggering this
+    /// remotely, and a health store recording each run aren't implemented in
+    /// this codebase yet; this method is the local, synchronous operation
+    /// those would call into.
+    pub fn reindex_paths(
+        &mut self,
+        store: &mut VectorStore,
+        paths: &[String],
+    ) -> Result<IngestSummary, IndexerError> {
+        let mut summary = IngestSummary::default();
+        for relative in paths {
+            let entry = self.config.root_dir.join(relative);
+            if !entry.exists() {
+                store.delete_by_path(relative)?;
+                summary.files_deleted += 1;
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(&entry) else {
+                summary.files_skipped.push(SkippedFile {
+                    path: relative.clone(),
+                    reason: "not valid UTF-8".to_string(),
+                });
+                continue;
+            };
+
+            let chunks = self.chunker.chunk_str(&content, Some(relative));
+            if chunks.is_empty() {
+                summary.files_skipped.push(SkippedFile {
+                    path: relative.clone(),
+                    reason: "produced no chunks".to_string(),
+                });
+                continue;
+            }
+
+            let mut embeddings = Vec::with_capacity(chunks.len());
+            for chunk in &chunks {
+                let key = EmbeddingCache::key(&chunk.content, MODEL_NAME, self.embeddings.dimension());
+                if let Some(cached) = self.cache.get(&key) {
+                    embeddings.push(cached.clone());
+                } else {
+                    let embedding = self.embeddings.embed(&[chunk.content.clone()]).remove(0);
+                    self.cache.insert(key, embedding.clone());
+                    embeddings.push(embedding);
+                }
+            }
+
+            let code_chunks: Vec<CodeChunk> = chunks
+                .into_iter()
+                .map(|chunk| CodeChunk {
+                    path: relative.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    content: chunk.content,
+                    language: chunk.metadata.language,
+                    chunk_type: chunk.metadata.chunk_type,
+                    symbol_name: chunk.metadata.symbol_name,
+                    content_hash: chunk.metadata.content_hash,
+                    chunker_version: chunk.metadata.chunker_version,
+                    doc_summary: chunk.metadata.doc_summary,
+                    context_imports: chunk.metadata.context_imports,
+                    estimated_tokens: chunk.metadata.estimated_tokens,
+                })
+                .collect();
+            store.upsert_chunks(code_chunks, embeddings)?;
+            summary.files_indexed += 1;
+        }
+
+        self.cache.persist()?;
+        Ok(summary)
+    }
+}
+
+/// Outcome of reading and chunking one file, produced by the bounded
+/// read/chunk pass in [`CodebaseIndexer::index`].
+enum FileRead {
+    /// Not valid UTF-8 (e.g. a binary file).
+    NotUtf8,
+    /// Content hash matched the last run's - not even chunked.
+    Unchanged,
+    /// Chunked (possibly into zero chunks, e.g. an empty file).
+    Parsed { file_hash: String, chunks: Vec<Chunk> },
+}
+
+/// Runs `work` over `items`, using up to `pool`'s thread count at once, or
+/// sequentially if `pool` is `None` - mirrors
+/// `codex_embeddings::EmbeddingService`'s own bounded-pool-or-sequential
+/// split for the same reason: a pool is only worth paying for above one
+/// thread.
+fn run_bounded<I, T, F>(pool: Option<&ThreadPool>, items: &[I], work: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    match pool {
+        Some(pool) => pool.install(|| items.par_iter().map(|item| work(item)).collect()),
+        None => items.iter().map(|item| work(item)).collect(),
+    }
+}
+
+/// Walks `config.root_dir`, honoring `.gitignore` and the configured
+/// exclude/include globs. Returns the files to index alongside the ones that
+/// were skipped because they were ignored, and why.
+fn walk_files(
+    config: &IndexerConfig,
+) -> Result<(Vec<std::path::PathBuf>, Vec<SkippedFile>), IndexerError> {
+    let all_files = walk_all_files(&config.root_dir)?;
+
+    let mut override_builder = ignore::overrides::OverrideBuilder::new(&config.root_dir);
+    for exclude in &config.exclude_globs {
+        override_builder
+            .add(&format!("!{exclude}"))
+            .map_err(|source| IndexerError::InvalidGlob {
+                glob: exclude.clone(),
+                source,
+            })?;
+    }
+    // Include globs are matched after the walk: as walker overrides they'd
+    // act as a whitelist, and the walker would drop every file matching none.
+    let mut include_builder = ignore::overrides::OverrideBuilder::new(&config.root_dir);
+    for include in &config.include_globs {
+        include_builder
+            .add(include)
+            .map_err(|source| IndexerError::InvalidGlob {
+                glob: include.clone(),
+                source,
+            })?;
+    }
+    let overrides = override_builder
+        .build()
+        .map_err(|source| IndexerError::InvalidGlob {
+            glob: "<override set>".to_string(),
+            source,
+        })?;
+    let includes = include_builder
+        .build()
+        .map_err(|source| IndexerError::InvalidGlob {
+            glob: "<include set>".to_string(),
+            source,
+        })?;
+
+    let mut walk_builder = ignore::WalkBuilder::new(&config.root_dir);
+    walk_builder
+        .overrides(overrides.clone())
+        // `root_dir` is an arbitrary directory to index, not necessarily a
+        // git checkout, so `.gitignore` files should be honored even when no
+        // `.git` directory is present.
+        .require_git(false);
+    if !config.respect_gitignore {
+        walk_builder
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .ignore(false)
+            .parents(false);
+    }
+
+    let mut files = Vec::new();
+    for entry in walk_builder.build() {
+        let entry = entry.map_err(|source| IndexerError::Walk {
+            path: config.root_dir.clone(),
+            source,
+        })?;
+        if entry.file_type().is_some_and(|ft| ft.is_file()) {
+            files.push(entry.into_path());
+        }
+    }
+    for path in &all_files {
+        if includes.matched(path, false).is_whitelist() && !files.contains(path) {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+
+    // Only used to tell a glob-excluded file apart from a gitignored one
+    // below - `overrides` only ever filters files, so re-running it against
+    // the handful of already-skipped paths is cheap relative to the walk
+    // itself.
+    let kept: std::collections::HashSet<&std::path::PathBuf> = files.iter().collect();
+    let skipped = all_files
+        .into_iter()
+        .filter(|path| !kept.contains(path))
+        .map(|path| {
+            let reason = if overrides.matched(&path, false).is_ignore() {
+                "matched an exclude_globs pattern".to_string()
+            } else {
+                "matched .gitignore".to_string()
+            };
+            SkippedFile {
+                path: path.to_string_lossy().to_string(),
+                reason,
+            }
+        })
+        .collect();
+    Ok((files, skipped))
+}
+
+fn walk_all_files(root: &std::path::Path) -> Result<Vec<std::path::PathBuf>, IndexerError> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = fs::read_dir(&dir).map_err(|source| IndexerError::Io {
+            path: dir.clone(),
+            source,
+        })?;
+        for entry in entries {
+            let entry = entry.map_err(|source| IndexerError::Io {
+                path: dir.clone(),
+                source,
+            })?;
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_vector_store::VectorStoreConfig;
+
+    #[test]
+    fn reindexing_unchanged_files_skips_them_entirely() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let first = indexer.index(&mut store, None).unwrap();
+        assert_eq!(first.embeddings_computed, 1);
+        assert_eq!(first.files_indexed, 1);
+        assert_eq!(first.files_unchanged, 0);
+
+        // Re-create the indexer to force it to reload the content hashes
+        // from disk, mirroring a `git checkout` that leaves content
+        // untouched but bumps mtimes.
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let second = indexer.index(&mut store, None).unwrap();
+        assert_eq!(second.embeddings_computed, 0, "no file changed, so nothing is re-embedded");
+        assert_eq!(second.embeddings_reused, 0, "the file was never even re-chunked");
+        assert_eq!(second.files_indexed, 0);
+        assert_eq!(second.files_unchanged, 1);
+    }
+
+    #[test]
+    fn an_unrelated_file_with_identical_chunk_content_still_reuses_the_embedding() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+        indexer.index(&mut store, None).unwrap();
+
+        // A different, never-before-seen file whose only chunk happens to
+        // have the exact same content - the per-chunk embedding cache, not
+        // the per-file content-hash skip, is what makes this free.
+        fs::write(root.path().join("b.rs"), "fn a() {}\n").unwrap();
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let second = indexer.index(&mut store, None).unwrap();
+        assert_eq!(second.files_unchanged, 1, "a.rs");
+        assert_eq!(second.files_indexed, 1, "b.rs");
+        assert_eq!(second.embeddings_computed, 0);
+        assert_eq!(second.embeddings_reused, 1, "b.rs's chunk reuses a.rs's embedding");
+    }
+
+    #[test]
+    fn git_diff_narrows_the_walk_to_what_changed_since_the_last_run() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(root.path())
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?} failed");
+        };
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("c.rs"), "fn c() {}\n").unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "test"]);
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "initial"]);
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let first = indexer.index(&mut store, None).unwrap();
+        assert_eq!(first.files_indexed, 3);
+        assert_eq!(first.change_detection, ChangeDetection::Full, "no baseline commit yet");
+
+        // b.rs is modified and c.rs is deleted, both committed; a.rs is untouched.
+        fs::write(root.path().join("b.rs"), "fn b_changed() {}\n").unwrap();
+        fs::remove_file(root.path().join("c.rs")).unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "change b, delete c"]);
+
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let second = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(second.change_detection, ChangeDetection::GitDiff);
+        assert_eq!(second.files_indexed, 1, "only b.rs was walked and re-embedded");
+        assert_eq!(second.files_unchanged, 0, "a.rs was never even walked, not just skipped");
+        assert!(store.chunks().any(|chunk| chunk.path == "a.rs"), "a.rs's old chunks remain");
+        assert!(!store.chunks().any(|chunk| chunk.path == "c.rs"), "c.rs was deleted");
+    }
+
+    #[test]
+    fn git_diff_reindexes_a_renamed_file_under_its_new_path() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(root.path())
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?} failed");
+        };
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "test"]);
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "initial"]);
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+        indexer.index(&mut store, None).unwrap();
+        assert!(store.chunks().any(|chunk| chunk.path == "a.rs"));
+
+        git(&["mv", "a.rs", "renamed.rs"]);
+        git(&["commit", "-q", "-m", "rename a.rs to renamed.rs"]);
+
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let second = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(second.change_detection, ChangeDetection::GitDiff);
+        assert_eq!(second.files_indexed, 1, "renamed.rs was walked and re-embedded");
+        assert!(store.chunks().any(|chunk| chunk.path == "renamed.rs"), "new path is indexed");
+        assert!(!store.chunks().any(|chunk| chunk.path == "a.rs"), "old path's chunks are gone");
+    }
+
+    #[test]
+    fn a_crash_after_committing_some_files_is_resumed_without_duplicating_them() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("c.rs"), "fn c() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        // Simulate a process that crashed after committing a.rs to `store`
+        // but before b.rs or c.rs: write exactly the durable state the real
+        // loop would have left behind for that one file, by hand.
+        let a_content = fs::read_to_string(root.path().join("a.rs")).unwrap();
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let partial = indexer
+            .reindex_paths(&mut store, &["a.rs".to_string()])
+            .unwrap();
+        assert_eq!(partial.files_indexed, 1);
+        fs::write(
+            index_dir.path().join("file_hashes.json"),
+            format!(
+                r#"{{"version":1,"hashes":{{"a.rs":"{}"}}}}"#,
+                FileHashes::hash_of(&a_content)
+            ),
+        )
+        .unwrap();
+
+        // Restart: a fresh indexer reloads that durable state from disk.
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let resumed = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(resumed.files_unchanged, 1, "a.rs was already committed");
+        assert_eq!(resumed.files_indexed, 2, "b.rs and c.rs complete the run");
+        assert_eq!(
+            store.chunks().filter(|chunk| chunk.path == "a.rs").count(),
+            1,
+            "a.rs was not re-upserted, so it isn't duplicated"
+        );
+        assert!(store.chunks().any(|chunk| chunk.path == "b.rs"));
+        assert!(store.chunks().any(|chunk| chunk.path == "c.rs"));
+    }
+
+    #[test]
+    fn clear_embedding_cache_forces_recompute() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        indexer.index(&mut store, None).unwrap();
+        indexer.clear_embedding_cache().unwrap();
+        let after_clear = indexer.index(&mut store, None).unwrap();
+        assert_eq!(after_clear.embeddings_computed, 1);
+    }
+
+    #[test]
+    fn a_corrupt_or_incompatible_embedding_cache_resets_instead_of_failing() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(index_dir.path().join("embedding_cache.json"), "{ not json").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config.clone()).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let first = indexer.index(&mut store, None).unwrap();
+        assert!(first.embedding_cache_reset, "corrupt JSON resets the cache");
+        assert_eq!(first.embeddings_computed, 1);
+
+        // The flag only fires once per `CodebaseIndexer` - and the cache
+        // `persist`ed by the run above is valid, so a fresh load shouldn't
+        // reset again either.
+        let second = indexer.index(&mut store, None).unwrap();
+        assert!(!second.embedding_cache_reset);
+
+        fs::write(
+            index_dir.path().join("embedding_cache.json"),
+            r#"{"version":999,"entries":{}}"#,
+        )
+        .unwrap();
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let third = indexer.index(&mut store, None).unwrap();
+        assert!(third.embedding_cache_reset, "a future version resets too");
+    }
+
+    #[tokio::test]
+    async fn index_stream_emits_per_file_events_and_ends_with_done() {
+        use futures::StreamExt as _;
+
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let events: Vec<IndexProgress> = indexer.index_stream(&mut store).collect().await;
+
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, IndexProgress::FileStarted { path } if path == "a.rs"))
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, IndexProgress::FileEmbedded { path } if path == "a.rs"))
+        );
+        match events.last() {
+            Some(IndexProgress::Done(stats)) => assert_eq!(stats.files_indexed, 1),
+            other => panic!("expected stream to end with Done, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn index_stream_can_be_dropped_early_without_finishing() {
+        use futures::StreamExt as _;
+
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let first = indexer.index_stream(&mut store).next().await;
+        assert!(matches!(first, Some(IndexProgress::FileStarted { .. })));
+        // Dropping the stream here must not panic or leave the indexer in a
+        // state that breaks a subsequent, fresh `index_stream` call.
+        let events: Vec<IndexProgress> = indexer.index_stream(&mut store).collect().await;
+        assert!(matches!(events.last(), Some(IndexProgress::Done(_))));
+    }
+
+    #[tokio::test]
+    async fn index_stream_emits_a_file_completed_event_with_monotonic_files_done() {
+        use futures::StreamExt as _;
+
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("empty.rs"), "").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let events: Vec<IndexProgress> = indexer.index_stream(&mut store).collect().await;
+
+        let completed: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                IndexProgress::FileCompleted {
+                    current_file,
+                    files_done,
+                    files_total,
+                    chunks_emitted,
+                } => Some((current_file.clone(), *files_done, *files_total, *chunks_emitted)),
+                _ => None,
+            })
+            .collect();
+
+        // 3 files on disk, so 3 completions - including empty.rs, which
+        // never reaches `FileChunked`/`FileEmbedded`.
+        assert_eq!(completed.len(), 3);
+        assert!(completed.iter().all(|(_, _, files_total, _)| *files_total == 3));
+        assert!(completed.iter().all(|(current_file, ..)| current_file.is_some()));
+
+        let files_done: Vec<usize> =
+            completed.iter().map(|(_, files_done, ..)| *files_done).collect();
+        assert_eq!(files_done, vec![1, 2, 3], "files_done increases monotonically");
+
+        let chunks_emitted: Vec<usize> =
+            completed.iter().map(|(.., chunks_emitted)| *chunks_emitted).collect();
+        assert!(
+            chunks_emitted.is_sorted(),
+            "chunks_emitted never decreases as files complete: {chunks_emitted:?}",
+        );
+        assert_eq!(*chunks_emitted.last().unwrap(), 2, "a.rs and b.rs each emit one chunk");
+    }
+
+    #[test]
+    fn gitignore_and_exclude_globs_are_skipped_unless_included() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir(root.path().join("target")).unwrap();
+        fs::write(root.path().join("target").join("built.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("kept.rs"), "fn kept() {}\n").unwrap();
+        fs::write(root.path().join("scratch.rs"), "fn scratch() {}\n").unwrap();
+        fs::create_dir(root.path().join("vendor")).unwrap();
+        fs::write(
+            root.path().join("vendor").join("lib.rs"),
+            "fn vendored() {}\n",
+        )
+        .unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path())
+            .with_exclude_globs(vec!["scratch.rs".to_string()])
+            .with_include_globs(vec!["vendor/**".to_string()]);
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let stats = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(stats.files_indexed, 2, "kept.rs and vendor/lib.rs");
+        assert_eq!(stats.files_skipped_ignored, stats.files_skipped.len());
+        assert_eq!(store.search(&[0.0; 768], 10).unwrap().len(), 2);
+
+        let built = stats
+            .files_skipped
+            .iter()
+            .find(|skipped| skipped.path.contains("built.rs"))
+            .expect("target/built.rs was skipped");
+        assert_eq!(built.reason, "matched .gitignore");
+
+        let scratch = stats
+            .files_skipped
+            .iter()
+            .find(|skipped| skipped.path.contains("scratch.rs"))
+            .expect("scratch.rs was skipped");
+        assert_eq!(scratch.reason, "matched an exclude_globs pattern");
+    }
+
+    #[test]
+    fn reindex_paths_indexes_the_given_files_and_reports_skips() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("empty.rs"), "").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let summary = indexer
+            .reindex_paths(
+                &mut store,
+                &[
+                    "a.rs".to_string(),
+                    "empty.rs".to_string(),
+                    "missing.rs".to_string(),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(summary.files_indexed, 1);
+        assert_eq!(summary.files_skipped.len(), 1);
+        assert!(summary.files_skipped.iter().any(|f| f.path == "empty.rs"));
+        assert_eq!(summary.files_deleted, 1);
+        assert_eq!(store.search(&[0.0; 768], 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reindex_paths_deletes_chunks_for_paths_that_no_longer_exist() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+        indexer
+            .reindex_paths(&mut store, &["a.rs".to_string(), "b.rs".to_string()])
+            .unwrap();
+        assert_eq!(store.len(), 2);
+
+        fs::remove_file(root.path().join("b.rs")).unwrap();
+        let summary = indexer
+            .reindex_paths(&mut store, &["a.rs".to_string(), "b.rs".to_string()])
+            .unwrap();
+
+        assert_eq!(summary.files_indexed, 1, "a.rs re-indexed");
+        assert_eq!(summary.files_deleted, 1, "b.rs no longer on disk");
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn reindex_paths_processes_exactly_the_given_subset() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("c.rs"), "fn c() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let summary = indexer
+            .reindex_paths(&mut store, &["a.rs".to_string(), "b.rs".to_string()])
+            .unwrap();
+
+        assert_eq!(summary.files_indexed, 2, "only a.rs and b.rs were requested");
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn dry_run_reports_stats_without_writing_to_the_store_or_cache() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path()).with_dry_run(true);
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let stats = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(stats.files_indexed, 1);
+        assert_eq!(stats.chunks_indexed, 1);
+        assert_eq!(stats.embeddings_computed, 0, "embedding service must not run");
+        assert_eq!(stats.embeddings_reused, 0);
+        assert!(store.is_empty(), "dry run must not write to the store");
+
+        // A real run afterwards must still compute every embedding fresh -
+        // the dry run must not have touched the on-disk embedding cache.
+        let real = indexer.index(&mut store, None).unwrap();
+        assert_eq!(real.embeddings_computed, 1);
+        assert_eq!(real.embeddings_reused, 0);
+    }
+
+    #[test]
+    fn indexing_several_files_embeds_every_uncached_chunk_across_all_of_them() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b_one() {}\nfn b_two() {}\n").unwrap();
+        fs::write(root.path().join("c.rs"), "fn c() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path());
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let stats = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(stats.files_indexed, 3);
+        assert!(stats.files_failed.is_empty());
+        assert_eq!(stats.embeddings_computed, 4, "one per chunk across all 3 files");
+        assert_eq!(stats.embeddings_reused, 0);
+
+        let b_chunks: Vec<_> = store.chunks().filter(|chunk| chunk.path == "b.rs").collect();
+        let mut start_lines: Vec<usize> = b_chunks.iter().map(|chunk| chunk.start_line).collect();
+        let sorted = {
+            start_lines.sort();
+            start_lines
+        };
+        assert_eq!(
+            b_chunks.iter().map(|chunk| chunk.start_line).collect::<Vec<_>>(),
+            sorted,
+            "b.rs's chunks are stored in source order",
+        );
+    }
+
+    #[test]
+    fn run_bounded_with_a_single_thread_never_runs_two_items_concurrently() {
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::atomic::Ordering;
+
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let concurrent = AtomicUsize::new(0);
+        let max_seen = AtomicUsize::new(0);
+        let items: Vec<u32> = (0..20).collect();
+
+        run_bounded(Some(&pool), &items, |_| {
+            let now = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+            max_seen.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            concurrent.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert_eq!(max_seen.load(Ordering::SeqCst), 1, "a single-thread pool serializes work");
+    }
+
+    #[test]
+    fn max_concurrent_files_set_to_one_still_indexes_every_file() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+        fs::write(root.path().join("c.rs"), "fn c() {}\n").unwrap();
+
+        let config =
+            IndexerConfig::new(root.path(), index_dir.path()).with_max_concurrent_files(1);
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let stats = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(stats.files_indexed, 3, "serialized processing still covers every file");
+        assert_eq!(store.len(), 3);
+    }
+
+    #[test]
+    fn max_concurrency_is_configurable_and_does_not_change_indexing_results() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.path().join("b.rs"), "fn b() {}\n").unwrap();
+
+        let config = IndexerConfig::new(root.path(), index_dir.path()).with_max_concurrency(4);
+        let mut indexer = CodebaseIndexer::new(config).unwrap();
+        let mut store =
+            VectorStore::open(VectorStoreConfig::new(index_dir.path().join("store.jsonl"), 768))
+                .unwrap();
+
+        let stats = indexer.index(&mut store, None).unwrap();
+
+        assert_eq!(stats.files_indexed, 2);
+        assert_eq!(stats.embeddings_computed, 2);
+        assert_eq!(store.len(), 2);
+    }
+}
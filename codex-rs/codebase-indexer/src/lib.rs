@@ -0,0 +1,25 @@
+//! Walks a repository, chunks and embeds its files, and stores the result in
+//! a `codex-vector-store` for semantic search.
+
+mod cache;
+mod config;
+mod debounce;
+mod error;
+mod file_hashes;
+mod git_change_detection;
+mod indexer;
+mod progress;
+mod stats;
+
+pub use config::IndexerConfig;
+pub use debounce::ChangeBatcher;
+pub use error::IndexerError;
+pub use indexer::CodebaseIndexer;
+pub use progress::IndexPhase;
+pub use progress::IndexProgress;
+pub use progress::ProgressCallback;
+pub use stats::ChangeDetection;
+pub use stats::FailedFile;
+pub use stats::IndexStats;
+pub use stats::IngestSummary;
+pub use stats::SkippedFile;
@@ -0,0 +1,44 @@
+/// Coarse-grained phase reported to a [`ProgressCallback`] during indexing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexPhase {
+    Scanning,
+    Chunking { path: String },
+    Embedding { path: String },
+    Done,
+}
+
+/// Invoked as a [`crate::CodebaseIndexer::index`] run progresses.
+pub type ProgressCallback<'a> = &'a dyn Fn(IndexPhase);
+
+/// A single event emitted by [`crate::CodebaseIndexer::index_stream`].
+///
+/// Unlike [`IndexPhase`], this carries one event per file (and per failure)
+/// rather than coarse phase transitions, so a caller can drive a live
+/// progress bar. The stream's final item is always `Done`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexProgress {
+    FileStarted { path: String },
+    FileChunked { path: String, chunk_count: usize },
+    FileEmbedded { path: String },
+    FileFailed { path: String, error: String },
+    /// Emitted once per file, right after it's done processing (indexed,
+    /// skipped, or failed) - a summary a caller can use to drive a progress
+    /// bar's throughput without having to track `FileStarted`/`FileChunked`/
+    /// `FileEmbedded`/`FileFailed` itself.
+    FileCompleted {
+        /// The file that was just completed. Always `Some` today - `Option`
+        /// leaves room for a future summary event not tied to one file.
+        current_file: Option<String>,
+        /// How many files have completed so far, including this one -
+        /// strictly increasing across the events of a single
+        /// [`crate::CodebaseIndexer::index_stream`] call.
+        files_done: usize,
+        /// Total files queued for this run, known up front since
+        /// `index_stream` walks the whole repository before processing any
+        /// of them.
+        files_total: usize,
+        /// Chunks emitted so far across every completed file, running total.
+        chunks_emitted: usize,
+    },
+    Done(crate::stats::IndexStats),
+}
@@ -0,0 +1,103 @@
+/// Summary of work done by a single [`crate::CodebaseIndexer::index`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    pub files_indexed: usize,
+    /// Files whose content hash matched the last run's, so they were read
+    /// only to hash them and never re-chunked or re-embedded. Disjoint from
+    /// `files_indexed`.
+    pub files_unchanged: usize,
+    pub chunks_indexed: usize,
+    /// Chunks whose embedding was freshly computed by the `EmbeddingService`.
+    pub embeddings_computed: usize,
+    /// Chunks whose embedding was reused from the content-hash cache.
+    pub embeddings_reused: usize,
+    /// Files skipped because they matched `.gitignore` or `exclude_globs`.
+    /// Equal to `files_skipped.len()`.
+    pub files_skipped_ignored: usize,
+    /// The files counted in `files_skipped_ignored`, alongside why each one
+    /// was skipped.
+    pub files_skipped: Vec<SkippedFile>,
+    /// Sum of [`codex_vector_store::CodeChunk::estimated_tokens`] across
+    /// every chunk indexed, for estimating the cost of a reindex - most
+    /// useful under [`crate::IndexerConfig::dry_run`], where it's the only
+    /// token-cost signal available since chunks are never embedded.
+    pub estimated_tokens: usize,
+    /// Files whose chunks were embedded but failed to write to the
+    /// [`codex_vector_store::VectorStore`], alongside why. Unlike a skipped
+    /// file, these were successfully read and chunked - one file's store
+    /// write failing doesn't stop the rest of the run.
+    pub files_failed: Vec<FailedFile>,
+    /// Wall-clock time spent walking `root_dir` for files to index.
+    pub scanning_duration: std::time::Duration,
+    /// Wall-clock time spent reading and chunking every file - this phase
+    /// handles up to [`crate::IndexerConfig::max_concurrent_files`] files at
+    /// once, on a pool separate from the embedding pass's
+    /// [`crate::IndexerConfig::max_concurrency`].
+    pub chunking_duration: std::time::Duration,
+    /// Wall-clock time spent in the single, batched
+    /// [`codex_embeddings::EmbeddingService::embed`] call covering every
+    /// file's uncached chunks - see [`crate::IndexerConfig::max_concurrency`].
+    pub embedding_duration: std::time::Duration,
+    /// Wall-clock time spent writing every file's chunks and embeddings to
+    /// the [`codex_vector_store::VectorStore`].
+    pub storing_duration: std::time::Duration,
+    /// `true` if [`crate::CodebaseIndexer::new`] discarded an on-disk
+    /// embedding cache that was corrupt, truncated, or written by an
+    /// incompatible schema version - every chunk gets re-embedded as a
+    /// result. Only ever `true` on the first run after construction; later
+    /// runs on the same [`crate::CodebaseIndexer`] always report `false`.
+    pub embedding_cache_reset: bool,
+    /// How this run decided which files needed a closer look. Always
+    /// [`ChangeDetection::Full`] on the very first run, or in any
+    /// repository that isn't a git work tree.
+    pub change_detection: ChangeDetection,
+    /// Which backend [`crate::CodebaseIndexer::new`]'s embedding service
+    /// actually constructed against - see
+    /// [`codex_embeddings::EmbeddingService::active_provider`]. Lets a
+    /// caller log what embedding actually ran on without reaching into the
+    /// indexer's private `EmbeddingService`.
+    pub active_embedding_provider: codex_embeddings::ExecutionProvider,
+}
+
+/// How [`crate::CodebaseIndexer::index`] narrowed down which files to walk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChangeDetection {
+    /// Every file under `root_dir` was walked and compared by content
+    /// hash - the only mode available outside a git work tree.
+    #[default]
+    Full,
+    /// Only the files `git diff --name-status` (plus the dirty working
+    /// tree) reported as changed since the last run were walked; content
+    /// hashes still gate each one individually.
+    GitDiff,
+}
+
+/// A file [`crate::CodebaseIndexer::index`] read and chunked successfully,
+/// but whose chunks couldn't be written to the
+/// [`codex_vector_store::VectorStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedFile {
+    pub path: String,
+    pub error: String,
+}
+
+/// Summary of a single [`crate::CodebaseIndexer::reindex_paths`] run, which
+/// re-indexes a caller-supplied list of paths rather than walking the whole
+/// repository (see [`IndexStats`] for that case).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestSummary {
+    pub files_indexed: usize,
+    pub files_skipped: Vec<SkippedFile>,
+    /// Paths in the caller's list that no longer exist on disk, and were
+    /// removed from the store instead of being skipped - see
+    /// [`crate::CodebaseIndexer::reindex_paths`].
+    pub files_deleted: usize,
+}
+
+/// A path [`crate::CodebaseIndexer::reindex_paths`] declined to (re-)index,
+/// and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
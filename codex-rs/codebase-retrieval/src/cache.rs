@@ -0,0 +1,220 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::result::SearchResults;
+
+/// Hit/miss counters for [`crate::HybridRetrieval`]'s query cache, broken
+/// down by which tier served the hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub memory_hits: usize,
+    pub disk_hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    generation: u64,
+    results: SearchResults,
+}
+
+/// A directory of serialized [`SearchResults`], one file per `(query, limit,
+/// exact_symbols)` combination, so a search result computed by a previous
+/// process invocation can still be served on this one.
+///
+/// Entries are validated against the index generation they were written
+/// under: a generation mismatch - because the index was rebuilt, or because
+/// `RetrievalConfig` fields that affect scoring changed - means the entry is
+/// stale and is discarded rather than served. Once either the entry count or
+/// total size exceeds the configured caps, the least-recently-read entries
+/// (by file mtime) are evicted.
+///
+/// All I/O here is best-effort: a cache is an optimization, not a source of
+/// truth, so any read or write failure (a missing directory, a permissions
+/// error, a corrupt file) is treated as a miss rather than propagated to the
+/// caller.
+pub(crate) struct DiskCache {
+    dir: PathBuf,
+    max_entries: usize,
+    max_bytes: u64,
+}
+
+impl DiskCache {
+    pub(crate) fn new(dir: PathBuf, max_entries: usize, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str, generation: u64) -> Option<SearchResults> {
+        let path = self.entry_path(key);
+        let contents = fs::read(&path).ok()?;
+        let entry: DiskEntry = serde_json::from_slice(&contents).ok()?;
+        if entry.generation != generation {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        touch(&path);
+        Some(entry.results)
+    }
+
+    pub(crate) fn put(&self, key: &str, generation: u64, results: &SearchResults) {
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        let entry = DiskEntry {
+            key: key.to_string(),
+            generation,
+            results: results.clone(),
+        };
+        if let Ok(json) = serde_json::to_vec(&entry) {
+            let _ = fs::write(self.entry_path(key), json);
+        }
+        self.evict_over_cap();
+    }
+
+    /// Every entry in this cache still valid under `generation`, for warming
+    /// an in-memory LRU on startup without the caller having to already know
+    /// which queries were previously cached.
+    pub(crate) fn entries_for_generation(&self, generation: u64) -> Vec<(String, SearchResults)> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        read_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let contents = fs::read(entry.path()).ok()?;
+                let entry: DiskEntry = serde_json::from_slice(&contents).ok()?;
+                (entry.generation == generation).then_some((entry.key, entry.results))
+            })
+            .collect()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(key.as_bytes());
+        self.dir.join(format!("{:x}.json", hasher.finalize()))
+    }
+
+    fn evict_over_cap(&self) {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries: Vec<(PathBuf, SystemTime, u64)> = read_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                let mtime = meta.modified().ok()?;
+                Some((entry.path(), mtime, meta.len()))
+            })
+            .collect();
+
+        let mut count = entries.len();
+        let mut bytes: u64 = entries.iter().map(|(_, _, len)| *len).sum();
+        if count <= self.max_entries && bytes <= self.max_bytes {
+            return;
+        }
+
+        entries.sort_by_key(|(_, mtime, _)| *mtime);
+        for (path, _, len) in entries {
+            if count <= self.max_entries && bytes <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                count -= 1;
+                bytes = bytes.saturating_sub(len);
+            }
+        }
+    }
+}
+
+/// Bumps a file's mtime to "now" so mtime-based LRU eviction treats a
+/// just-read entry as recently used, not just a just-written one.
+fn touch(path: &Path) {
+    if let Ok(file) = OpenOptions::new().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(score: f32) -> SearchResults {
+        use codex_vector_store::CodeChunk;
+
+        use crate::result::SearchResult;
+        use crate::result::SearchSource;
+
+        SearchResults(vec![SearchResult {
+            chunk: CodeChunk {
+                path: "a.rs".to_string(),
+                start_line: 1,
+                end_line: 1,
+                content: "fn a() {}".to_string(),
+                language: None,
+                chunk_type: None,
+                symbol_name: None,
+                content_hash: None,
+                chunker_version: None,
+                doc_summary: None,
+                context_imports: None,
+                estimated_tokens: None,
+            },
+            score,
+            normalized_score: score,
+            source: SearchSource::Both,
+            fuzzy_score: None,
+            semantic_score: None,
+            fuzzy_rank: None,
+            semantic_rank: None,
+            breakdown: None,
+            merged_from: Vec::new(),
+        }])
+    }
+
+    #[test]
+    fn a_put_entry_is_returned_by_get_under_the_same_generation() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().to_path_buf(), 100, 1_000_000);
+
+        cache.put("q", 1, &results(0.5));
+        assert_eq!(cache.get("q", 1), Some(results(0.5)));
+    }
+
+    #[test]
+    fn an_entry_from_a_stale_generation_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().to_path_buf(), 100, 1_000_000);
+
+        cache.put("q", 1, &results(0.5));
+        assert_eq!(cache.get("q", 2), None);
+    }
+
+    #[test]
+    fn entries_beyond_max_entries_are_evicted_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().to_path_buf(), 2, 1_000_000);
+
+        cache.put("a", 1, &results(0.1));
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put("b", 1, &results(0.2));
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put("c", 1, &results(0.3));
+
+        assert_eq!(cache.get("a", 1), None);
+        assert!(cache.get("b", 1).is_some());
+        assert!(cache.get("c", 1).is_some());
+    }
+}
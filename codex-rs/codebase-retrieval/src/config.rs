@@ -0,0 +1,231 @@
+use crate::error::RetrievalError;
+
+/// How fuzzy (BM25) and semantic (embedding) rankings are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FusionStrategy {
+    /// Reciprocal Rank Fusion over both channels.
+    #[default]
+    Rrf,
+    SemanticOnly,
+    FuzzyOnly,
+    /// Min-max normalizes each channel's raw scores to `0.0..=1.0`, then
+    /// takes a weighted sum, instead of fusing by rank. Unlike [`Self::Rrf`],
+    /// this keeps each channel's score magnitude (not just its ordering)
+    /// in play, which matters when one channel's scores cluster tightly
+    /// while the other's spread out - RRF would treat both spreads
+    /// identically since it only looks at rank.
+    ///
+    /// A channel with zero or one results (nothing to spread across a
+    /// range) normalizes every one of its scores to `1.0` rather than
+    /// `0.0`, since there's no meaningful "worst" score to anchor a range
+    /// to; a chunk absent from a channel contributes `0.0` from it, same as
+    /// [`Self::Rrf`] treats a missing rank.
+    Normalized { fuzzy_weight: f32, semantic_weight: f32 },
+}
+
+/// How the fused ranking is adjusted before being returned.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RerankStrategy {
+    /// Return the fused ordering (per `fusion_strategy`) unchanged.
+    #[default]
+    FusedOnly,
+    /// Rerank the top `rerank_candidates` via the [`crate::Reranker`]
+    /// supplied through [`crate::HybridRetrieval::with_reranker`].
+    Custom,
+    /// Reorder the top `rerank_candidates` by Maximal Marginal Relevance,
+    /// using chunk embeddings to penalize redundancy with results already
+    /// picked, to reduce near-duplicate hits. `lambda` trades off relevance
+    /// (`1.0`) against diversity (`0.0`).
+    Mmr { lambda: f32 },
+}
+
+/// Configuration for [`crate::HybridRetrieval`].
+#[derive(Debug, Clone)]
+pub struct RetrievalConfig {
+    pub fusion_strategy: FusionStrategy,
+    /// The `k` constant in Reciprocal Rank Fusion: `1 / (k + rank)`. Larger
+    /// values flatten the influence of rank differences.
+    pub rrf_k: usize,
+    /// Per-channel multipliers applied to each side's Reciprocal Rank
+    /// Fusion contribution before summing: `(fuzzy_weight, semantic_weight)`.
+    /// Consulted by every `fusion_strategy` except
+    /// [`FusionStrategy::Normalized`], which uses its own weights; under
+    /// `FuzzyOnly`/`SemanticOnly` only that channel's weight applies. Raise `semantic_weight` for a repo where identifiers are a weak
+    /// signal and queries describe behavior rather than naming it.
+    pub source_weights: (f32, f32),
+    /// Populate `SearchResult::breakdown` with a [`crate::result::ScoreBreakdown`]
+    /// for every hit. Off by default since most callers only need the final
+    /// `score`/`source`; enable it when diagnosing why a hit ranked where it did.
+    pub explain: bool,
+    pub rerank_strategy: RerankStrategy,
+    /// How many of the top fused candidates are passed to the
+    /// [`crate::Reranker`] when `rerank_strategy` is [`RerankStrategy::Custom`].
+    pub rerank_candidates: usize,
+    /// In-memory LRU capacity for cached search results, keyed by `(query,
+    /// limit, exact_symbols)`. `None` (the default) disables the in-memory
+    /// tier.
+    pub memory_cache_capacity: Option<usize>,
+    /// Directory used to persist the query cache across process restarts,
+    /// so the CLI's "every invocation is a fresh process" use case still
+    /// benefits from caching. `None` (the default) disables the disk tier.
+    ///
+    /// A cached entry is only served if it was written under the index's
+    /// current generation (derived from the indexed chunks' paths and
+    /// content hashes); entries from an older index are discarded.
+    pub cache_dir: Option<std::path::PathBuf>,
+    /// Maximum number of entries kept under `cache_dir` before the
+    /// least-recently-read ones are evicted.
+    pub disk_cache_max_entries: usize,
+    /// Maximum total size, in bytes, of `cache_dir` before the
+    /// least-recently-read entries are evicted.
+    pub disk_cache_max_bytes: u64,
+    /// Drop hits whose [`crate::result::SearchResult::normalized_score`]
+    /// falls below this cutoff before `limit` truncation, so a query with no
+    /// good matches returns fewer (or zero) results instead of padding out
+    /// to `limit` with noise. `None` (the default) disables filtering.
+    pub min_score: Option<f32>,
+    /// Added to a chunk's fuzzy score when its content contains the whole
+    /// query string as a contiguous substring, before fusion. BM25 (the
+    /// fuzzy engine) scores on term overlap alone, so a multi-word query
+    /// like `"spawn blocking"` ranks a chunk that merely mentions both words
+    /// the same as one that uses the literal phrase; this rewards the
+    /// stronger signal. `0.0` (the default) disables the boost.
+    pub phrase_exact_bonus: f32,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            fusion_strategy: FusionStrategy::default(),
+            rrf_k: 60,
+            source_weights: (1.0, 1.0),
+            explain: false,
+            rerank_strategy: RerankStrategy::default(),
+            rerank_candidates: 20,
+            memory_cache_capacity: None,
+            cache_dir: None,
+            disk_cache_max_entries: 1_000,
+            disk_cache_max_bytes: 50_000_000,
+            min_score: None,
+            phrase_exact_bonus: 0.0,
+        }
+    }
+}
+
+impl RetrievalConfig {
+    /// Minimal work per search: fused ranking only, no MMR rerank, no
+    /// in-memory cache. Suited to a one-off search where the caller won't
+    /// repeat the same query.
+    pub fn fast() -> Self {
+        Self {
+            rerank_strategy: RerankStrategy::FusedOnly,
+            rerank_candidates: 10,
+            memory_cache_capacity: None,
+            ..Self::default()
+        }
+    }
+
+    /// [`Self::default`] plus an in-memory result cache, for a caller that
+    /// repeats queries (e.g. a conversation revisiting the same area of the
+    /// codebase) without needing [`Self::accurate`]'s extra rerank pass.
+    pub fn balanced() -> Self {
+        Self {
+            memory_cache_capacity: Some(256),
+            ..Self::default()
+        }
+    }
+
+    /// Reranks the top fused candidates by Maximal Marginal Relevance to cut
+    /// near-duplicate hits, at the cost of the extra rerank pass every
+    /// search now does. Suited to a caller that shows results directly to a
+    /// person rather than feeding them to a model that can tolerate some
+    /// redundancy.
+    pub fn accurate() -> Self {
+        Self {
+            rerank_strategy: RerankStrategy::Mmr { lambda: 0.7 },
+            rerank_candidates: 50,
+            memory_cache_capacity: Some(256),
+            ..Self::default()
+        }
+    }
+
+    /// Starts a [`RetrievalConfigBuilder`] seeded with [`Self::default`]'s
+    /// values, for changing one field without having to restate every other
+    /// default.
+    pub fn builder() -> RetrievalConfigBuilder {
+        RetrievalConfigBuilder { config: Self::default() }
+    }
+
+    /// Checks that every field is internally consistent enough to produce
+    /// meaningful results - not that the configuration is *good*, only that
+    /// it can't silently degrade into searches that always return nothing
+    /// (or collapse every candidate to the same score).
+    pub fn validate(&self) -> Result<(), RetrievalError> {
+        if self.rerank_candidates == 0
+            && !matches!(self.rerank_strategy, RerankStrategy::FusedOnly)
+        {
+            return Err(RetrievalError::InvalidRerankCandidates {
+                value: self.rerank_candidates,
+            });
+        }
+        if let Some(min_score) = self.min_score
+            && !(0.0..=1.0).contains(&min_score)
+        {
+            return Err(RetrievalError::InvalidMinScore { value: min_score });
+        }
+        if let FusionStrategy::Normalized { fuzzy_weight, semantic_weight } = self.fusion_strategy
+            && fuzzy_weight + semantic_weight <= 0.0
+        {
+            return Err(RetrievalError::InvalidFusionWeights {
+                fuzzy_weight,
+                semantic_weight,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Builds a [`RetrievalConfig`] one field at a time from [`RetrievalConfig::default`],
+/// validating the result in [`Self::build`] rather than leaving a caller to
+/// know every default just to change one of them.
+pub struct RetrievalConfigBuilder {
+    config: RetrievalConfig,
+}
+
+impl RetrievalConfigBuilder {
+    pub fn fusion_strategy(mut self, fusion_strategy: FusionStrategy) -> Self {
+        self.config.fusion_strategy = fusion_strategy;
+        self
+    }
+
+    pub fn rerank_strategy(mut self, rerank_strategy: RerankStrategy) -> Self {
+        self.config.rerank_strategy = rerank_strategy;
+        self
+    }
+
+    /// How many of the top fused candidates are passed to the active
+    /// `rerank_strategy` - see [`RetrievalConfig::rerank_candidates`].
+    pub fn rerank_candidates(mut self, rerank_candidates: usize) -> Self {
+        self.config.rerank_candidates = rerank_candidates;
+        self
+    }
+
+    /// Sets `memory_cache_capacity` - see [`RetrievalConfig::memory_cache_capacity`].
+    pub fn cache_size(mut self, cache_size: Option<usize>) -> Self {
+        self.config.memory_cache_capacity = cache_size;
+        self
+    }
+
+    pub fn min_score(mut self, min_score: Option<f32>) -> Self {
+        self.config.min_score = min_score;
+        self
+    }
+
+    /// Validates the configuration built so far and returns it, or the first
+    /// [`RetrievalError`] found. See [`RetrievalConfig::validate`] for what's
+    /// checked.
+    pub fn build(self) -> Result<RetrievalConfig, RetrievalError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
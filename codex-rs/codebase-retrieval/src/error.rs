@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RerankError {
+    #[error(transparent)]
+    Custom(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Returned by [`crate::RetrievalConfigBuilder::build`] and
+/// [`crate::HybridRetrieval::with_config`] when a [`crate::RetrievalConfig`]
+/// can't produce meaningful search results as configured.
+#[derive(Debug, Error, PartialEq)]
+pub enum RetrievalError {
+    /// `rerank_candidates` was `0`, so [`crate::RerankStrategy::Custom`]/
+    /// [`crate::RerankStrategy::Mmr`] would always rerank an empty slice.
+    #[error("rerank_candidates must be greater than 0, got {value}")]
+    InvalidRerankCandidates { value: usize },
+    /// `min_score` was outside `0.0..=1.0`, the range
+    /// [`crate::result::SearchResult::normalized_score`] is clamped to, so
+    /// the cutoff could never (or always) trigger.
+    #[error("min_score must be within 0.0..=1.0, got {value}")]
+    InvalidMinScore { value: f32 },
+    /// [`crate::FusionStrategy::Normalized`]'s weights were both zero (or
+    /// negative), so the weighted sum they control would always collapse to
+    /// `0.0` regardless of either channel's score.
+    #[error(
+        "FusionStrategy::Normalized weights must sum to a positive number, \
+         got fuzzy_weight={fuzzy_weight} semantic_weight={semantic_weight}"
+    )]
+    InvalidFusionWeights { fuzzy_weight: f32, semantic_weight: f32 },
+}
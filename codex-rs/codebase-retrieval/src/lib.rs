@@ -0,0 +1,25 @@
+//! Fuses fuzzy and semantic search over indexed code chunks.
+
+mod cache;
+mod config;
+mod error;
+mod rerank;
+mod result;
+mod retrieval;
+
+pub use cache::CacheStats;
+pub use config::FusionStrategy;
+pub use config::RerankStrategy;
+pub use config::RetrievalConfig;
+pub use config::RetrievalConfigBuilder;
+pub use error::RerankError;
+pub use error::RetrievalError;
+pub use rerank::Reranker;
+pub use result::MergedOverlap;
+pub use result::ScoreBreakdown;
+pub use result::SearchResult;
+pub use result::SearchResults;
+pub use result::SearchSource;
+pub use result::SearchStage;
+pub use result::SearchStats;
+pub use retrieval::HybridRetrieval;
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+
+use crate::error::RerankError;
+use crate::result::SearchResult;
+use crate::retrieval::cosine_similarity;
+
+/// Pluggable reordering of the top fused candidates.
+///
+/// This is the extension point for reranking that can't be expressed as a
+/// cheap in-process heuristic, e.g. a cross-encoder model or an LLM call.
+/// Implementations only see the top `rerank_candidates` results (see
+/// [`crate::RetrievalConfig`]) to keep latency bounded, and a failed rerank
+/// must not fail the whole search: [`crate::HybridRetrieval::search`] falls
+/// back to the fused ordering when [`Self::rerank`] returns an error.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    async fn rerank(
+        &self,
+        query: &str,
+        candidates: Vec<SearchResult>,
+    ) -> Result<Vec<SearchResult>, RerankError>;
+}
+
+/// Reorders `candidates` by Maximal Marginal Relevance to reduce
+/// near-duplicate hits: greedily picks the next result maximizing
+/// `lambda * relevance - (1 - lambda) * max_similarity_to_already_selected`,
+/// where `relevance` is the candidate's fused `score` and similarity is
+/// cosine similarity over `embeddings`.
+///
+/// `embeddings[i]` must correspond to `candidates[i]`. `lambda` close to
+/// `1.0` behaves like the original relevance ordering; close to `0.0`
+/// prioritizes diversity over relevance.
+pub(crate) fn mmr_rerank(
+    candidates: Vec<SearchResult>,
+    embeddings: Vec<Vec<f32>>,
+    lambda: f32,
+) -> Vec<SearchResult> {
+    debug_assert_eq!(candidates.len(), embeddings.len());
+    let mut remaining: Vec<(SearchResult, Vec<f32>)> =
+        candidates.into_iter().zip(embeddings).collect();
+    let mut selected = Vec::with_capacity(remaining.len());
+    let mut selected_embeddings: Vec<Vec<f32>> = Vec::with_capacity(remaining.len());
+
+    while !remaining.is_empty() {
+        let (best_idx, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(idx, (result, embedding))| {
+                let redundancy = selected_embeddings
+                    .iter()
+                    .map(|picked| cosine_similarity(embedding, picked))
+                    .fold(f32::NEG_INFINITY, f32::max);
+                let redundancy = if redundancy.is_finite() { redundancy } else { 0.0 };
+                (idx, lambda * result.score - (1.0 - lambda) * redundancy)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .expect("remaining is non-empty");
+
+        let (result, embedding) = remaining.remove(best_idx);
+        selected.push(result);
+        selected_embeddings.push(embedding);
+    }
+
+    selected
+}
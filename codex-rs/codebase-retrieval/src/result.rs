@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use codex_vector_store::CodeChunk;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Which channel(s) contributed a [`SearchResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchSource {
+    Fuzzy,
+    Semantic,
+    Both,
+}
+
+/// A single fused search hit, carrying enough per-channel detail to explain
+/// why it ranked where it did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub chunk: CodeChunk,
+    /// The final, fused score used to order results.
+    pub score: f32,
+    /// `score` divided by the highest score a hit could reach under the
+    /// search's [`crate::config::RetrievalConfig`] (rank `0` on every
+    /// contributing channel), clamped to `0.0..=1.0`. Unlike `score`, this is
+    /// comparable across different `rrf_k`/`source_weights` configurations,
+    /// which is what makes it meaningful as a fixed cutoff in
+    /// [`SearchResults::above`] or [`crate::config::RetrievalConfig::min_score`].
+    /// An exact-symbol boost or a [`crate::config::RerankStrategy::Custom`]
+    /// reranker can push the raw `score` above that ceiling; this is clamped
+    /// rather than letting it exceed `1.0`.
+    pub normalized_score: f32,
+    pub source: SearchSource,
+    pub fuzzy_score: Option<f32>,
+    pub semantic_score: Option<f32>,
+    pub fuzzy_rank: Option<usize>,
+    pub semantic_rank: Option<usize>,
+    /// Per-stage score contributions, present only when
+    /// `RetrievalConfig::explain` is enabled.
+    pub breakdown: Option<ScoreBreakdown>,
+    /// Other hits [`SearchResults::dedupe_overlapping`] folded into this one
+    /// because their chunk's line range overlapped this one's past its
+    /// threshold - e.g. a function indexed both standalone and inside its
+    /// parent `impl` block. Empty unless that ran.
+    #[serde(default)]
+    pub merged_from: Vec<MergedOverlap>,
+}
+
+/// A [`SearchResult`] dropped by [`SearchResults::dedupe_overlapping`] in
+/// favor of a higher-scored, overlapping hit from the same file - kept only
+/// so a caller can see what was folded in, not to be searched or rendered
+/// on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MergedOverlap {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+}
+
+/// Per-stage contributions behind a [`SearchResult`]'s final `score`, for
+/// diagnosing relevance bugs. Only populated when `RetrievalConfig::explain`
+/// is set, since computing and carrying it for every hit of every search has
+/// a cost most callers don't want to pay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub fuzzy_rank: Option<usize>,
+    pub semantic_rank: Option<usize>,
+    /// The score produced by `RetrievalConfig::fusion_strategy` (plus any
+    /// exact-symbol boost), before reranking - what the comments elsewhere
+    /// in this crate call "the fused ordering".
+    pub fused_score: f32,
+    /// `score - fused_score` after reranking. `0.0` if `rerank_strategy` is
+    /// [`crate::config::RerankStrategy::FusedOnly`], the reranker wasn't
+    /// reached (outside `rerank_candidates`), or it left this hit's score
+    /// unchanged - which [`crate::config::RerankStrategy::Mmr`] always does,
+    /// since it only reorders candidates rather than rescoring them.
+    pub rerank_delta: f32,
+}
+
+/// A phase of [`crate::HybridRetrieval::search_with_stats`]'s pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchStage {
+    Fuzzy,
+    Semantic,
+    Fusion,
+    Rerank,
+}
+
+/// How long each pipeline stage took on a single
+/// [`crate::HybridRetrieval::search_with_stats`] call, for debugging a slow
+/// repo's retrieval latency.
+///
+/// A stage is omitted rather than reported as zero when it wasn't run at all
+/// (e.g. `Semantic` under [`crate::config::FusionStrategy::FuzzyOnly`], or
+/// `Rerank` under [`crate::config::RerankStrategy::FusedOnly`]). On a cache
+/// hit, `stage_timings` is empty - none of the pipeline ran, so there's
+/// nothing to measure and negligible overhead added by collecting it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchStats {
+    pub stage_timings: Vec<(SearchStage, Duration)>,
+}
+
+/// A ranked list of [`SearchResult`]s.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchResults(pub Vec<SearchResult>);
+
+impl SearchResults {
+    pub fn as_slice(&self) -> &[SearchResult] {
+        &self.0
+    }
+
+    /// Every hit whose [`SearchResult::normalized_score`] is at least
+    /// `threshold`, preserving order. Use this to drop low-confidence hits
+    /// from a result set after the fact; [`crate::config::RetrievalConfig::min_score`]
+    /// applies the same cutoff inside [`crate::HybridRetrieval::search`] itself.
+    pub fn above(&self, threshold: f32) -> SearchResults {
+        SearchResults(
+            self.0
+                .iter()
+                .filter(|result| result.normalized_score >= threshold)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Merges hits whose chunk occupies (nearly) the same lines of the same
+    /// file - e.g. a function indexed both standalone and again inside its
+    /// parent `impl` block - keeping the higher-scored hit of each pair and
+    /// recording the dropped one's range in its [`SearchResult::merged_from`].
+    /// Preserves the order of whichever hit of each merged pair was kept.
+    ///
+    /// Two hits merge once the intersection of their line ranges covers at
+    /// least `threshold` of the smaller range. Results are only ever
+    /// compared within the same [`SearchResult::chunk`]'s `path` - two hits
+    /// from different files never merge, even with byte-identical content.
+    pub fn dedupe_overlapping(&self, threshold: f32) -> SearchResults {
+        let mut kept: Vec<SearchResult> = Vec::new();
+        for result in &self.0 {
+            let overlapping = kept.iter_mut().find(|existing| {
+                existing.chunk.path == result.chunk.path
+                    && line_overlap_fraction(&existing.chunk, &result.chunk) >= threshold
+            });
+            match overlapping {
+                Some(existing) if result.score > existing.score => {
+                    let mut merged_from = std::mem::take(&mut existing.merged_from);
+                    merged_from.push(MergedOverlap {
+                        start_line: existing.chunk.start_line,
+                        end_line: existing.chunk.end_line,
+                        score: existing.score,
+                    });
+                    merged_from.append(&mut result.merged_from.clone());
+                    *existing = result.clone();
+                    existing.merged_from = merged_from;
+                }
+                Some(existing) => {
+                    existing.merged_from.push(MergedOverlap {
+                        start_line: result.chunk.start_line,
+                        end_line: result.chunk.end_line,
+                        score: result.score,
+                    });
+                    existing.merged_from.append(&mut result.merged_from.clone());
+                }
+                None => kept.push(result.clone()),
+            }
+        }
+        SearchResults(kept)
+    }
+
+    /// Renders a human-readable breakdown of why the hit at `idx` ranked
+    /// where it did.
+    pub fn explain(&self, idx: usize) -> String {
+        let Some(result) = self.0.get(idx) else {
+            return format!("no result at index {idx}");
+        };
+        let fuzzy = match (result.fuzzy_rank, result.fuzzy_score) {
+            (Some(rank), Some(score)) => format!("fuzzy rank {rank} (score {score:.4})"),
+            _ => "no fuzzy match".to_string(),
+        };
+        let semantic = match (result.semantic_rank, result.semantic_score) {
+            (Some(rank), Some(score)) => format!("semantic rank {rank} (score {score:.4})"),
+            _ => "no semantic match".to_string(),
+        };
+        format!(
+            "{}:{}-{} fused score {:.4} via {:?}: {fuzzy}; {semantic}",
+            result.chunk.path, result.chunk.start_line, result.chunk.end_line, result.score, result.source,
+        )
+    }
+}
+
+/// Fraction of the smaller of `a`/`b`'s (inclusive) line range covered by
+/// their intersection, `0.0` if they don't overlap at all.
+fn line_overlap_fraction(a: &CodeChunk, b: &CodeChunk) -> f32 {
+    let overlap_start = a.start_line.max(b.start_line);
+    let overlap_end = a.end_line.min(b.end_line);
+    if overlap_end < overlap_start {
+        return 0.0;
+    }
+    let overlap = (overlap_end - overlap_start + 1) as f32;
+    let a_len = (a.end_line - a.start_line + 1) as f32;
+    let b_len = (b.end_line - b.start_line + 1) as f32;
+    overlap / a_len.min(b_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start_line: usize, end_line: usize, content: &str) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line,
+            end_line,
+            content: content.to_string(),
+            language: None,
+            chunk_type: None,
+            symbol_name: None,
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    fn result(chunk: CodeChunk, score: f32) -> SearchResult {
+        SearchResult {
+            chunk,
+            score,
+            normalized_score: score,
+            source: SearchSource::Both,
+            fuzzy_score: None,
+            semantic_score: None,
+            fuzzy_rank: None,
+            semantic_rank: None,
+            breakdown: None,
+            merged_from: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dedupe_overlapping_keeps_the_higher_scored_hit_and_records_the_other() {
+        let standalone = result(chunk("a.rs", 10, 14, "fn parse() {}"), 0.6);
+        let inside_impl = result(chunk("a.rs", 9, 15, "fn parse() {}"), 0.9);
+        let results = SearchResults(vec![standalone, inside_impl]);
+
+        let deduped = results.dedupe_overlapping(0.5);
+
+        assert_eq!(deduped.as_slice().len(), 1);
+        let kept = &deduped.as_slice()[0];
+        assert_eq!(kept.chunk.start_line, 9);
+        assert_eq!(
+            kept.merged_from,
+            vec![MergedOverlap { start_line: 10, end_line: 14, score: 0.6 }]
+        );
+    }
+
+    #[test]
+    fn dedupe_overlapping_never_merges_across_different_files() {
+        let a = result(chunk("a.rs", 10, 14, "fn parse() {}"), 0.6);
+        let b = result(chunk("b.rs", 10, 14, "fn parse() {}"), 0.9);
+        let results = SearchResults(vec![a, b]);
+
+        let deduped = results.dedupe_overlapping(0.01);
+
+        assert_eq!(deduped.as_slice().len(), 2);
+    }
+
+    #[test]
+    fn dedupe_overlapping_leaves_hits_below_the_threshold_untouched() {
+        let a = result(chunk("a.rs", 1, 5, "fn a() {}"), 0.6);
+        let b = result(chunk("a.rs", 4, 10, "fn b() {}"), 0.9);
+        let results = SearchResults(vec![a, b]);
+
+        // Intersection is lines 4-5 (2 lines) out of a's 5-line range: 0.4.
+        let deduped = results.dedupe_overlapping(0.5);
+
+        assert_eq!(deduped.as_slice().len(), 2);
+    }
+}
@@ -0,0 +1,1400 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use bm25::Document;
+use bm25::Language;
+use bm25::SearchEngine;
+use bm25::SearchEngineBuilder;
+use codex_embeddings::EmbeddingService;
+use codex_vector_store::CodeChunk;
+use lru::LruCache;
+
+use crate::cache::CacheStats;
+use crate::cache::DiskCache;
+use crate::config::FusionStrategy;
+use crate::config::RerankStrategy;
+use crate::config::RetrievalConfig;
+use crate::error::RetrievalError;
+use crate::rerank::Reranker;
+use crate::rerank::mmr_rerank;
+use crate::result::ScoreBreakdown;
+use crate::result::SearchResult;
+use crate::result::SearchResults;
+use crate::result::SearchSource;
+use crate::result::SearchStage;
+use crate::result::SearchStats;
+
+/// Fuses fuzzy (BM25) and semantic (embedding cosine similarity) search over
+/// a fixed set of chunks.
+pub struct HybridRetrieval {
+    config: RetrievalConfig,
+    chunks: Vec<CodeChunk>,
+    embeddings: Vec<Vec<f32>>,
+    fuzzy_engine: SearchEngine<usize>,
+    embedding_service: EmbeddingService,
+    reranker: Option<Box<dyn Reranker>>,
+    memory_cache: Option<Mutex<LruCache<String, SearchResults>>>,
+    disk_cache: Option<DiskCache>,
+    /// Identifies this index's content, so a cache entry written by a
+    /// previous index can be told apart from one that still matches.
+    generation: u64,
+    cache_stats: Mutex<CacheStats>,
+}
+
+impl HybridRetrieval {
+    /// Builds an index with [`RetrievalConfig::default`], which always
+    /// passes [`RetrievalConfig::validate`] - use [`Self::with_config`] for
+    /// a hand-rolled or [`RetrievalConfigBuilder`]-built config, which may
+    /// not.
+    pub fn new(chunks: Vec<CodeChunk>, embeddings: Vec<Vec<f32>>) -> Self {
+        Self::with_config(chunks, embeddings, RetrievalConfig::default())
+            .expect("RetrievalConfig::default always passes validation")
+    }
+
+    /// Like [`Self::new`], but with a caller-supplied `config`. Rejected
+    /// with a [`RetrievalError`] up front, rather than constructing an index
+    /// that would silently return degraded (or always-empty) search results
+    /// - see [`RetrievalConfig::validate`] for what's checked.
+    pub fn with_config(
+        chunks: Vec<CodeChunk>,
+        embeddings: Vec<Vec<f32>>,
+        config: RetrievalConfig,
+    ) -> Result<Self, RetrievalError> {
+        config.validate()?;
+        let documents: Vec<Document<usize>> = chunks
+            .iter()
+            .enumerate()
+            .map(|(idx, chunk)| Document::new(idx, chunk.content.clone()))
+            .collect();
+        let fuzzy_engine =
+            SearchEngineBuilder::<usize>::with_documents(Language::English, documents).build();
+        let generation = compute_generation(&chunks, &config);
+        let memory_cache = config
+            .memory_cache_capacity
+            .and_then(NonZeroUsize::new)
+            .map(|capacity| Mutex::new(LruCache::new(capacity)));
+        let disk_cache = config.cache_dir.clone().map(|dir| {
+            DiskCache::new(dir, config.disk_cache_max_entries, config.disk_cache_max_bytes)
+        });
+
+        if let (Some(memory_cache), Some(disk_cache)) = (&memory_cache, &disk_cache) {
+            let mut memory_cache = memory_cache.lock().unwrap();
+            for (key, results) in disk_cache.entries_for_generation(generation) {
+                memory_cache.put(key, results);
+            }
+        }
+
+        Ok(Self {
+            memory_cache,
+            disk_cache,
+            config,
+            chunks,
+            embeddings,
+            fuzzy_engine,
+            embedding_service: EmbeddingService::new(),
+            reranker: None,
+            generation,
+            cache_stats: Mutex::new(CacheStats::default()),
+        })
+    }
+
+    /// Hit/miss counters for the query cache, broken down by which tier
+    /// (in-memory or on-disk) served each hit.
+    pub fn cache_stats(&self) -> CacheStats {
+        *self.cache_stats.lock().unwrap()
+    }
+
+    /// Plugs in a [`Reranker`] used when `config.rerank_strategy` is
+    /// [`RerankStrategy::Custom`].
+    pub fn with_reranker(mut self, reranker: Box<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Appends `chunks` and their corresponding `embeddings` to this index in
+    /// place, so a caller that's keeping retrieval in sync with an indexer
+    /// doesn't have to reconstruct a whole new `HybridRetrieval` (and redo
+    /// its own embedding/config setup) for every incremental update.
+    ///
+    /// The `bm25` fuzzy engine has no incremental-update API of its own, so
+    /// this still rebuilds it from the full (now-larger) chunk list; that
+    /// rebuild is cheap relative to reconstructing `HybridRetrieval` itself,
+    /// since it skips re-embedding every unrelated chunk.
+    ///
+    /// # Thread safety
+    ///
+    /// Takes `&mut self`, like any other mutating method here: the caller
+    /// needs exclusive access for the duration of the call (e.g. behind its
+    /// own `Mutex`/`RwLock`), same as `remove_chunks`. Concurrent `&self`
+    /// searches can't overlap a call to this method under Rust's borrow
+    /// rules; a search that completes before this call still reflects the
+    /// old index, and one that starts after reflects the new one.
+    pub fn add_chunks(&mut self, chunks: Vec<CodeChunk>, embeddings: Vec<Vec<f32>>) {
+        self.chunks.extend(chunks);
+        self.embeddings.extend(embeddings);
+        self.rebuild_fuzzy_engine();
+        self.invalidate_cache();
+    }
+
+    /// Removes every chunk whose `path` is in `paths`, returning the number
+    /// of chunks removed. See [`Self::add_chunks`] for thread-safety
+    /// expectations.
+    pub fn remove_chunks(&mut self, paths: &[String]) -> usize {
+        let before = self.chunks.len();
+        let (kept_chunks, kept_embeddings): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.chunks)
+                .into_iter()
+                .zip(std::mem::take(&mut self.embeddings))
+                .filter(|(chunk, _)| !paths.iter().any(|path| path == &chunk.path))
+                .unzip();
+        self.chunks = kept_chunks;
+        self.embeddings = kept_embeddings;
+        let removed = before - self.chunks.len();
+        if removed > 0 {
+            self.rebuild_fuzzy_engine();
+            self.invalidate_cache();
+        }
+        removed
+    }
+
+    /// Applies an incremental update from an indexer in one step: drops
+    /// every chunk whose path is in `removed_paths`, embeds and appends
+    /// `added` via `self.embedding_service`, then rebuilds the fuzzy corpus
+    /// once against the resulting set. Equivalent to
+    /// [`Self::remove_chunks`] followed by [`Self::add_chunks`], but without
+    /// the wasted rebuild in between, and without asking the caller to embed
+    /// `added` itself.
+    ///
+    /// # Consistency
+    ///
+    /// Like [`Self::add_chunks`]/[`Self::remove_chunks`], this takes
+    /// `&mut self`: Rust's borrow checker guarantees no concurrent `&self`
+    /// search can be in progress while it runs, and `self.chunks`,
+    /// `self.embeddings`, and `self.fuzzy_engine` are all brought up to date
+    /// before the call returns. A search that started before
+    /// this call returns sees the old corpus throughout; a search that
+    /// starts after sees the new one throughout. Neither can observe
+    /// `removed_paths` already dropped but `added` not yet indexed, or any
+    /// other partially-applied state.
+    pub fn update_chunks(&mut self, added: Vec<CodeChunk>, removed_paths: Vec<String>) {
+        if added.is_empty() && removed_paths.is_empty() {
+            return;
+        }
+        if !removed_paths.is_empty() {
+            let (kept_chunks, kept_embeddings): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut self.chunks)
+                    .into_iter()
+                    .zip(std::mem::take(&mut self.embeddings))
+                    .filter(|(chunk, _)| !removed_paths.iter().any(|path| path == &chunk.path))
+                    .unzip();
+            self.chunks = kept_chunks;
+            self.embeddings = kept_embeddings;
+        }
+        if !added.is_empty() {
+            let new_embeddings = self
+                .embedding_service
+                .embed_documents(added.iter().map(|chunk| chunk.content.clone()).collect());
+            self.chunks.extend(added);
+            self.embeddings.extend(new_embeddings);
+        }
+        self.rebuild_fuzzy_engine();
+        self.invalidate_cache();
+    }
+
+    /// Number of chunks currently indexed, for a caller to sanity-check
+    /// against its own count after [`Self::update_chunks`].
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    fn rebuild_fuzzy_engine(&mut self) {
+        let documents: Vec<Document<usize>> = self
+            .chunks
+            .iter()
+            .enumerate()
+            .map(|(idx, chunk)| Document::new(idx, chunk.content.clone()))
+            .collect();
+        self.fuzzy_engine =
+            SearchEngineBuilder::<usize>::with_documents(Language::English, documents).build();
+    }
+
+    /// Bumps `generation` (so stale disk-cache entries from before this
+    /// mutation are rejected) and drops the in-memory LRU outright, since its
+    /// entries carry no generation of their own to check.
+    fn invalidate_cache(&mut self) {
+        self.generation = compute_generation(&self.chunks, &self.config);
+        if let Some(memory_cache) = &self.memory_cache {
+            memory_cache.lock().unwrap().clear();
+        }
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> SearchResults {
+        self.search_internal(query, limit, &[]).await.0
+    }
+
+    /// Like [`Self::search`], but boosts any candidate whose `symbol_name`
+    /// exactly matches one of `exact_symbols` ahead of the fused ranking,
+    /// before reranking and truncation.
+    ///
+    /// Intended for callers (e.g. a query analyzer) that have already
+    /// detected the user is asking about a specific symbol by name rather
+    /// than describing it, where an exact match is a stronger signal than
+    /// anything BM25 or embedding similarity can express on their own.
+    pub async fn search_prioritizing_symbols(
+        &self,
+        query: &str,
+        limit: usize,
+        exact_symbols: &[String],
+    ) -> SearchResults {
+        self.search_internal(query, limit, exact_symbols).await.0
+    }
+
+    /// Like [`Self::search`], but also reports how long each pipeline stage
+    /// took, for debugging slow retrieval on a large repo. See
+    /// [`SearchStats`] for what "a stage" means and why a cache hit reports
+    /// an empty `stage_timings`.
+    pub async fn search_with_stats(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> (SearchResults, SearchStats) {
+        self.search_internal(query, limit, &[]).await
+    }
+
+    async fn search_internal(
+        &self,
+        query: &str,
+        limit: usize,
+        exact_symbols: &[String],
+    ) -> (SearchResults, SearchStats) {
+        let cache_key = cache_key(query, limit, exact_symbols);
+
+        if let Some(memory_cache) = &self.memory_cache
+            && let Some(results) = memory_cache.lock().unwrap().get(&cache_key)
+        {
+            self.cache_stats.lock().unwrap().memory_hits += 1;
+            return (results.clone(), SearchStats::default());
+        }
+        if let Some(disk_cache) = &self.disk_cache
+            && let Some(results) = disk_cache.get(&cache_key, self.generation)
+        {
+            self.cache_stats.lock().unwrap().disk_hits += 1;
+            if let Some(memory_cache) = &self.memory_cache {
+                memory_cache.lock().unwrap().put(cache_key.clone(), results.clone());
+            }
+            return (results, SearchStats::default());
+        }
+        self.cache_stats.lock().unwrap().misses += 1;
+
+        let mut stage_timings = Vec::with_capacity(4);
+
+        let fuzzy_started = Instant::now();
+        let fuzzy_ranked = match self.config.fusion_strategy {
+            FusionStrategy::SemanticOnly => Vec::new(),
+            _ => self.fuzzy_ranked(query),
+        };
+        if self.config.fusion_strategy != FusionStrategy::SemanticOnly {
+            stage_timings.push((SearchStage::Fuzzy, fuzzy_started.elapsed()));
+        }
+
+        let semantic_started = Instant::now();
+        let semantic_ranked = match self.config.fusion_strategy {
+            FusionStrategy::FuzzyOnly => Vec::new(),
+            _ => self.semantic_ranked(query),
+        };
+        if self.config.fusion_strategy != FusionStrategy::FuzzyOnly {
+            stage_timings.push((SearchStage::Semantic, semantic_started.elapsed()));
+        }
+
+        let fusion_started = Instant::now();
+
+        let fuzzy_rank: HashMap<usize, (usize, f32)> = fuzzy_ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, (id, score))| (*id, (rank, *score)))
+            .collect();
+        let semantic_rank: HashMap<usize, (usize, f32)> = semantic_ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, (id, score))| (*id, (rank, *score)))
+            .collect();
+
+        let mut ids: Vec<usize> = fuzzy_rank.keys().chain(semantic_rank.keys()).copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let normalized_channels = match self.config.fusion_strategy {
+            FusionStrategy::Normalized { .. } => Some((
+                min_max_normalize(&fuzzy_ranked),
+                min_max_normalize(&semantic_ranked),
+            )),
+            _ => None,
+        };
+
+        let mut results: Vec<SearchResult> = ids
+            .into_iter()
+            .map(|id| {
+                let fuzzy = fuzzy_rank.get(&id).copied();
+                let semantic = semantic_rank.get(&id).copied();
+                let source = match (fuzzy.is_some(), semantic.is_some()) {
+                    (true, true) => SearchSource::Both,
+                    (true, false) => SearchSource::Fuzzy,
+                    (false, true) => SearchSource::Semantic,
+                    (false, false) => unreachable!("id only exists if present in one map"),
+                };
+                let score = match (self.config.fusion_strategy, &normalized_channels) {
+                    (
+                        FusionStrategy::Normalized { fuzzy_weight, semantic_weight },
+                        Some((fuzzy_norm, semantic_norm)),
+                    ) => {
+                        fuzzy_norm.get(&id).copied().unwrap_or(0.0) * fuzzy_weight
+                            + semantic_norm.get(&id).copied().unwrap_or(0.0) * semantic_weight
+                    }
+                    _ => {
+                        let rrf = |rank: Option<(usize, f32)>, weight: f32| {
+                            rank.map_or(0.0, |(rank, _)| {
+                                weight / (self.config.rrf_k + rank + 1) as f32
+                            })
+                        };
+                        let (fuzzy_weight, semantic_weight) = self.config.source_weights;
+                        rrf(fuzzy, fuzzy_weight) + rrf(semantic, semantic_weight)
+                    }
+                };
+                SearchResult {
+                    chunk: self.chunks[id].clone(),
+                    score,
+                    normalized_score: self.normalize_score(score),
+                    source,
+                    fuzzy_score: fuzzy.map(|(_, score)| score),
+                    semantic_score: semantic.map(|(_, score)| score),
+                    fuzzy_rank: fuzzy.map(|(rank, _)| rank),
+                    semantic_rank: semantic.map(|(rank, _)| rank),
+                    breakdown: None,
+                    merged_from: Vec::new(),
+                }
+            })
+            .collect();
+
+        if !exact_symbols.is_empty() {
+            for result in &mut results {
+                if exact_symbols
+                    .iter()
+                    .any(|symbol| result.chunk.symbol_name.as_deref() == Some(symbol.as_str()))
+                {
+                    result.score += EXACT_SYMBOL_BOOST;
+                }
+            }
+        }
+
+        if self.config.explain {
+            for result in &mut results {
+                result.breakdown = Some(ScoreBreakdown {
+                    fuzzy_rank: result.fuzzy_rank,
+                    semantic_rank: result.semantic_rank,
+                    fused_score: result.score,
+                    rerank_delta: 0.0,
+                });
+            }
+        }
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        stage_timings.push((SearchStage::Fusion, fusion_started.elapsed()));
+
+        let rerank_started = Instant::now();
+        match self.config.rerank_strategy {
+            RerankStrategy::FusedOnly => {}
+            RerankStrategy::Custom => {
+                if let Some(reranker) = &self.reranker {
+                    let rerank_candidates = self.config.rerank_candidates.min(results.len());
+                    let tail = results.split_off(rerank_candidates);
+                    let head = std::mem::take(&mut results);
+                    // A failed rerank degrades gracefully to the fused ordering
+                    // rather than failing the whole search.
+                    results = match reranker.rerank(query, head.clone()).await {
+                        Ok(reranked) => reranked,
+                        Err(_) => head,
+                    };
+                    results.extend(tail);
+
+                    if self.config.explain {
+                        for result in &mut results {
+                            if let Some(breakdown) = &mut result.breakdown {
+                                breakdown.rerank_delta = result.score - breakdown.fused_score;
+                            }
+                        }
+                    }
+                }
+            }
+            RerankStrategy::Mmr { lambda } => {
+                let rerank_candidates = self.config.rerank_candidates.min(results.len());
+                let tail = results.split_off(rerank_candidates);
+                let head = std::mem::take(&mut results);
+                // Embeddings are recomputed from each candidate's content
+                // rather than looked up by id, so `SearchResult` needn't carry
+                // its originating chunk id. `embed_documents` is deterministic,
+                // so the candidates' vectors are comparable with each other -
+                // all MMR's redundancy term needs - though they differ from
+                // `self.embeddings` wherever those were built without the
+                // document prefix.
+                let embeddings = self.embedding_service.embed_documents(
+                    head.iter().map(|result| result.chunk.content.clone()).collect(),
+                );
+                results = mmr_rerank(head, embeddings, lambda);
+                results.extend(tail);
+            }
+        }
+        let rerank_ran = match self.config.rerank_strategy {
+            RerankStrategy::FusedOnly => false,
+            RerankStrategy::Custom => self.reranker.is_some(),
+            RerankStrategy::Mmr { .. } => true,
+        };
+        if rerank_ran {
+            stage_timings.push((SearchStage::Rerank, rerank_started.elapsed()));
+        }
+
+        // Recomputed after any boost/rerank above may have moved `score`,
+        // rather than trying to track it incrementally through each step.
+        for result in &mut results {
+            result.normalized_score = self.normalize_score(result.score);
+        }
+
+        if let Some(min_score) = self.config.min_score {
+            results.retain(|result| result.normalized_score >= min_score);
+        }
+
+        results.truncate(limit);
+        let results = SearchResults(results);
+
+        if let Some(memory_cache) = &self.memory_cache {
+            memory_cache.lock().unwrap().put(cache_key.clone(), results.clone());
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.put(&cache_key, self.generation, &results);
+        }
+        (results, SearchStats { stage_timings })
+    }
+
+    fn fuzzy_ranked(&self, query: &str) -> Vec<(usize, f32)> {
+        let mut ranked: Vec<(usize, f32)> = self
+            .fuzzy_engine
+            .search(query, self.chunks.len())
+            .into_iter()
+            .map(|result| (result.document.id, result.score))
+            .collect();
+        if self.config.phrase_exact_bonus != 0.0 {
+            for (id, score) in &mut ranked {
+                if self.chunks[*id].content.contains(query) {
+                    *score += self.config.phrase_exact_bonus;
+                }
+            }
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        }
+        ranked
+    }
+
+    fn semantic_ranked(&self, query: &str) -> Vec<(usize, f32)> {
+        let Some(query_embedding) = self.embedding_service.embed(&[query.to_string()]).pop() else {
+            return Vec::new();
+        };
+        let mut scored: Vec<(usize, f32)> = self
+            .embeddings
+            .iter()
+            .enumerate()
+            .map(|(id, embedding)| (id, cosine_similarity(&query_embedding, embedding)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+    }
+
+    /// `score` as a fraction of [`Self::max_possible_score`], clamped to
+    /// `0.0..=1.0`. See [`SearchResult::normalized_score`] for why this
+    /// (rather than the raw fused score) is the comparable quantity to
+    /// threshold on.
+    fn normalize_score(&self, score: f32) -> f32 {
+        let max = self.max_possible_score();
+        if max <= 0.0 {
+            return 0.0;
+        }
+        (score / max).clamp(0.0, 1.0)
+    }
+
+    /// The highest fused score a hit could reach under this search's
+    /// config: ranking `0` (first place) on every channel [`FusionStrategy`]
+    /// consults, each weighted by `config.source_weights` - or, under
+    /// [`FusionStrategy::Normalized`], both channels maxed out at `1.0` and
+    /// weighted by its own `fuzzy_weight`/`semantic_weight` fields.
+    fn max_possible_score(&self) -> f32 {
+        match self.config.fusion_strategy {
+            FusionStrategy::Normalized { fuzzy_weight, semantic_weight } => {
+                fuzzy_weight + semantic_weight
+            }
+            FusionStrategy::FuzzyOnly => {
+                self.config.source_weights.0 / (self.config.rrf_k + 1) as f32
+            }
+            FusionStrategy::SemanticOnly => {
+                self.config.source_weights.1 / (self.config.rrf_k + 1) as f32
+            }
+            _ => {
+                let (fuzzy_weight, semantic_weight) = self.config.source_weights;
+                (fuzzy_weight + semantic_weight) / (self.config.rrf_k + 1) as f32
+            }
+        }
+    }
+}
+
+/// Min-max normalizes `ranked`'s raw scores to `0.0..=1.0`, keyed by id, for
+/// [`FusionStrategy::Normalized`]. A channel with fewer than two results has
+/// no spread to normalize across, so every id present gets `1.0` rather than
+/// an arbitrary or undefined ratio; an id absent from `ranked` simply has no
+/// entry, and callers treat a missing entry as `0.0`.
+fn min_max_normalize(ranked: &[(usize, f32)]) -> HashMap<usize, f32> {
+    if ranked.len() < 2 {
+        return ranked.iter().map(|(id, _)| (*id, 1.0)).collect();
+    }
+    let min = ranked.iter().map(|(_, score)| *score).fold(f32::INFINITY, f32::min);
+    let max = ranked.iter().map(|(_, score)| *score).fold(f32::NEG_INFINITY, f32::max);
+    if max <= min {
+        return ranked.iter().map(|(id, _)| (*id, 1.0)).collect();
+    }
+    ranked
+        .iter()
+        .map(|(id, score)| (*id, (score - min) / (max - min)))
+        .collect()
+}
+
+/// Added to a candidate's fused score when it's an exact symbol-name match;
+/// large enough to outrank any RRF score, which never exceeds `1.0`.
+const EXACT_SYMBOL_BOOST: f32 = 1000.0;
+
+/// Identifies both the content of an index and the `RetrievalConfig` fields
+/// that affect scoring, so a query cache entry can detect that either the
+/// index was rebuilt or the config it was computed under has since changed.
+fn compute_generation(chunks: &[CodeChunk], config: &RetrievalConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for chunk in chunks {
+        chunk.path.hash(&mut hasher);
+        chunk.content_hash.hash(&mut hasher);
+    }
+    // `FusionStrategy`/`RerankStrategy` don't implement `Hash` (the latter
+    // carries an `f32`), so their `Debug` output - deterministic for the
+    // finite values these configs actually hold - stands in for one.
+    format!("{:?}", config.fusion_strategy).hash(&mut hasher);
+    config.rrf_k.hash(&mut hasher);
+    config.source_weights.0.to_bits().hash(&mut hasher);
+    config.source_weights.1.to_bits().hash(&mut hasher);
+    config.phrase_exact_bonus.to_bits().hash(&mut hasher);
+    format!("{:?}", config.rerank_strategy).hash(&mut hasher);
+    config.rerank_candidates.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cache_key(query: &str, limit: usize, exact_symbols: &[String]) -> String {
+    format!("{limit}:{}:{}", exact_symbols.join(","), query.trim())
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, content: &str) -> CodeChunk {
+        chunk_with_symbol(path, content, None)
+    }
+
+    fn chunk_with_symbol(path: &str, content: &str, symbol_name: Option<&str>) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 1,
+            content: content.to_string(),
+            language: None,
+            chunk_type: None,
+            symbol_name: symbol_name.map(str::to_string),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn semantic_only_and_fuzzy_only_leave_the_other_side_unset() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn unrelated() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+
+        let semantic_only = HybridRetrieval::with_config(
+            chunks.clone(),
+            embeddings.clone(),
+            RetrievalConfig {
+                fusion_strategy: FusionStrategy::SemanticOnly,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let results = semantic_only.search("parse_error_handling", 10).await;
+        assert!(results.0.iter().all(|r| r.fuzzy_score.is_none()));
+        assert!(results.0.iter().all(|r| r.semantic_score.is_some()));
+
+        let fuzzy_only = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                fusion_strategy: FusionStrategy::FuzzyOnly,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let results = fuzzy_only.search("parse_error_handling", 10).await;
+        assert!(results.0.iter().all(|r| r.semantic_score.is_none()));
+    }
+
+    #[tokio::test]
+    async fn both_channel_hit_is_marked_both() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let retrieval = HybridRetrieval::new(chunks, embeddings);
+        let results = retrieval.search("parse_error_handling", 10).await;
+        assert_eq!(results.0[0].source, SearchSource::Both);
+        assert!(results.0[0].fuzzy_rank.is_some());
+        assert!(results.0[0].semantic_rank.is_some());
+    }
+
+    #[tokio::test]
+    async fn add_chunks_makes_a_new_chunk_searchable_without_a_rebuild() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let mut retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        // The semantic channel ranks every indexed chunk, so "a.rs" is the
+        // only hit until "b.rs" is added.
+        let before = retrieval.search("fn unrelated_thing_entirely", 10).await;
+        assert_eq!(
+            before.0.iter().map(|r| r.chunk.path.as_str()).collect::<Vec<_>>(),
+            vec!["a.rs"]
+        );
+
+        let new_chunk = chunk("b.rs", "fn unrelated_thing_entirely() {}");
+        let new_embedding = EmbeddingService::new().embed(&[new_chunk.content.clone()]);
+        retrieval.add_chunks(vec![new_chunk], new_embedding);
+
+        let after = retrieval.search("fn unrelated_thing_entirely", 10).await;
+        assert!(after.0.iter().any(|r| r.chunk.path == "b.rs"));
+    }
+
+    #[tokio::test]
+    async fn remove_chunks_drops_a_chunk_from_subsequent_results() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn parse_error_handling_too() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let mut retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        let removed = retrieval.remove_chunks(&["a.rs".to_string()]);
+        assert_eq!(removed, 1);
+
+        let results = retrieval.search("parse_error_handling", 10).await;
+        assert!(results.0.iter().all(|r| r.chunk.path != "a.rs"));
+    }
+
+    #[tokio::test]
+    async fn update_chunks_adds_and_removes_in_one_pass() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn parse_error_handling_too() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks.iter().map(|c| c.content.clone()).collect::<Vec<_>>(),
+        );
+        let mut retrieval = HybridRetrieval::new(chunks, embeddings);
+        assert_eq!(retrieval.chunk_count(), 2);
+
+        retrieval.update_chunks(
+            vec![chunk("c.rs", "fn unrelated_thing_entirely() {}")],
+            vec!["a.rs".to_string()],
+        );
+
+        assert_eq!(retrieval.chunk_count(), 2);
+        let results = retrieval.search("fn unrelated_thing_entirely", 10).await;
+        assert!(results.0.iter().any(|r| r.chunk.path == "c.rs"));
+        let results = retrieval.search("parse_error_handling", 10).await;
+        assert!(results.0.iter().all(|r| r.chunk.path != "a.rs"));
+        assert!(results.0.iter().any(|r| r.chunk.path == "b.rs"));
+    }
+
+    #[tokio::test]
+    async fn update_chunks_with_nothing_added_or_removed_is_a_no_op() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let mut retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        retrieval.update_chunks(Vec::new(), Vec::new());
+
+        assert_eq!(retrieval.chunk_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_search_never_observes_a_partially_applied_update() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let mut retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        let before = retrieval.search("parse_error_handling", 10).await;
+        assert_eq!(before.0.len(), 1);
+        assert_eq!(before.0[0].chunk.path, "a.rs");
+
+        retrieval.update_chunks(
+            vec![chunk("b.rs", "fn parse_error_handling() {}")],
+            vec!["a.rs".to_string()],
+        );
+
+        // After `update_chunks` returns, every chunk and embedding in the
+        // index is self-consistent: never `a.rs` still present alongside
+        // `b.rs`, nor an in-between state with neither.
+        let after = retrieval.search("parse_error_handling", 10).await;
+        assert_eq!(after.0.len(), 1);
+        assert_eq!(after.0[0].chunk.path, "b.rs");
+    }
+
+    #[tokio::test]
+    async fn explain_populates_a_breakdown_matching_the_rrf_formula() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                explain: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let results = retrieval.search("parse_error_handling", 10).await;
+
+        let result = &results.0[0];
+        let breakdown = result.breakdown.expect("explain is enabled");
+        assert_eq!(breakdown.fuzzy_rank, result.fuzzy_rank);
+        assert_eq!(breakdown.semantic_rank, result.semantic_rank);
+        let fuzzy_rank = breakdown.fuzzy_rank.expect("hit found by both sources");
+        let semantic_rank = breakdown.semantic_rank.expect("hit found by both sources");
+        let expected_fused_score = 1.0 / (RetrievalConfig::default().rrf_k + fuzzy_rank + 1) as f32
+            + 1.0 / (RetrievalConfig::default().rrf_k + semantic_rank + 1) as f32;
+        assert_eq!(breakdown.fused_score, expected_fused_score);
+        assert_eq!(breakdown.rerank_delta, 0.0);
+    }
+
+    #[tokio::test]
+    async fn above_returns_empty_when_every_hit_is_below_threshold() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn format_exec_output() {}"),
+            chunk("c.rs", "fn compute_replacements() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        let results = retrieval.search("quantum hummingbird teapot", 10).await;
+        assert!(!results.0.is_empty());
+        assert!(results.0.iter().all(|r| r.normalized_score < 0.6));
+        assert!(results.above(0.6).0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn min_score_drops_low_confidence_hits_before_limit_truncation() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn format_exec_output() {}"),
+            chunk("c.rs", "fn compute_replacements() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                min_score: Some(0.6),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let results = retrieval.search("quantum hummingbird teapot", 10).await;
+        assert!(results.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_repeated_query_is_served_from_the_memory_cache() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                memory_cache_capacity: Some(16),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let first = retrieval.search("parse_error_handling", 10).await;
+        let second = retrieval.search("parse_error_handling", 10).await;
+
+        assert_eq!(first, second);
+        assert_eq!(retrieval.cache_stats().misses, 1);
+        assert_eq!(retrieval.cache_stats().memory_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn search_with_stats_reports_all_four_stages_on_a_cache_miss() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn unrelated() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                rerank_strategy: RerankStrategy::Mmr { lambda: 0.5 },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let (_, stats) = retrieval.search_with_stats("parse_error_handling", 10).await;
+
+        for stage in [
+            SearchStage::Fuzzy,
+            SearchStage::Semantic,
+            SearchStage::Fusion,
+            SearchStage::Rerank,
+        ] {
+            let duration = stats
+                .stage_timings
+                .iter()
+                .find(|(s, _)| *s == stage)
+                .map(|(_, duration)| *duration)
+                .unwrap_or_else(|| panic!("missing timing for {stage:?}"));
+            assert!(duration > std::time::Duration::ZERO, "{stage:?} reported zero duration");
+        }
+    }
+
+    #[tokio::test]
+    async fn search_with_stats_reports_no_timings_on_a_cache_hit() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                memory_cache_capacity: Some(16),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let (_, first) = retrieval.search_with_stats("parse_error_handling", 10).await;
+        assert!(!first.stage_timings.is_empty());
+
+        let (_, second) = retrieval.search_with_stats("parse_error_handling", 10).await;
+        assert!(second.stage_timings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_query_cached_to_disk_survives_a_new_hybridretrieval_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let config = RetrievalConfig {
+            cache_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let first_instance =
+            HybridRetrieval::with_config(chunks.clone(), embeddings.clone(), config.clone())
+            .unwrap();
+        let from_fresh_search = first_instance.search("parse_error_handling", 10).await;
+        assert_eq!(first_instance.cache_stats().disk_hits, 0);
+
+        let second_instance = HybridRetrieval::with_config(chunks, embeddings, config).unwrap();
+        let from_disk = second_instance.search("parse_error_handling", 10).await;
+
+        assert_eq!(from_fresh_search, from_disk);
+        assert_eq!(second_instance.cache_stats().disk_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn a_disk_cache_entry_is_ignored_once_the_index_contents_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RetrievalConfig {
+            cache_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let original_chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let original_embeddings =
+            EmbeddingService::new().embed(&[original_chunks[0].content.clone()]);
+        let original = HybridRetrieval::with_config(
+            original_chunks,
+            original_embeddings,
+            config.clone(),
+        )
+        .unwrap();
+        original.search("parse_error_handling", 10).await;
+
+        let changed_chunks = vec![chunk("b.rs", "fn unrelated() {}")];
+        let changed_embeddings =
+            EmbeddingService::new().embed(&[changed_chunks[0].content.clone()]);
+        let changed = HybridRetrieval::with_config(changed_chunks, changed_embeddings, config)
+            .unwrap();
+        changed.search("parse_error_handling", 10).await;
+
+        assert_eq!(changed.cache_stats().disk_hits, 0);
+        assert_eq!(changed.cache_stats().misses, 1);
+    }
+
+    #[tokio::test]
+    async fn a_disk_cache_entry_is_ignored_once_the_rerank_config_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+
+        let original = HybridRetrieval::with_config(
+            chunks.clone(),
+            embeddings.clone(),
+            RetrievalConfig {
+                cache_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        original.search("parse_error_handling", 10).await;
+
+        let reconfigured = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                cache_dir: Some(dir.path().to_path_buf()),
+                rerank_strategy: RerankStrategy::Mmr { lambda: 0.5 },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        reconfigured.search("parse_error_handling", 10).await;
+
+        assert_eq!(reconfigured.cache_stats().disk_hits, 0);
+        assert_eq!(reconfigured.cache_stats().misses, 1);
+    }
+
+    #[tokio::test]
+    async fn a_fresh_instance_warms_its_memory_cache_from_disk_on_construction() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let config = RetrievalConfig {
+            cache_dir: Some(dir.path().to_path_buf()),
+            memory_cache_capacity: Some(10),
+            ..Default::default()
+        };
+
+        let first_instance =
+            HybridRetrieval::with_config(chunks.clone(), embeddings.clone(), config.clone())
+            .unwrap();
+        let from_fresh_search = first_instance.search("parse_error_handling", 10).await;
+
+        // Constructing a second instance pointed at the same `cache_dir`
+        // should warm its in-memory LRU from disk immediately, rather than
+        // waiting for the first query to hit the disk tier.
+        let second_instance = HybridRetrieval::with_config(chunks, embeddings, config).unwrap();
+        let warmed = second_instance.search("parse_error_handling", 10).await;
+
+        assert_eq!(from_fresh_search, warmed);
+        assert_eq!(second_instance.cache_stats().memory_hits, 1);
+        assert_eq!(second_instance.cache_stats().disk_hits, 0);
+    }
+
+    #[tokio::test]
+    async fn search_prioritizing_symbols_ranks_an_exact_symbol_match_first() {
+        let chunks = vec![
+            chunk_with_symbol("a.rs", "fn kill_process(pid: u32) -> Result<()> {}", Some("kill_process")),
+            chunk_with_symbol(
+                "b.rs",
+                "fn shutdown_background_shell_manager() {}",
+                Some("shutdown_background_shell_manager"),
+            ),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let retrieval = HybridRetrieval::new(chunks, embeddings);
+
+        // A plain fused search over this query ranks "b.rs" first since it
+        // semantically and fuzzily matches more of the query text.
+        let fused = retrieval
+            .search("why does background shell manager return NotFound", 10)
+            .await;
+        assert_eq!(fused.0[0].chunk.path, "b.rs");
+
+        let prioritized = retrieval
+            .search_prioritizing_symbols(
+                "why does background shell manager return NotFound",
+                10,
+                &["kill_process".to_string()],
+            )
+            .await;
+        assert_eq!(prioritized.0[0].chunk.path, "a.rs");
+    }
+
+    struct ReverseReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for ReverseReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            mut candidates: Vec<SearchResult>,
+        ) -> Result<Vec<SearchResult>, crate::error::RerankError> {
+            candidates.reverse();
+            Ok(candidates)
+        }
+    }
+
+    struct FailingReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for FailingReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            _candidates: Vec<SearchResult>,
+        ) -> Result<Vec<SearchResult>, crate::error::RerankError> {
+            Err(crate::error::RerankError::Custom("boom".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_reranker_reorders_the_top_candidates() {
+        let chunks = vec![
+            chunk("a.rs", "fn parse_error_handling() {}"),
+            chunk("b.rs", "fn parse_error_handling_too() {}"),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks
+                .iter()
+                .map(|c| c.content.clone())
+                .collect::<Vec<_>>(),
+        );
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                rerank_strategy: RerankStrategy::Custom,
+                ..Default::default()
+            },
+        ).unwrap()
+        .with_reranker(Box::new(ReverseReranker));
+
+        let results = retrieval.search("parse_error_handling", 10).await;
+        assert_eq!(results.0.len(), 2);
+        // A no-op fused search would rank "a.rs" first; the reranker reverses it.
+        assert_eq!(results.0[0].chunk.path, "b.rs");
+    }
+
+    #[tokio::test]
+    async fn a_failing_reranker_falls_back_to_the_fused_ordering() {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                rerank_strategy: RerankStrategy::Custom,
+                ..Default::default()
+            },
+        ).unwrap()
+        .with_reranker(Box::new(FailingReranker));
+
+        let results = retrieval.search("parse_error_handling", 10).await;
+        assert_eq!(results.0.len(), 1);
+        assert_eq!(results.0[0].chunk.path, "a.rs");
+    }
+
+    #[tokio::test]
+    async fn mmr_reranking_promotes_a_distinct_chunk_over_near_duplicates() {
+        let query = "parse error handling retries safely for malformed network responses";
+        let chunks = vec![
+            chunk("dup1.rs", query),
+            chunk(
+                "dup2.rs",
+                "parse error handling retries safely for malformed network responses too",
+            ),
+            chunk("dup3.rs", "parse error handling retries safely for malformed network requests"),
+            chunk(
+                "distinct.rs",
+                "rewrites the deployment rollout schedule used by the release pipeline",
+            ),
+        ];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks.iter().map(|c| c.content.clone()).collect::<Vec<_>>(),
+        );
+
+        let fused = HybridRetrieval::new(chunks.clone(), embeddings.clone())
+            .search(query, 10)
+            .await;
+        let fused_rank = fused
+            .0
+            .iter()
+            .position(|result| result.chunk.path == "distinct.rs")
+            .expect("distinct.rs should still be a candidate");
+        // The three duplicate-ish chunks all score higher than the unrelated
+        // one on relevance alone, so it's ranked last.
+        assert_eq!(fused_rank, 3);
+
+        let mmr = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                rerank_strategy: RerankStrategy::Mmr { lambda: 0.5 },
+                ..Default::default()
+            },
+        ).unwrap()
+        .search(query, 10)
+        .await;
+        let mmr_rank = mmr
+            .0
+            .iter()
+            .position(|result| result.chunk.path == "distinct.rs")
+            .expect("distinct.rs should still be a candidate");
+        // MMR penalizes the duplicates for being redundant with whichever of
+        // them is picked first, promoting the distinct chunk well ahead of
+        // where pure relevance ordering put it.
+        assert!(mmr_rank < fused_rank, "expected {mmr_rank} < {fused_rank}");
+    }
+
+    #[tokio::test]
+    async fn raising_semantic_weight_reorders_results_toward_embedding_ranked_hits() {
+        let query = "parse_error_handling";
+        let query_embedding = EmbeddingService::new().embed(&[query.to_string()]).pop().unwrap();
+        let negated_embedding: Vec<f32> = query_embedding.iter().map(|value| -value).collect();
+
+        // Both chunks contain the query term (so both are BM25 matches),
+        // but "fuzzy_favored" repeats it and so outranks "semantic_favored"
+        // on the fuzzy side. Semantic similarity is assigned the opposite
+        // way regardless of content: "fuzzy_favored" is embedded in the
+        // direction exactly opposite the query, "semantic_favored" in the
+        // same direction as the query.
+        let chunks = vec![
+            chunk(
+                "fuzzy_favored.rs",
+                "parse_error_handling failed because parse_error_handling occurred during the build",
+            ),
+            chunk(
+                "semantic_favored.rs",
+                "parse_error_handling surfaced once while gardening tomatoes bloom nicely",
+            ),
+        ];
+        let embeddings = vec![negated_embedding, query_embedding];
+
+        let default_weights = HybridRetrieval::new(chunks.clone(), embeddings.clone());
+        let results = default_weights.search(query, 10).await;
+        assert_eq!(results.0[0].chunk.path, "fuzzy_favored.rs");
+
+        let semantic_favored = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                source_weights: (1.0, 1_000.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let results = semantic_favored.search(query, 10).await;
+        assert_eq!(results.0[0].chunk.path, "semantic_favored.rs");
+    }
+
+    #[test]
+    fn min_max_normalize_scales_a_crafted_score_set_to_zero_one() {
+        let normalized = min_max_normalize(&[(0, 10.0), (1, 6.0), (2, 2.0)]);
+        assert_eq!(normalized[&0], 1.0);
+        assert_eq!(normalized[&1], 0.5);
+        assert_eq!(normalized[&2], 0.0);
+    }
+
+    #[test]
+    fn min_max_normalize_maps_tied_or_singleton_scores_to_one() {
+        let tied = min_max_normalize(&[(0, 5.0), (1, 5.0)]);
+        assert_eq!(tied[&0], 1.0);
+        assert_eq!(tied[&1], 1.0);
+
+        let singleton = min_max_normalize(&[(0, 5.0)]);
+        assert_eq!(singleton[&0], 1.0);
+
+        assert!(min_max_normalize(&[]).is_empty());
+    }
+
+    #[test]
+    fn normalized_fusion_preserves_a_magnitude_gap_that_rrf_collapses_into_a_tie() {
+        // Fuzzy: id 0 has a commanding lead over id 1. Semantic: id 1 edges
+        // out id 0 by a hair. A rank-only fusion (Rrf) sees "id 0 is fuzzy
+        // rank 0, semantic rank 1" and "id 1 is fuzzy rank 1, semantic rank
+        // 0" as symmetric and ties them - it can't tell that id 0's fuzzy
+        // lead is enormous while id 1's semantic lead is negligible.
+        let fuzzy_ranked = [(0usize, 100.0), (1usize, 1.0), (2usize, 0.0)];
+        let semantic_ranked = [(1usize, 1.001), (0usize, 1.0), (2usize, 0.0)];
+        let rrf_k = 60usize;
+        let rrf = |rank: usize| 1.0 / (rrf_k + rank + 1) as f32;
+
+        let rrf_score =
+            |fuzzy_rank: usize, semantic_rank: usize| rrf(fuzzy_rank) + rrf(semantic_rank);
+        assert_eq!(rrf_score(0, 1), rrf_score(1, 0));
+
+        let fuzzy_norm = min_max_normalize(&fuzzy_ranked);
+        let semantic_norm = min_max_normalize(&semantic_ranked);
+        let normalized_score = |id: usize| fuzzy_norm[&id] + semantic_norm[&id];
+
+        // Normalized instead reflects that id 0's fuzzy lead (1.0 vs 0.01)
+        // dwarfs id 1's semantic lead (1.0 vs 0.999), so id 0 wins clearly.
+        assert!(normalized_score(0) > normalized_score(1) + 0.5);
+    }
+
+    #[tokio::test]
+    async fn phrase_exact_bonus_ranks_the_contiguous_phrase_above_the_scattered_match() {
+        // Both chunks mention "spawn" and "blocking" equally often, so BM25
+        // alone scores them about the same - it only counts term overlap,
+        // not word order or adjacency.
+        let scattered =
+            chunk("scattered.rs", "we spawn the worker and later perform a blocking read");
+        let exact_phrase =
+            chunk("exact.rs", "this helper does spawn blocking work under the hood");
+        let chunks = vec![scattered, exact_phrase];
+        let embeddings = EmbeddingService::new().embed(
+            &chunks.iter().map(|c| c.content.clone()).collect::<Vec<_>>(),
+        );
+
+        let retrieval = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                fusion_strategy: FusionStrategy::FuzzyOnly,
+                phrase_exact_bonus: 50.0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let results = retrieval.search("spawn blocking", 10).await;
+        assert_eq!(results.0[0].chunk.path, "exact.rs");
+    }
+
+    #[test]
+    fn presets_all_pass_validation() {
+        assert_eq!(RetrievalConfig::fast().validate(), Ok(()));
+        assert_eq!(RetrievalConfig::balanced().validate(), Ok(()));
+        assert_eq!(RetrievalConfig::accurate().validate(), Ok(()));
+    }
+
+    #[test]
+    fn builder_changes_one_field_and_keeps_the_rest_at_their_default() {
+        let config = RetrievalConfig::builder().min_score(Some(0.25)).build().unwrap();
+        assert_eq!(config.min_score, Some(0.25));
+        assert_eq!(config.rerank_candidates, RetrievalConfig::default().rerank_candidates);
+    }
+
+    #[test]
+    fn builder_rejects_zero_rerank_candidates_for_a_strategy_that_needs_them() {
+        let err = RetrievalConfig::builder()
+            .rerank_strategy(RerankStrategy::Mmr { lambda: 0.5 })
+            .rerank_candidates(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, RetrievalError::InvalidRerankCandidates { value: 0 });
+    }
+
+    #[test]
+    fn builder_rejects_an_out_of_range_min_score() {
+        let err = RetrievalConfig::builder().min_score(Some(1.5)).build().unwrap_err();
+        assert_eq!(err, RetrievalError::InvalidMinScore { value: 1.5 });
+    }
+
+    #[test]
+    fn builder_rejects_normalized_weights_that_sum_to_zero() {
+        let err = RetrievalConfig::builder()
+            .fusion_strategy(FusionStrategy::Normalized {
+                fuzzy_weight: 0.0,
+                semantic_weight: 0.0,
+            })
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RetrievalError::InvalidFusionWeights {
+                fuzzy_weight: 0.0,
+                semantic_weight: 0.0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn with_config_rejects_an_invalid_hand_rolled_config_instead_of_building_a_broken_index()
+    {
+        let chunks = vec![chunk("a.rs", "fn parse_error_handling() {}")];
+        let embeddings = EmbeddingService::new().embed(&[chunks[0].content.clone()]);
+
+        let err = HybridRetrieval::with_config(
+            chunks,
+            embeddings,
+            RetrievalConfig {
+                min_score: Some(-1.0),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, RetrievalError::InvalidMinScore { value: -1.0 });
+    }
+}
@@ -24,6 +24,7 @@ use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExecCommandBeginEvent;
 use codex_protocol::protocol::ExecCommandEndEvent;
 use codex_protocol::protocol::ExecCommandSource;
+use codex_protocol::protocol::ExecExitSummary;
 use codex_protocol::protocol::ExecCommandStatus;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::TurnStartedEvent;
@@ -227,8 +228,14 @@ pub(crate) async fn execute_user_shell_command(
                         aggregated_output: aborted_message.clone(),
                         exit_code: -1,
                         duration: Duration::ZERO,
-                        formatted_output: aborted_message,
+                        formatted_output: aborted_message.clone(),
                         status: ExecCommandStatus::Failed,
+                        exit_summary: Some(ExecExitSummary::from_event_fields(
+                            Duration::ZERO,
+                            "",
+                            &aborted_message,
+                            &aborted_message,
+                        )),
                     }),
                 )
                 .await;
@@ -260,6 +267,12 @@ pub(crate) async fn execute_user_shell_command(
                         } else {
                             ExecCommandStatus::Failed
                         },
+                        exit_summary: Some(ExecExitSummary::from_event_fields(
+                            output.duration,
+                            &output.stdout.text,
+                            &output.stderr.text,
+                            &output.aggregated_output.text,
+                        )),
                     }),
                 )
                 .await;
@@ -300,6 +313,12 @@ pub(crate) async fn execute_user_shell_command(
                             turn_context.truncation_policy,
                         ),
                         status: ExecCommandStatus::Failed,
+                        exit_summary: Some(ExecExitSummary::from_event_fields(
+                            exec_output.duration,
+                            &exec_output.stdout.text,
+                            &exec_output.stderr.text,
+                            &exec_output.aggregated_output.text,
+                        )),
                     }),
                 )
                 .await;
@@ -350,6 +350,11 @@ pub struct ExecCommandToolOutput {
     pub process_id: Option<i32>,
     pub exit_code: Option<i32>,
     pub original_token_count: Option<usize>,
+    /// Cumulative approx-token output spent against this session's per-turn
+    /// budget so far, including this call - see
+    /// `crate::unified_exec::UnifiedExecProcessManager::account_turn_output`.
+    /// `None` outside of unified exec's own construction sites.
+    pub tokens_used_this_turn: Option<usize>,
     pub session_command: Option<Vec<String>>,
 }
 
@@ -393,6 +398,8 @@ impl ToolOutput for ExecCommandToolOutput {
             session_id: Option<i32>,
             #[serde(skip_serializing_if = "Option::is_none")]
             original_token_count: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            tokens_used_this_turn: Option<usize>,
             output: String,
         }
 
@@ -402,6 +409,7 @@ impl ToolOutput for ExecCommandToolOutput {
             exit_code: self.exit_code,
             session_id: self.process_id,
             original_token_count: self.original_token_count,
+            tokens_used_this_turn: self.tokens_used_this_turn,
             output: self.truncated_output(),
         };
 
@@ -440,6 +448,10 @@ impl ExecCommandToolOutput {
             sections.push(format!("Original token count: {original_token_count}"));
         }
 
+        if let Some(tokens_used_this_turn) = self.tokens_used_this_turn {
+            sections.push(format!("Tokens used this turn: {tokens_used_this_turn}"));
+        }
+
         sections.push("Output:".to_string());
         sections.push(self.truncated_output());
 
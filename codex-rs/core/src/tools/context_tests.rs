@@ -388,6 +388,7 @@ fn exec_command_tool_output_formats_truncated_response() {
         process_id: None,
         exit_code: Some(0),
         original_token_count: Some(10),
+        tokens_used_this_turn: None,
         session_command: None,
     }
     .to_response_item("call-42", &payload);
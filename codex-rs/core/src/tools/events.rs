@@ -10,6 +10,7 @@ use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExecCommandBeginEvent;
 use codex_protocol::protocol::ExecCommandEndEvent;
+use codex_protocol::protocol::ExecExitSummary;
 use codex_protocol::protocol::ExecCommandSource;
 use codex_protocol::protocol::ExecCommandStatus;
 use codex_protocol::protocol::FileChange;
@@ -471,6 +472,12 @@ async fn emit_exec_end(
     exec_input: ExecCommandInput<'_>,
     exec_result: ExecCommandResult,
 ) {
+    let exit_summary = ExecExitSummary::from_event_fields(
+        exec_result.duration,
+        &exec_result.stdout,
+        &exec_result.stderr,
+        &exec_result.aggregated_output,
+    );
     ctx.session
         .send_event(
             ctx.turn,
@@ -490,6 +497,7 @@ async fn emit_exec_end(
                 duration: exec_result.duration,
                 formatted_output: exec_result.formatted_output,
                 status: exec_result.status,
+                exit_summary: Some(exit_summary),
             }),
         )
         .await;
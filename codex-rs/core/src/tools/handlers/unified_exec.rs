@@ -34,6 +34,13 @@ use serde::Deserialize;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Dispatches on `tool_name.name`: `exec_command` opens or reuses a session,
+/// `write_stdin` polls/writes one.
+///
+/// `UnifiedExecProcessManager::list_sessions`/`attach` (`process_manager.rs`)
+/// exist as manager-level methods but aren't reachable from here: the
+/// `codex_tools` plan crate declares no `list_sessions`/`attach` function
+/// tool, and no match arm below routes one.
 pub struct UnifiedExecHandler;
 
 #[derive(Debug, Deserialize)]
@@ -305,6 +312,7 @@ impl ToolHandler for UnifiedExecHandler {
                         process_id: None,
                         exit_code: None,
                         original_token_count: None,
+                        tokens_used_this_turn: None,
                         session_command: None,
                     });
                 }
@@ -345,6 +353,7 @@ impl ToolHandler for UnifiedExecHandler {
                         input: &args.chars,
                         yield_time_ms: args.yield_time_ms,
                         max_output_tokens: args.max_output_tokens,
+                        turn_id: turn.sub_id.clone(),
                     })
                     .await
                     .map_err(|err| {
@@ -256,6 +256,7 @@ fn exec_command_post_tool_use_payload_uses_output_for_noninteractive_one_shot_co
         process_id: None,
         exit_code: Some(0),
         original_token_count: None,
+        tokens_used_this_turn: None,
         session_command: Some(vec![
             "/bin/zsh".to_string(),
             "-lc".to_string(),
@@ -286,6 +287,7 @@ fn exec_command_post_tool_use_payload_skips_interactive_exec() {
         process_id: None,
         exit_code: Some(0),
         original_token_count: None,
+        tokens_used_this_turn: None,
         session_command: Some(vec![
             "/bin/zsh".to_string(),
             "-lc".to_string(),
@@ -313,6 +315,7 @@ fn exec_command_post_tool_use_payload_skips_running_sessions() {
         process_id: Some(45),
         exit_code: None,
         original_token_count: None,
+        tokens_used_this_turn: None,
         session_command: Some(vec![
             "/bin/zsh".to_string(),
             "-lc".to_string(),
@@ -1,10 +1,23 @@
 use crate::unified_exec::UNIFIED_EXEC_OUTPUT_MAX_BYTES;
 use std::collections::VecDeque;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
 
 /// A capped buffer that preserves a stable prefix ("head") and suffix ("tail"),
 /// dropping the middle once it exceeds the configured maximum. The buffer is
 /// symmetric meaning 50% of the capacity is allocated to the head and 50% is
 /// allocated to the tail.
+///
+/// This is the only output-capping buffer in this crate; there is no
+/// separate `ShellLogBuffer`, `LOG_CAPACITY` line-count cap, cursor-based
+/// `read_log`, or `BackgroundShellLogMode` to extend. Bytes evicted from the
+/// middle are tallied in `omitted_bytes`, and - if the buffer was created
+/// with [`Self::with_spill_file`] - also appended to that file before being
+/// dropped, so a caller willing to pay for disk I/O can still recover a
+/// long-running shell's full history; callers that don't opt in keep the
+/// original in-memory-only behavior.
 #[derive(Debug)]
 pub(crate) struct HeadTailBuffer {
     max_bytes: usize,
@@ -15,6 +28,7 @@ pub(crate) struct HeadTailBuffer {
     head_bytes: usize,
     tail_bytes: usize,
     omitted_bytes: usize,
+    spill_file: Option<File>,
 }
 
 impl Default for HeadTailBuffer {
@@ -29,6 +43,22 @@ impl HeadTailBuffer {
     /// The retained output is split across a prefix ("head") and suffix ("tail")
     /// budget, dropping bytes from the middle once the limit is exceeded.
     pub(crate) fn new(max_bytes: usize) -> Self {
+        Self::with_spill(max_bytes, None)
+    }
+
+    /// Like [`Self::new`], but every byte evicted from the middle is also
+    /// appended to `spill_path` (created if it doesn't exist, truncated if
+    /// it does) before being dropped from memory.
+    pub(crate) fn with_spill_file(max_bytes: usize, spill_path: &Path) -> std::io::Result<Self> {
+        let spill_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(spill_path)?;
+        Ok(Self::with_spill(max_bytes, Some(spill_file)))
+    }
+
+    fn with_spill(max_bytes: usize, spill_file: Option<File>) -> Self {
         let head_budget = max_bytes / 2;
         let tail_budget = max_bytes.saturating_sub(head_budget);
         Self {
@@ -40,6 +70,17 @@ impl HeadTailBuffer {
             head_bytes: 0,
             tail_bytes: 0,
             omitted_bytes: 0,
+            spill_file,
+        }
+    }
+
+    /// Appends `evicted` to the spill file (if one was configured) before
+    /// its caller tallies it into `omitted_bytes`. Spill errors (e.g. a full
+    /// disk) are swallowed: losing the evicted bytes a second time over is
+    /// no worse than the no-spill behavior every other buffer already has.
+    fn spill(&mut self, evicted: &[u8]) {
+        if let Some(spill_file) = self.spill_file.as_mut() {
+            let _ = spill_file.write_all(evicted);
         }
     }
 
@@ -64,6 +105,7 @@ impl HeadTailBuffer {
     /// dropped to preserve the tail budget.
     pub(crate) fn push_chunk(&mut self, chunk: Vec<u8>) {
         if self.max_bytes == 0 {
+            self.spill(&chunk);
             self.omitted_bytes = self.omitted_bytes.saturating_add(chunk.len());
             return;
         }
@@ -131,6 +173,7 @@ impl HeadTailBuffer {
 
     fn push_to_tail(&mut self, chunk: Vec<u8>) {
         if self.tail_budget == 0 {
+            self.spill(&chunk);
             self.omitted_bytes = self.omitted_bytes.saturating_add(chunk.len());
             return;
         }
@@ -141,6 +184,11 @@ impl HeadTailBuffer {
             let start = chunk.len().saturating_sub(self.tail_budget);
             let kept = chunk[start..].to_vec();
             let dropped = chunk.len().saturating_sub(kept.len());
+            // Spill in chronological order: the tail's existing (older)
+            // bytes first, then the dropped (newer) prefix of this chunk.
+            let old_tail: Vec<u8> = self.tail.iter().flatten().copied().collect();
+            self.spill(&old_tail);
+            self.spill(&chunk[..dropped]);
             self.omitted_bytes = self
                 .omitted_bytes
                 .saturating_add(self.tail_bytes)
@@ -159,20 +207,24 @@ impl HeadTailBuffer {
     fn trim_tail_to_budget(&mut self) {
         let mut excess = self.tail_bytes.saturating_sub(self.tail_budget);
         while excess > 0 {
-            match self.tail.front_mut() {
+            let dropped = match self.tail.front_mut() {
                 Some(front) if excess >= front.len() => {
                     excess -= front.len();
                     self.tail_bytes = self.tail_bytes.saturating_sub(front.len());
                     self.omitted_bytes = self.omitted_bytes.saturating_add(front.len());
-                    self.tail.pop_front();
+                    self.tail.pop_front()
                 }
                 Some(front) => {
-                    front.drain(..excess);
+                    let dropped = front.drain(..excess).collect();
                     self.tail_bytes = self.tail_bytes.saturating_sub(excess);
                     self.omitted_bytes = self.omitted_bytes.saturating_add(excess);
-                    break;
+                    excess = 0;
+                    Some(dropped)
                 }
                 None => break,
+            };
+            if let Some(dropped) = dropped {
+                self.spill(&dropped);
             }
         }
     }
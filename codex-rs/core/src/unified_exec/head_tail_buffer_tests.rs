@@ -87,3 +87,18 @@ fn fills_head_then_tail_across_multiple_chunks() {
     assert_eq!(buf.to_bytes(), b"012346789a".to_vec());
     assert_eq!(buf.omitted_bytes(), 1);
 }
+
+#[test]
+fn with_spill_file_writes_evicted_middle_bytes_to_disk() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let spill_path = dir.path().join("spill.log");
+    let mut buf =
+        HeadTailBuffer::with_spill_file(/*max_bytes*/ 10, &spill_path).expect("open spill file");
+
+    buf.push_chunk(b"0123456789".to_vec());
+    buf.push_chunk(b"ab".to_vec());
+
+    let spilled = std::fs::read(&spill_path).expect("read spill file");
+    assert_eq!(spilled, b"56".to_vec());
+    assert_eq!(buf.omitted_bytes(), spilled.len());
+}
@@ -21,6 +21,43 @@
 //! - `process.rs`: PTY process lifecycle + output buffering.
 //! - `process_state.rs`: shared exit/failure state for local and remote processes.
 //! - `process_manager.rs`: orchestration (approvals, sandboxing, reuse) and request handling.
+//!
+//! There is no separate `BackgroundShellManager` type or `read_log`/cursor
+//! polling API in this crate, but output already streams rather than being
+//! polled: `UnifiedExecProcess::output_receiver` (`process.rs`) hands out a
+//! `tokio::sync::broadcast::Receiver<Vec<u8>>` fed by
+//! `async_watcher::start_streaming_output`. `UnifiedExecProcessManager::
+//! subscribe_log` wraps that same receiver into line-delimited [`LogLine`]s,
+//! replaying the existing transcript backlog (the same bytes `attach` reads)
+//! before switching to live lines. Same as `attach`/`list_sessions`, it's a
+//! manager-level method that no tool handler routes to (see the
+//! `tools::handlers::unified_exec` handler's doc comment).
+//!
+//! There is likewise no `shell-model` crate or `BackgroundShellWaitParams`
+//! type, but `UnifiedExecProcessManager::wait_for` gives the same
+//! "block until this process finishes" behavior per-process: it wraps the
+//! `tokio::sync::watch::channel::<ProcessState>` each `UnifiedExecProcess`
+//! already holds (`process.rs`), previously only awaited internally (e.g.
+//! the early-exit race in `UnifiedExecProcess::from_spawned`), in a
+//! `watch::Receiver::changed` loop raced against a caller-supplied timeout.
+//!
+//! `resource_sampler.rs` follows the same per-process shape for CPU/RSS:
+//! `UnifiedExecProcess` samples itself by OS pid rather than some
+//! `BackgroundShellManager`-owned table of pids, and `list_sessions`
+//! (`process_manager.rs`) surfaces the latest reading and peak RSS per
+//! `UnifiedExecSessionSnapshot` the same way it already surfaces
+//! `pending_output_bytes`.
+//!
+//! There's likewise no `BackgroundShellKillParams`-style request a caller
+//! can pass its own `grace_period_ms` to, and no `Terminated` event distinct
+//! from the `ExecCommandEnd` `spawn_exit_watcher` (`async_watcher.rs`)
+//! already emits on exit. `UnifiedExecProcess::terminate_with_grace`
+//! (`process.rs`) - SIGTERM the process group, poll for exit, escalate to
+//! the existing hard `terminate()` after `PRUNE_TERMINATE_GRACE_PERIOD_MS` -
+//! is used only for LRU eviction under `MAX_UNIFIED_EXEC_PROCESSES`
+//! (`process_manager::prune_processes_if_needed`); `terminate_all_processes`
+//! and a `write_stdin` whose write failed still call the hard `terminate()`
+//! directly.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -44,6 +81,7 @@ mod head_tail_buffer;
 mod process;
 mod process_manager;
 mod process_state;
+mod resource_sampler;
 
 pub(crate) fn set_deterministic_process_ids_for_tests(enabled: bool) {
     process_manager::set_deterministic_process_ids_for_tests(enabled);
@@ -62,6 +100,14 @@ pub(crate) const MIN_EMPTY_YIELD_TIME_MS: u64 = 5_000;
 pub(crate) const MAX_YIELD_TIME_MS: u64 = 30_000;
 pub(crate) const DEFAULT_MAX_BACKGROUND_TERMINAL_TIMEOUT_MS: u64 = 300_000;
 pub(crate) const DEFAULT_MAX_OUTPUT_TOKENS: usize = 10_000;
+/// Cumulative approx-token output budget for one turn's worth of
+/// `exec_command`/`write_stdin` calls against the same session - see
+/// `UnifiedExecProcessManager::account_turn_output`. Each call's own
+/// `max_output_tokens` already bounds a single read; this instead bounds
+/// the sum across every read in a turn, so a long poll loop can't stay
+/// under that per-call cap while still adding up to tens of thousands of
+/// tokens of terminal noise.
+pub(crate) const DEFAULT_TURN_OUTPUT_TOKEN_BUDGET: usize = DEFAULT_MAX_OUTPUT_TOKENS;
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_TOKENS: usize = UNIFIED_EXEC_OUTPUT_MAX_BYTES / 4;
 pub(crate) const MAX_UNIFIED_EXEC_PROCESSES: usize = 64;
@@ -69,6 +115,22 @@ pub(crate) const MAX_UNIFIED_EXEC_PROCESSES: usize = 64;
 // Send a warning message to the models when it reaches this number of processes.
 pub(crate) const WARNING_UNIFIED_EXEC_PROCESSES: usize = 60;
 
+/// How long an exited process is kept in the store (so a client that hasn't
+/// polled it yet still sees one [`UnifiedExecSessionSnapshot`] tombstone)
+/// before [`UnifiedExecProcessManager::list_sessions`] garbage-collects it.
+pub(crate) const DEFAULT_SESSION_IDLE_TTL_MS: u64 = 5 * 60_000;
+
+/// Grace period given to a still-running process evicted by LRU pruning (see
+/// `process_manager::prune_processes_if_needed`) before it's force-killed -
+/// long enough for a cleanup handler (e.g. `docker compose down` on SIGTERM)
+/// to run rather than leaving containers behind. There's no user-facing
+/// "kill this background shell" tool call in this crate for a caller to pass
+/// its own grace period to. This applies to eviction-under-capacity only;
+/// `terminate_all_processes` and a failed `write_stdin` still force-kill
+/// without a grace period - see
+/// [`process::UnifiedExecProcess::terminate_with_grace`].
+pub(crate) const PRUNE_TERMINATE_GRACE_PERIOD_MS: u64 = 2_000;
+
 pub(crate) struct UnifiedExecContext {
     pub session: Arc<Session>,
     pub turn: Arc<TurnContext>,
@@ -107,6 +169,11 @@ pub(crate) struct WriteStdinRequest<'a> {
     pub input: &'a str,
     pub yield_time_ms: u64,
     pub max_output_tokens: Option<usize>,
+    /// [`crate::codex::TurnContext::sub_id`] of the turn making this call -
+    /// see `UnifiedExecProcessManager::account_turn_output`, which resets
+    /// this session's output token budget whenever this differs from the
+    /// last call's.
+    pub turn_id: String,
 }
 
 #[derive(Default)]
@@ -125,14 +192,23 @@ impl ProcessStore {
 pub(crate) struct UnifiedExecProcessManager {
     process_store: Mutex<ProcessStore>,
     max_write_stdin_yield_time_ms: u64,
+    session_idle_ttl_ms: u64,
 }
 
 impl UnifiedExecProcessManager {
     pub(crate) fn new(max_write_stdin_yield_time_ms: u64) -> Self {
+        Self::with_session_idle_ttl(max_write_stdin_yield_time_ms, DEFAULT_SESSION_IDLE_TTL_MS)
+    }
+
+    pub(crate) fn with_session_idle_ttl(
+        max_write_stdin_yield_time_ms: u64,
+        session_idle_ttl_ms: u64,
+    ) -> Self {
         Self {
             process_store: Mutex::new(ProcessStore::default()),
             max_write_stdin_yield_time_ms: max_write_stdin_yield_time_ms
                 .max(MIN_EMPTY_YIELD_TIME_MS),
+            session_idle_ttl_ms,
         }
     }
 }
@@ -143,21 +219,130 @@ impl Default for UnifiedExecProcessManager {
     }
 }
 
+/// A point-in-time view of one unified-exec session, for
+/// [`UnifiedExecProcessManager::list_sessions`] and `attach`.
+///
+/// `exited` is `None` for a live session and `Some(exit_code)` for a
+/// tombstone: a session that has already exited but was surfaced here so a
+/// client that lost track of its `process_id` (e.g. across a restart) can
+/// still observe that it's gone, rather than getting `UnknownProcessId`.
+/// Tombstones are reported for exactly one `list_sessions` call.
+pub(crate) struct UnifiedExecSessionSnapshot {
+    pub process_id: i32,
+    pub command: Vec<String>,
+    pub cwd: AbsolutePathBuf,
+    pub started_at: tokio::time::Instant,
+    /// Bytes already captured from the process but not yet returned by a
+    /// poll (`exec_command`/`write_stdin`) call.
+    pub pending_output_bytes: usize,
+    pub exited: Option<i32>,
+    /// Most recent CPU/RSS reading, or `None` if this process has no known
+    /// pid or this platform can't sample it (see `resource_sampler`).
+    pub cpu_percent: Option<f32>,
+    pub peak_rss_bytes: Option<u64>,
+    /// Explicit environment overrides (`ShellEnvironmentPolicy::r#set`) this
+    /// session was started with, same as [`ProcessEntry::env_overrides`].
+    pub env_overrides: HashMap<String, String>,
+}
+
+/// One line of output from [`UnifiedExecProcessManager::subscribe_log`], or
+/// the terminal marker sent once the process exits and no more lines will
+/// follow.
+#[derive(Debug, Clone)]
+pub(crate) enum LogLine {
+    Line(String),
+    Terminated { exit_code: Option<i32> },
+}
+
+/// Capacity of the per-subscription broadcast channel
+/// [`UnifiedExecProcessManager::subscribe_log`] creates. Lines are small and
+/// a slow subscriber should see `Lagged` and catch up rather than stall the
+/// line-splitting task, so this is generous relative to
+/// `UnifiedExecProcess`'s own 64-slot raw-byte channel (`process.rs`).
+pub(crate) const LOG_SUBSCRIPTION_CHANNEL_CAPACITY: usize = 256;
+
+/// Result of [`UnifiedExecProcessManager::wait_for`]: either the process had
+/// already exited (or exited before the timeout), or the timeout elapsed
+/// first and the process is still running.
+pub(crate) enum WaitOutcome {
+    Exited { exit_code: Option<i32> },
+    TimedOut,
+}
+
 struct ProcessEntry {
     process: Arc<UnifiedExecProcess>,
     call_id: String,
     process_id: i32,
     command: Vec<String>,
+    cwd: AbsolutePathBuf,
+    /// Explicit environment overrides this session was started with
+    /// (`ShellEnvironmentPolicy::r#set`), surfaced on
+    /// [`UnifiedExecSessionSnapshot`] the same way `cwd` already is.
+    env_overrides: HashMap<String, String>,
     tty: bool,
     network_approval_id: Option<String>,
     session: Weak<Session>,
+    /// Full head/tail-capped output history for this process, shared with
+    /// the background exit watcher; read non-destructively by `attach` so it
+    /// doesn't disturb the bytes the next poll call is waiting to drain.
+    transcript: Arc<Mutex<head_tail_buffer::HeadTailBuffer>>,
+    started_at: tokio::time::Instant,
     last_used: tokio::time::Instant,
+    /// This session's cumulative output token budget for the turn named by
+    /// `turn_output_budget.turn_id` - see
+    /// `UnifiedExecProcessManager::account_turn_output`.
+    turn_output_budget: Mutex<TurnOutputBudget>,
+}
+
+/// Tracks [`ProcessEntry::turn_output_budget`]'s cumulative token spend for
+/// one turn. `turn_id` is empty (never equal to a real
+/// [`crate::codex::TurnContext::sub_id`]) until the first call accounts
+/// against it, so that first call always resets rather than inheriting a
+/// stale `tokens_used` of `0` that happened to already be correct.
+#[derive(Default)]
+pub(crate) struct TurnOutputBudget {
+    turn_id: String,
+    tokens_used: usize,
 }
 
 pub(crate) fn clamp_yield_time(yield_time_ms: u64) -> u64 {
     yield_time_ms.clamp(MIN_YIELD_TIME_MS, MAX_YIELD_TIME_MS)
 }
 
+/// Pure arithmetic behind `UnifiedExecProcessManager::account_turn_output`,
+/// split out so it can be unit tested without a running process or the
+/// process store's lock. Resets `budget` first if `turn_id` differs from its
+/// last call, then either returns a suppression notice in place of `text`
+/// (budget exhausted) or caps `requested_max_tokens` to whatever's left of
+/// the turn budget and records `original_token_count` against it.
+pub(crate) fn apply_turn_output_budget(
+    budget: &mut TurnOutputBudget,
+    turn_id: &str,
+    requested_max_tokens: Option<usize>,
+    original_token_count: usize,
+    text: String,
+) -> (String, Option<usize>, usize) {
+    if budget.turn_id != turn_id {
+        budget.turn_id = turn_id.to_string();
+        budget.tokens_used = 0;
+    }
+
+    let remaining = DEFAULT_TURN_OUTPUT_TOKEN_BUDGET.saturating_sub(budget.tokens_used);
+    if remaining == 0 {
+        let over = original_token_count.max(1);
+        let notice = format!(
+            "output suppressed ({over} tokens over budget); use max_output_tokens to request more"
+        );
+        return (notice, None, budget.tokens_used);
+    }
+
+    let effective_max_tokens = requested_max_tokens
+        .map(|requested| requested.min(remaining))
+        .unwrap_or(remaining);
+    budget.tokens_used += original_token_count.min(effective_max_tokens);
+    (text, Some(effective_max_tokens), budget.tokens_used)
+}
+
 pub(crate) fn resolve_max_tokens(max_tokens: Option<usize>) -> usize {
     max_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS)
 }
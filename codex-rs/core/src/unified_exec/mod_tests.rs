@@ -114,10 +114,15 @@ async fn exec_command_with_tty(
             call_id: context.call_id.clone(),
             process_id,
             command: command.clone(),
+            cwd: cwd.clone(),
+            env_overrides: HashMap::new(),
             tty,
             network_approval_id: None,
             session: Arc::downgrade(session),
+            transcript: Arc::new(tokio::sync::Mutex::new(HeadTailBuffer::default())),
+            started_at,
             last_used: started_at,
+            turn_output_budget: tokio::sync::Mutex::new(TurnOutputBudget::default()),
         };
         manager
             .process_store
@@ -165,6 +170,7 @@ async fn exec_command_with_tty(
         process_id: response_process_id,
         exit_code,
         original_token_count: Some(approx_token_count(&text)),
+        tokens_used_this_turn: None,
         session_command: Some(command),
     })
 }
@@ -194,6 +200,7 @@ async fn write_stdin(
             input,
             yield_time_ms,
             max_output_tokens: None,
+            turn_id: "test-turn".to_string(),
         })
         .await
 }
@@ -319,6 +326,155 @@ async fn multi_unified_exec_sessions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn list_sessions_reports_live_command_and_cwd() -> anyhow::Result<()> {
+    skip_if_sandbox!(Ok(()));
+
+    let (session, turn) = test_session_and_turn().await;
+    let open_shell = exec_command(
+        &session, &turn, "bash -i", /*yield_time_ms*/ 2_500, /*workdir*/ None,
+    )
+    .await?;
+    let process_id = open_shell.process_id.expect("expected process id");
+
+    let sessions = session.services.unified_exec_manager.list_sessions().await;
+    let snapshot = sessions
+        .iter()
+        .find(|snapshot| snapshot.process_id == process_id)
+        .expect("expected a snapshot for the open session");
+    assert_eq!(
+        snapshot.command,
+        vec!["bash".to_string(), "-lc".to_string(), "bash -i".to_string()]
+    );
+    assert_eq!(snapshot.cwd, turn.cwd);
+    assert_eq!(snapshot.env_overrides, turn.shell_environment_policy.r#set);
+    assert!(snapshot.exited.is_none());
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn list_sessions_reports_resource_usage_for_a_live_process() -> anyhow::Result<()> {
+    skip_if_sandbox!(Ok(()));
+
+    let (session, turn) = test_session_and_turn().await;
+    let open_shell = exec_command(
+        &session, &turn, "bash -i", /*yield_time_ms*/ 2_500, /*workdir*/ None,
+    )
+    .await?;
+    let process_id = open_shell.process_id.expect("expected process id");
+
+    // The sampler reads its first sample immediately on spawn, well before
+    // its first interval elapses - this just waits for that task to get
+    // scheduled, not for a whole sampling period.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let sessions = session.services.unified_exec_manager.list_sessions().await;
+    let snapshot = sessions
+        .iter()
+        .find(|snapshot| snapshot.process_id == process_id)
+        .expect("expected a snapshot for the open session");
+    assert!(snapshot.peak_rss_bytes.is_some());
+    assert!(snapshot.cpu_percent.is_some());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn attach_returns_recent_output_without_consuming_the_poll_buffer() -> anyhow::Result<()> {
+    skip_if_sandbox!(Ok(()));
+
+    let (session, turn) = test_session_and_turn().await;
+    let open_shell = exec_command(
+        &session, &turn, "bash -i", /*yield_time_ms*/ 2_500, /*workdir*/ None,
+    )
+    .await?;
+    let process_id = open_shell.process_id.expect("expected process id");
+
+    write_stdin(
+        &session,
+        process_id,
+        "echo from_attach_test\n",
+        /*yield_time_ms*/ 2_500,
+    )
+    .await?;
+
+    let manager = &session.services.unified_exec_manager;
+    let first = manager.attach(process_id).await?;
+    let second = manager.attach(process_id).await?;
+    assert_eq!(first, second, "attach should not consume the transcript");
+    assert!(String::from_utf8_lossy(&first).contains("from_attach_test"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn list_sessions_gcs_idle_exited_sessions_into_a_one_time_tombstone() -> anyhow::Result<()> {
+    skip_if_sandbox!(Ok(()));
+
+    let (session, turn) = test_session_and_turn().await;
+    let manager = UnifiedExecProcessManager::with_session_idle_ttl(
+        DEFAULT_MAX_BACKGROUND_TERMINAL_TIMEOUT_MS,
+        /*session_idle_ttl_ms*/ 0,
+    );
+
+    let process_id = manager.allocate_process_id().await;
+    let command = vec!["bash".to_string(), "-lc".to_string(), "exit 3".to_string()];
+    let request = test_exec_request(&turn, command.clone(), turn.cwd.clone(), shell_env());
+    let process = Arc::new(
+        manager
+            .open_session_with_exec_env(
+                process_id,
+                &request,
+                /*tty*/ false,
+                Box::new(NoopSpawnLifecycle),
+                turn.environment.as_ref().expect("turn environment"),
+            )
+            .await?,
+    );
+
+    while !process.has_exited() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    manager.process_store.lock().await.processes.insert(
+        process_id,
+        ProcessEntry {
+            process: Arc::clone(&process),
+            call_id: "call".to_string(),
+            process_id,
+            command: command.clone(),
+            cwd: turn.cwd.clone(),
+            env_overrides: HashMap::new(),
+            tty: false,
+            network_approval_id: None,
+            session: Arc::downgrade(&session),
+            transcript: Arc::new(tokio::sync::Mutex::new(HeadTailBuffer::default())),
+            started_at: Instant::now(),
+            last_used: Instant::now(),
+            turn_output_budget: tokio::sync::Mutex::new(TurnOutputBudget::default()),
+        },
+    );
+
+    let sessions = manager.list_sessions().await;
+    let tombstone = sessions
+        .iter()
+        .find(|snapshot| snapshot.process_id == process_id)
+        .expect("expected a tombstone for the exited session");
+    assert_eq!(tombstone.exited, Some(3));
+
+    let sessions_again = manager.list_sessions().await;
+    assert!(
+        sessions_again
+            .iter()
+            .all(|snapshot| snapshot.process_id != process_id),
+        "tombstone should only be reported once"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn unified_exec_timeouts() -> anyhow::Result<()> {
     skip_if_sandbox!(Ok(()));
@@ -29,6 +29,10 @@ use super::UNIFIED_EXEC_OUTPUT_MAX_TOKENS;
 use super::UnifiedExecError;
 use super::head_tail_buffer::HeadTailBuffer;
 use super::process_state::ProcessState;
+use super::resource_sampler;
+use super::resource_sampler::DEFAULT_SAMPLE_INTERVAL;
+use super::resource_sampler::ResourceSample;
+use super::resource_sampler::ResourceUsage;
 
 const EARLY_EXIT_GRACE_PERIOD: Duration = Duration::from_millis(150);
 
@@ -85,6 +89,9 @@ pub(crate) struct UnifiedExecProcess {
     output_task: Option<JoinHandle<()>>,
     sandbox_type: SandboxType,
     _spawn_lifecycle: Option<SpawnLifecycleHandle>,
+    pid: Option<u32>,
+    resource_usage: Arc<ResourceUsage>,
+    resource_sample_task: Option<JoinHandle<()>>,
 }
 
 impl std::fmt::Debug for UnifiedExecProcess {
@@ -126,9 +133,44 @@ impl UnifiedExecProcess {
             output_task: None,
             sandbox_type,
             _spawn_lifecycle: spawn_lifecycle,
+            pid: None,
+            resource_usage: Arc::new(ResourceUsage::default()),
+            resource_sample_task: None,
         }
     }
 
+    /// Records `pid` and starts sampling its CPU/RSS every `interval`,
+    /// stopping on its own once [`Self::cancellation_token`] fires. Spawns no
+    /// sampling task if this platform doesn't support sampling (see
+    /// `resource_sampler`); `from_spawned` only calls this when the spawn
+    /// backend captured a pid.
+    fn start_resource_sampling(&mut self, pid: u32, interval: Duration) {
+        self.pid = Some(pid);
+        self.resource_sample_task = resource_sampler::spawn(
+            pid,
+            interval,
+            Arc::clone(&self.resource_usage),
+            self.cancellation_token.clone(),
+        );
+    }
+
+    /// The OS pid this process was spawned with, if the spawn backend
+    /// captured one.
+    pub(crate) fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// The most recent CPU/RSS sample, or `None` if sampling hasn't produced
+    /// one yet (including: sampling was never started for this process).
+    pub(crate) fn latest_resource_sample(&self) -> Option<ResourceSample> {
+        self.resource_usage.latest()
+    }
+
+    /// The highest RSS observed across this process's lifetime so far.
+    pub(crate) fn peak_rss_bytes(&self) -> Option<u64> {
+        self.resource_usage.peak_rss_bytes()
+    }
+
     pub(super) async fn write(&self, data: &[u8]) -> Result<(), UnifiedExecError> {
         match &self.process_handle {
             ProcessHandle::Local(process_handle) => process_handle
@@ -168,6 +210,13 @@ impl UnifiedExecProcess {
         self.output_tx.subscribe()
     }
 
+    /// A cloned handle onto this process's exit-state watch channel, for
+    /// [`super::process_manager::UnifiedExecProcessManager::wait_for`] to
+    /// await a change on without holding the process store's lock.
+    pub(super) fn state_receiver(&self) -> watch::Receiver<ProcessState> {
+        self.state_rx.clone()
+    }
+
     pub(super) fn cancellation_token(&self) -> CancellationToken {
         self.cancellation_token.clone()
     }
@@ -210,6 +259,47 @@ impl UnifiedExecProcess {
         if let Some(output_task) = &self.output_task {
             output_task.abort();
         }
+        if let Some(resource_sample_task) = &self.resource_sample_task {
+            resource_sample_task.abort();
+        }
+    }
+
+    /// Sends SIGTERM to this process's group and polls up to `grace_period_ms`
+    /// for it to exit on its own (so cleanup handlers like `docker compose`'s
+    /// can run) before falling back to [`Self::terminate`]'s hard kill.
+    /// Returns `true` if the process exited gracefully within the grace
+    /// period, `false` if it had to be force-killed.
+    ///
+    /// Falls straight back to [`Self::terminate`] - no SIGTERM, no grace
+    /// period - when `grace_period_ms` is `None`, this process has no known
+    /// pid (the exec-server-backed transport; see [`Self::pid`]), or this
+    /// platform's `terminate_process_group` is a no-op (Windows). That last
+    /// case is exactly "grace is a no-op but still succeeds".
+    pub(super) async fn terminate_with_grace(&self, grace_period_ms: Option<u64>) -> bool {
+        let sent_sigterm = match (grace_period_ms, self.pid) {
+            (Some(_), Some(pid)) => {
+                codex_utils_pty::process_group::terminate_process_group(pid).unwrap_or(false)
+            }
+            _ => false,
+        };
+        let Some(grace_period_ms) = grace_period_ms.filter(|_| sent_sigterm) else {
+            self.terminate();
+            return false;
+        };
+
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(grace_period_ms);
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+        while tokio::time::Instant::now() < deadline {
+            if self.has_exited() {
+                self.terminate();
+                return true;
+            }
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            tokio::time::sleep(POLL_INTERVAL.min(remaining)).await;
+        }
+
+        self.terminate();
+        false
     }
 
     async fn snapshot_output(&self) -> Vec<Vec<u8>> {
@@ -284,11 +374,15 @@ impl UnifiedExecProcess {
             mut exit_rx,
         } = spawned;
         let output_rx = codex_utils_pty::combine_output_receivers(stdout_rx, stderr_rx);
+        let pid = process_handle.pid();
         let mut managed = Self::new(
             ProcessHandle::Local(Box::new(process_handle)),
             sandbox_type,
             Some(spawn_lifecycle),
         );
+        if let Some(pid) = pid {
+            managed.start_resource_sampling(pid, DEFAULT_SAMPLE_INTERVAL);
+        }
         managed.output_task = Some(Self::spawn_local_output_task(
             output_rx,
             Arc::clone(&managed.output_buffer),
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use tokio::sync::Notify;
+use tokio::sync::broadcast;
 use tokio::sync::watch;
 use tokio::time::Duration;
 use tokio::time::Instant;
@@ -26,18 +27,26 @@ use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::runtimes::unified_exec::UnifiedExecRequest as UnifiedExecToolRequest;
 use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
 use crate::tools::sandboxing::ToolCtx;
+use crate::unified_exec::DEFAULT_TURN_OUTPUT_TOKEN_BUDGET;
 use crate::unified_exec::ExecCommandRequest;
+use crate::unified_exec::LOG_SUBSCRIPTION_CHANNEL_CAPACITY;
+use crate::unified_exec::LogLine;
 use crate::unified_exec::MAX_UNIFIED_EXEC_PROCESSES;
 use crate::unified_exec::MAX_YIELD_TIME_MS;
 use crate::unified_exec::MIN_EMPTY_YIELD_TIME_MS;
 use crate::unified_exec::MIN_YIELD_TIME_MS;
+use crate::unified_exec::PRUNE_TERMINATE_GRACE_PERIOD_MS;
 use crate::unified_exec::ProcessEntry;
 use crate::unified_exec::ProcessStore;
+use crate::unified_exec::TurnOutputBudget;
 use crate::unified_exec::UnifiedExecContext;
 use crate::unified_exec::UnifiedExecError;
 use crate::unified_exec::UnifiedExecProcessManager;
+use crate::unified_exec::UnifiedExecSessionSnapshot;
 use crate::unified_exec::WARNING_UNIFIED_EXEC_PROCESSES;
+use crate::unified_exec::WaitOutcome;
 use crate::unified_exec::WriteStdinRequest;
+use crate::unified_exec::apply_turn_output_budget;
 use crate::unified_exec::async_watcher::emit_exec_end_for_unified_exec;
 use crate::unified_exec::async_watcher::emit_failed_exec_end_for_unified_exec;
 use crate::unified_exec::async_watcher::spawn_exit_watcher;
@@ -174,6 +183,19 @@ fn exec_server_process_id(process_id: i32) -> String {
     process_id.to_string()
 }
 
+/// Splits complete, newline-terminated lines off the front of `carry`,
+/// leaving any trailing partial line in place for the next call - the pure
+/// line-framing behind [`UnifiedExecProcessManager::subscribe_log`], kept
+/// free of the channel/receiver plumbing so it can be unit tested directly.
+fn drain_complete_lines(carry: &mut Vec<u8>) -> Vec<String> {
+    let mut lines = Vec::new();
+    while let Some(newline_at) = carry.iter().position(|&byte| byte == b'\n') {
+        let line: Vec<u8> = carry.drain(..=newline_at).collect();
+        lines.push(String::from_utf8_lossy(&line[..line.len() - 1]).into_owned());
+    }
+    lines
+}
+
 impl UnifiedExecProcessManager {
     pub(crate) async fn allocate_process_id(&self) -> i32 {
         loop {
@@ -224,6 +246,232 @@ impl UnifiedExecProcessManager {
         }
     }
 
+    /// Lists every live session plus a tombstone for each session this call
+    /// just garbage-collected for having sat exited and idle past
+    /// `session_idle_ttl_ms` - each tombstone is reported for exactly this
+    /// one call, since the entry backing it is already gone from the store
+    /// by the time this returns.
+    ///
+    /// A live session's `pending_output_bytes` is read from its per-call poll
+    /// buffer, not the `transcript` `attach` reads from, since that's the
+    /// count a client deciding whether to poll again actually cares about.
+    pub(crate) async fn list_sessions(&self) -> Vec<UnifiedExecSessionSnapshot> {
+        struct Live {
+            process_id: i32,
+            process: Arc<UnifiedExecProcess>,
+            command: Vec<String>,
+            cwd: AbsolutePathBuf,
+            env_overrides: HashMap<String, String>,
+            started_at: Instant,
+        }
+
+        let (live, idle_exited) = {
+            let mut store = self.process_store.lock().await;
+            let idle_exited =
+                Self::take_idle_exited_processes(&mut store, self.session_idle_ttl_ms);
+
+            let live = store
+                .processes
+                .values()
+                .map(|entry| Live {
+                    process_id: entry.process_id,
+                    process: Arc::clone(&entry.process),
+                    command: entry.command.clone(),
+                    cwd: entry.cwd.clone(),
+                    env_overrides: entry.env_overrides.clone(),
+                    started_at: entry.started_at,
+                })
+                .collect::<Vec<_>>();
+            (live, idle_exited)
+        };
+
+        let mut snapshots = Vec::with_capacity(live.len() + idle_exited.len());
+        for entry in live {
+            let output_buffer = entry.process.output_handles().output_buffer;
+            let pending_output_bytes = output_buffer.lock().await.retained_bytes();
+            let latest_sample = entry.process.latest_resource_sample();
+            snapshots.push(UnifiedExecSessionSnapshot {
+                process_id: entry.process_id,
+                command: entry.command,
+                cwd: entry.cwd,
+                started_at: entry.started_at,
+                pending_output_bytes,
+                exited: None,
+                cpu_percent: latest_sample.map(|sample| sample.cpu_percent),
+                peak_rss_bytes: entry.process.peak_rss_bytes(),
+                env_overrides: entry.env_overrides,
+            });
+        }
+        for entry in idle_exited {
+            Self::unregister_network_approval_for_entry(&entry).await;
+            let exit_code = entry.process.exit_code();
+            let peak_rss_bytes = entry.process.peak_rss_bytes();
+            snapshots.push(UnifiedExecSessionSnapshot {
+                process_id: entry.process_id,
+                command: entry.command,
+                cwd: entry.cwd,
+                started_at: entry.started_at,
+                pending_output_bytes: 0,
+                exited: Some(exit_code.unwrap_or(-1)),
+                cpu_percent: None,
+                peak_rss_bytes,
+                env_overrides: entry.env_overrides,
+            });
+        }
+        snapshots
+    }
+
+    /// Returns the recent output window for `process_id` without writing to
+    /// its stdin, so a client that lost track of a session (e.g. after
+    /// restarting) can re-attach and see what it missed.
+    ///
+    /// Reads the `transcript` (the same head/tail-capped history the
+    /// background exit watcher emits `ExecCommandEnd` from) rather than the
+    /// per-call poll buffer `exec_command`/`write_stdin` drain, since the
+    /// poll buffer may already be empty if another caller polled first.
+    pub(crate) async fn attach(&self, process_id: i32) -> Result<Vec<u8>, UnifiedExecError> {
+        let transcript = {
+            let store = self.process_store.lock().await;
+            let entry = store
+                .processes
+                .get(&process_id)
+                .ok_or(UnifiedExecError::UnknownProcessId { process_id })?;
+            Arc::clone(&entry.transcript)
+        };
+        Ok(transcript.lock().await.to_bytes())
+    }
+
+    /// Subscribes to `process_id`'s output as line-delimited [`LogLine`]s:
+    /// the existing transcript (the same bytes [`Self::attach`] reads)
+    /// replayed as complete lines first, then every new line as it arrives,
+    /// ending with a single [`LogLine::Terminated`] once the underlying
+    /// byte stream (`UnifiedExecProcess::output_receiver`) closes.
+    ///
+    /// Subscribes to the raw byte broadcast *before* reading the transcript
+    /// snapshot, so a line that arrives in the gap between the two is
+    /// replayed once from the backlog rather than lost - duplicating a line
+    /// is preferable to dropping one.
+    pub(crate) async fn subscribe_log(
+        &self,
+        process_id: i32,
+    ) -> Result<broadcast::Receiver<LogLine>, UnifiedExecError> {
+        let (process, transcript) = {
+            let store = self.process_store.lock().await;
+            let entry = store
+                .processes
+                .get(&process_id)
+                .ok_or(UnifiedExecError::UnknownProcessId { process_id })?;
+            (Arc::clone(&entry.process), Arc::clone(&entry.transcript))
+        };
+
+        let mut byte_receiver = process.output_receiver();
+        let mut carry = transcript.lock().await.to_bytes();
+
+        let (tx, rx) = broadcast::channel(LOG_SUBSCRIPTION_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            for line in drain_complete_lines(&mut carry) {
+                if tx.send(LogLine::Line(line)).is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                match byte_receiver.recv().await {
+                    Ok(chunk) => {
+                        carry.extend_from_slice(&chunk);
+                        for line in drain_complete_lines(&mut carry) {
+                            if tx.send(LogLine::Line(line)).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            if !carry.is_empty() {
+                let trailing = String::from_utf8_lossy(&carry).into_owned();
+                let _ = tx.send(LogLine::Line(trailing));
+            }
+            let _ = tx.send(LogLine::Terminated {
+                exit_code: process.exit_code(),
+            });
+        });
+
+        Ok(rx)
+    }
+
+    /// Blocks until `process_id` exits or `timeout` elapses, whichever comes
+    /// first, by racing `UnifiedExecProcess`'s exit-state watch channel
+    /// against the timeout rather than polling.
+    pub(crate) async fn wait_for(
+        &self,
+        process_id: i32,
+        timeout: Duration,
+    ) -> Result<WaitOutcome, UnifiedExecError> {
+        let process = {
+            let store = self.process_store.lock().await;
+            let entry = store
+                .processes
+                .get(&process_id)
+                .ok_or(UnifiedExecError::UnknownProcessId { process_id })?;
+            Arc::clone(&entry.process)
+        };
+
+        if process.has_exited() {
+            return Ok(WaitOutcome::Exited {
+                exit_code: process.exit_code(),
+            });
+        }
+
+        let mut state_rx = process.state_receiver();
+        let deadline = Instant::now() + timeout;
+        while !process.has_exited() {
+            match tokio::time::timeout_at(deadline, state_rx.changed()).await {
+                // A new state was published; loop back around and check
+                // has_exited() again - it may have been a failure_message
+                // update rather than the exit itself.
+                Ok(Ok(())) => continue,
+                // The sender side (owned by this same `process`) can't drop
+                // while we're holding an `Arc` to it, so this is unreachable
+                // in practice; treat it the same as an exit rather than
+                // looping forever.
+                Ok(Err(_)) => break,
+                Err(_) => return Ok(WaitOutcome::TimedOut),
+            }
+        }
+        Ok(WaitOutcome::Exited {
+            exit_code: process.exit_code(),
+        })
+    }
+
+    /// Removes and returns exited sessions that have sat untouched for longer
+    /// than `idle_ttl_ms`.
+    ///
+    /// This is a backstop for sessions a client stopped polling after they
+    /// exited: a still-polled session is already pruned by
+    /// `refresh_process_state` the moment its exit is observed, without ever
+    /// going through here.
+    fn take_idle_exited_processes(store: &mut ProcessStore, idle_ttl_ms: u64) -> Vec<ProcessEntry> {
+        let idle_ttl = Duration::from_millis(idle_ttl_ms);
+        let now = Instant::now();
+        let idle_exited: Vec<i32> = store
+            .processes
+            .iter()
+            .filter(|(_, entry)| {
+                entry.process.has_exited()
+                    && now.saturating_duration_since(entry.last_used) >= idle_ttl
+            })
+            .map(|(process_id, _)| *process_id)
+            .collect();
+
+        idle_exited
+            .into_iter()
+            .filter_map(|process_id| store.remove(process_id))
+            .collect()
+    }
+
     pub(crate) async fn exec_command(
         &self,
         request: ExecCommandRequest,
@@ -384,16 +632,31 @@ impl UnifiedExecProcessManager {
             (None, exit_code)
         };
 
+        // There's no per-call `Tokenizer::try_default()`/vocabulary load to
+        // amortize here: `approx_token_count` (and `formatted_truncate_text`'s
+        // `TruncationPolicy::Tokens` path in `process.rs`) are both pure
+        // `len / 4` byte-length arithmetic, not a real tokenizer, so there's
+        // no shared instance to construct once and reuse across calls.
         let original_token_count = approx_token_count(&text);
+        let (text, max_output_tokens, tokens_used_this_turn) = self
+            .account_turn_output(
+                process_id,
+                &context.turn.sub_id,
+                request.max_output_tokens,
+                original_token_count,
+                text,
+            )
+            .await;
         let response = ExecCommandToolOutput {
             event_call_id: context.call_id.clone(),
             chunk_id,
             wall_time,
-            raw_output: collected,
-            max_output_tokens: request.max_output_tokens,
+            raw_output: text.into_bytes(),
+            max_output_tokens,
             process_id: response_process_id,
             exit_code,
             original_token_count: Some(original_token_count),
+            tokens_used_this_turn: Some(tokens_used_this_turn),
             session_command: Some(request.command.clone()),
         };
 
@@ -477,6 +740,15 @@ impl UnifiedExecProcessManager {
             self.release_process_id(process_id).await;
             return Err(UnifiedExecError::process_failed(message));
         }
+        let (text, max_output_tokens, tokens_used_this_turn) = self
+            .account_turn_output(
+                process_id,
+                &request.turn_id,
+                request.max_output_tokens,
+                original_token_count,
+                text,
+            )
+            .await;
 
         // After polling, refresh_process_state tells us whether the PTY is
         // still alive or has exited and been removed from the store; we thread
@@ -508,17 +780,55 @@ impl UnifiedExecProcessManager {
             event_call_id,
             chunk_id,
             wall_time,
-            raw_output: collected,
-            max_output_tokens: request.max_output_tokens,
+            raw_output: text.into_bytes(),
+            max_output_tokens,
             process_id,
             exit_code,
             original_token_count: Some(original_token_count),
+            tokens_used_this_turn: Some(tokens_used_this_turn),
             session_command: Some(session_command.clone()),
         };
 
         Ok(response)
     }
 
+    /// Applies `process_id`'s cumulative per-turn output token budget to a
+    /// call that captured `original_token_count` tokens of output, resetting
+    /// the budget first if `turn_id` differs from the session's last call.
+    /// Once the budget is exhausted, `text` is replaced by a compact
+    /// suppression notice for the rest of the turn. A `requested_max_tokens`
+    /// still caps a single call's own output, but never above what's left
+    /// of the turn budget - "honored up to the remaining budget".
+    ///
+    /// A process with no stored [`ProcessEntry`] (a one-shot command that
+    /// already exited before `exec_command` could store one) has no budget
+    /// to account against, since there's no later call on the same session
+    /// to have accumulated with - `text`/`requested_max_tokens` are
+    /// returned unchanged. The actual arithmetic lives in
+    /// [`apply_turn_output_budget`], which is unit tested directly.
+    async fn account_turn_output(
+        &self,
+        process_id: i32,
+        turn_id: &str,
+        requested_max_tokens: Option<usize>,
+        original_token_count: usize,
+        text: String,
+    ) -> (String, Option<usize>, usize) {
+        let store = self.process_store.lock().await;
+        let Some(entry) = store.processes.get(&process_id) else {
+            return (text, requested_max_tokens, original_token_count);
+        };
+
+        let mut budget = entry.turn_output_budget.lock().await;
+        apply_turn_output_budget(
+            &mut budget,
+            turn_id,
+            requested_max_tokens,
+            original_token_count,
+            text,
+        )
+    }
+
     async fn refresh_process_state(&self, process_id: i32) -> ProcessStatus {
         let status = {
             let mut store = self.process_store.lock().await;
@@ -605,10 +915,15 @@ impl UnifiedExecProcessManager {
             call_id: context.call_id.clone(),
             process_id,
             command: command.to_vec(),
+            cwd: cwd.clone(),
+            env_overrides: context.turn.shell_environment_policy.r#set.clone(),
             tty,
             network_approval_id,
             session: Arc::downgrade(&context.session),
+            transcript: Arc::clone(&transcript),
+            started_at,
             last_used: started_at,
+            turn_output_budget: tokio::sync::Mutex::new(TurnOutputBudget::default()),
         };
         let (number_processes, pruned_entry) = {
             let mut store = self.process_store.lock().await;
@@ -620,7 +935,17 @@ impl UnifiedExecProcessManager {
         // network-approval cleanup only after dropping that lock.
         if let Some(pruned_entry) = pruned_entry {
             Self::unregister_network_approval_for_entry(&pruned_entry).await;
-            pruned_entry.process.terminate();
+            // Graceful: give the evicted process a chance to run its own
+            // cleanup handlers before force-killing it - see
+            // `PRUNE_TERMINATE_GRACE_PERIOD_MS`. Spawned rather than awaited
+            // so opening the new process that triggered this eviction isn't
+            // held up by the grace period.
+            let pruned_process = Arc::clone(&pruned_entry.process);
+            tokio::spawn(async move {
+                pruned_process
+                    .terminate_with_grace(Some(PRUNE_TERMINATE_GRACE_PERIOD_MS))
+                    .await;
+            });
         }
 
         if number_processes >= WARNING_UNIFIED_EXEC_PROCESSES {
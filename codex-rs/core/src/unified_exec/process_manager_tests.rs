@@ -188,3 +188,69 @@ fn pruning_protects_recent_processes_even_if_exited() {
     // (10) is exited but among the last 8; we should drop the LRU outside that set.
     assert_eq!(candidate, Some(1));
 }
+
+#[test]
+fn turn_output_budget_caps_requested_max_tokens_to_what_remains() {
+    let mut budget = TurnOutputBudget::default();
+
+    let (text, max_output_tokens, tokens_used_this_turn) = apply_turn_output_budget(
+        &mut budget,
+        "turn-1",
+        Some(DEFAULT_TURN_OUTPUT_TOKEN_BUDGET + 1_000),
+        100,
+        "hello".to_string(),
+    );
+
+    assert_eq!(text, "hello");
+    assert_eq!(max_output_tokens, Some(DEFAULT_TURN_OUTPUT_TOKEN_BUDGET));
+    assert_eq!(tokens_used_this_turn, 100);
+}
+
+#[test]
+fn turn_output_budget_accumulates_across_calls_in_the_same_turn() {
+    let mut budget = TurnOutputBudget::default();
+
+    apply_turn_output_budget(&mut budget, "turn-1", None, 100, "first".to_string());
+    let (_, _, tokens_used_this_turn) =
+        apply_turn_output_budget(&mut budget, "turn-1", None, 50, "second".to_string());
+
+    assert_eq!(tokens_used_this_turn, 150);
+}
+
+#[test]
+fn turn_output_budget_resets_when_the_turn_id_changes() {
+    let mut budget = TurnOutputBudget::default();
+
+    apply_turn_output_budget(
+        &mut budget,
+        "turn-1",
+        None,
+        DEFAULT_TURN_OUTPUT_TOKEN_BUDGET,
+        "first".to_string(),
+    );
+    let (text, max_output_tokens, tokens_used_this_turn) =
+        apply_turn_output_budget(&mut budget, "turn-2", None, 10, "second".to_string());
+
+    assert_eq!(text, "second");
+    assert_eq!(max_output_tokens, Some(DEFAULT_TURN_OUTPUT_TOKEN_BUDGET));
+    assert_eq!(tokens_used_this_turn, 10);
+}
+
+#[test]
+fn turn_output_budget_suppresses_output_once_exhausted() {
+    let mut budget = TurnOutputBudget::default();
+
+    apply_turn_output_budget(
+        &mut budget,
+        "turn-1",
+        None,
+        DEFAULT_TURN_OUTPUT_TOKEN_BUDGET,
+        "first".to_string(),
+    );
+    let (text, max_output_tokens, tokens_used_this_turn) =
+        apply_turn_output_budget(&mut budget, "turn-1", None, 500, "second".to_string());
+
+    assert!(text.contains("output suppressed"));
+    assert_eq!(max_output_tokens, None);
+    assert_eq!(tokens_used_this_turn, DEFAULT_TURN_OUTPUT_TOKEN_BUDGET);
+}
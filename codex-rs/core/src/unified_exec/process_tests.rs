@@ -140,3 +140,27 @@ async fn remote_process_waits_for_early_exit_event() {
     assert!(process.has_exited());
     assert_eq!(process.exit_code(), Some(17));
 }
+
+#[tokio::test]
+async fn terminate_with_grace_force_kills_immediately_when_no_pid_is_known() {
+    // The exec-server-backed transport has no OS pid to send SIGTERM to
+    // (see `UnifiedExecProcess::pid`), so grace is skipped entirely rather
+    // than waiting out the grace period before giving up on it.
+    let process = remote_process(WriteStatus::Accepted).await;
+
+    let exited_gracefully = tokio::time::timeout(
+        Duration::from_millis(200),
+        process.terminate_with_grace(Some(60_000)),
+    )
+    .await
+    .expect("terminate_with_grace should return without waiting out the grace period");
+
+    assert!(!exited_gracefully);
+}
+
+#[tokio::test]
+async fn terminate_with_grace_force_kills_immediately_when_grace_period_is_none() {
+    let process = remote_process(WriteStatus::Accepted).await;
+
+    assert!(!process.terminate_with_grace(None).await);
+}
@@ -0,0 +1,194 @@
+//! Periodic CPU/RSS sampling for a [`UnifiedExecProcess`](super::UnifiedExecProcess)
+//! by OS pid.
+//!
+//! There's no `BackgroundShellManager`/`ShellState` type in this crate for a
+//! sampler to hang off of - see the module doc on `unified_exec` for the
+//! other pieces of that type that don't exist here either. This samples the
+//! process directly instead, since that's the thing in this crate that
+//! actually owns a pid (when the spawn backend captured one; see
+//! `codex_utils_pty::ProcessHandle::pid`). Sampling is Linux-only today
+//! (`/proc/<pid>/stat` + `/proc/<pid>/status`) - on other platforms, or for
+//! the exec-server-backed transport (whose process lives in a different
+//! process entirely), [`read_sample`] always returns `None` and every
+//! [`ResourceUsage`] stays empty.
+
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// How often a sampler reads `/proc` for a process's CPU/RSS, absent an
+/// explicit interval.
+pub(crate) const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// One point-in-time resource reading for a sampled process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ResourceSample {
+    pub(crate) cpu_percent: f32,
+    pub(crate) rss_bytes: u64,
+}
+
+/// Shared state a sampling task publishes into and
+/// [`UnifiedExecProcess`](super::UnifiedExecProcess) reads back out of.
+#[derive(Default)]
+pub(crate) struct ResourceUsage {
+    latest: StdMutex<Option<ResourceSample>>,
+    peak_rss_bytes: AtomicU64,
+}
+
+impl ResourceUsage {
+    pub(crate) fn latest(&self) -> Option<ResourceSample> {
+        self.latest.lock().ok().and_then(|guard| *guard)
+    }
+
+    /// The highest RSS observed so far, or `None` if nothing has been
+    /// sampled yet.
+    pub(crate) fn peak_rss_bytes(&self) -> Option<u64> {
+        match self.peak_rss_bytes.load(Ordering::Relaxed) {
+            0 => None,
+            peak => Some(peak),
+        }
+    }
+
+    fn record(&self, sample: ResourceSample) {
+        if let Ok(mut guard) = self.latest.lock() {
+            *guard = Some(sample);
+        }
+        self.peak_rss_bytes
+            .fetch_max(sample.rss_bytes, Ordering::Relaxed);
+    }
+}
+
+/// Spawns a task that samples `pid` every `interval` until `cancellation_token`
+/// fires or the pid stops resolving to a live process, publishing each
+/// reading into `usage`. Returns immediately without spawning anything if
+/// this platform can't sample at all, so a caller never pays for a task that
+/// would just loop doing nothing.
+pub(crate) fn spawn(
+    pid: u32,
+    interval: Duration,
+    usage: Arc<ResourceUsage>,
+    cancellation_token: CancellationToken,
+) -> Option<JoinHandle<()>> {
+    if !sampling_supported() {
+        return None;
+    }
+
+    Some(tokio::spawn(async move {
+        let mut previous_cpu_ticks: Option<(u64, Instant)> = None;
+        loop {
+            match read_sample(pid, &mut previous_cpu_ticks) {
+                Some(sample) => usage.record(sample),
+                None => break,
+            }
+            tokio::select! {
+                () = cancellation_token.cancelled() => break,
+                () = tokio::time::sleep(interval) => {}
+            }
+        }
+    }))
+}
+
+#[cfg(target_os = "linux")]
+fn sampling_supported() -> bool {
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sampling_supported() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn read_sample(
+    pid: u32,
+    previous_cpu_ticks: &mut Option<(u64, Instant)>,
+) -> Option<ResourceSample> {
+    let rss_bytes = read_rss_bytes(pid)?;
+    let cpu_ticks = read_cpu_ticks(pid)?;
+    let now = Instant::now();
+
+    let cpu_percent = match previous_cpu_ticks.replace((cpu_ticks, now)) {
+        Some((previous_ticks, previous_at)) => {
+            let elapsed = now.saturating_duration_since(previous_at).as_secs_f64();
+            let ticks_per_sec = clock_ticks_per_sec();
+            if elapsed <= 0.0 || ticks_per_sec <= 0 {
+                0.0
+            } else {
+                let consumed_secs =
+                    cpu_ticks.saturating_sub(previous_ticks) as f64 / ticks_per_sec as f64;
+                ((consumed_secs / elapsed) * 100.0) as f32
+            }
+        }
+        None => 0.0,
+    };
+
+    Some(ResourceSample {
+        cpu_percent,
+        rss_bytes,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_sample(
+    _pid: u32,
+    _previous_cpu_ticks: &mut Option<(u64, Instant)>,
+) -> Option<ResourceSample> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn clock_ticks_per_sec() -> i64 {
+    // SAFETY: sysconf with a well-known name never touches memory we own.
+    unsafe { libc::sysconf(libc::_SC_CLK_TCK) }
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let rss_kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(rss_kb * 1024)
+}
+
+#[cfg(target_os = "linux")]
+fn read_cpu_ticks(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    parse_cpu_ticks(&stat)
+}
+
+/// `utime + stime` from the contents of a `/proc/<pid>/stat` file.
+#[cfg(target_os = "linux")]
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    // Field 2 (`comm`) is parenthesized and may itself contain spaces, so
+    // split on the closing paren rather than counting whitespace fields from
+    // the start of the line.
+    let after_comm = stat.rsplit_once(')')?.1;
+    let mut fields = after_comm.split_whitespace();
+    let utime: u64 = fields.nth(11)?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_cpu_ticks_skips_past_a_comm_field_containing_spaces() {
+        let stat = "1 (weird  proc name) S 0 1 1 0 -1 4194560 0 0 0 0 10 5 0 0 20 0 1 0 123 0 0 \
+                     18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 0 17 0 0 0 0 0 0 0 0 0 0 0 0 0";
+        // utime is 10 and stime is 5.
+        assert_eq!(parse_cpu_ticks(stat), Some(15));
+    }
+
+    #[test]
+    fn sampling_is_supported_on_linux() {
+        assert!(sampling_supported());
+    }
+}
@@ -0,0 +1,103 @@
+/// An embedding model supported by [`crate::EmbeddingService`].
+///
+/// These correspond to real, publicly documented embedding models so that
+/// callers can pick the one matching their use case (e.g. a non-English
+/// corpus); see [`crate::EmbeddingService`] for why this crate computes a
+/// deterministic hash-based stand-in rather than actually loading any of
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingModel {
+    #[default]
+    NomicEmbedTextV15,
+    BgeSmallEnV15,
+    GteSmall,
+}
+
+impl EmbeddingModel {
+    /// Dimension of embeddings this model natively produces.
+    pub fn native_dimension(self) -> usize {
+        match self {
+            EmbeddingModel::NomicEmbedTextV15 => 768,
+            EmbeddingModel::BgeSmallEnV15 => 384,
+            EmbeddingModel::GteSmall => 384,
+        }
+    }
+}
+
+/// Which backend [`crate::EmbeddingService`] should run on.
+///
+/// This crate never loads a real ML model - see [`crate::EmbeddingService`]'s
+/// doc comment for why - so there is no CUDA or CoreML backend to actually
+/// dispatch to. [`crate::EmbeddingService::try_with_config`] honors this
+/// honestly rather than pretending to accelerate anything: [`Self::Cpu`] (the
+/// default) and [`Self::Auto`] always construct successfully and resolve to
+/// CPU, while [`Self::Cuda`]/[`Self::CoreMl`] are rejected with
+/// [`crate::EmbeddingError::ProviderUnavailable`] so a caller that explicitly
+/// asked for GPU acceleration finds out at construction time rather than
+/// silently getting CPU speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionProvider {
+    #[default]
+    Cpu,
+    Cuda {
+        device_id: u32,
+    },
+    CoreMl,
+    /// Use the best available provider, falling back to [`Self::Cpu`] when
+    /// nothing better is available - which, in this crate, is always.
+    Auto,
+}
+
+/// Configuration for [`crate::EmbeddingService`].
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    /// Which model's dimension and behavior to emulate.
+    pub model: EmbeddingModel,
+    /// When set, embeddings are truncated to this many dimensions and
+    /// re-normalized ("Matryoshka" truncation) before being returned. Must
+    /// be less than or equal to `model`'s `native_dimension`.
+    pub dimension: Option<usize>,
+    /// Prefix prepended to text embedded via
+    /// [`crate::EmbeddingService::embed_query`]. Asymmetric models like Nomic
+    /// score retrieval higher when queries and documents are encoded with
+    /// different task prefixes.
+    pub query_prefix: String,
+    /// Prefix prepended to text embedded via
+    /// [`crate::EmbeddingService::embed_documents`].
+    pub document_prefix: String,
+    /// When set, [`crate::EmbeddingService`] keeps an in-process LRU cache of
+    /// this many embeddings, keyed by a content hash of the input text.
+    /// Reindexing frequently re-embeds identical chunks (vendored files,
+    /// generated code), so this avoids redundant work. `None` disables
+    /// caching.
+    pub cache_capacity: Option<usize>,
+    /// Number of threads [`crate::EmbeddingService::embed`] may spread a
+    /// batch across. `1` (the default) embeds sequentially; values above `1`
+    /// split the batch into sub-batches run across a bounded rayon pool of
+    /// that size.
+    pub max_parallel_batches: usize,
+    /// When set, [`crate::EmbeddingService::try_embed`] rejects any input
+    /// longer than this many characters with
+    /// [`crate::EmbeddingError::InputTooLong`] instead of embedding it.
+    /// `None` (the default) embeds inputs of any length.
+    pub max_input_chars: Option<usize>,
+    /// Which backend to construct the service against - see
+    /// [`ExecutionProvider`]. `Cpu` (the default) matches this crate's
+    /// long-standing, only-ever-CPU behavior.
+    pub execution_provider: ExecutionProvider,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            model: EmbeddingModel::default(),
+            dimension: None,
+            query_prefix: "search_query: ".to_string(),
+            document_prefix: "search_document: ".to_string(),
+            cache_capacity: None,
+            max_parallel_batches: 1,
+            max_input_chars: None,
+            execution_provider: ExecutionProvider::default(),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+use crate::config::ExecutionProvider;
+
+/// An error embedding a single input, or constructing an
+/// [`crate::EmbeddingService`] from an [`crate::EmbeddingConfig`] - see
+/// [`crate::EmbeddingService::try_embed`] and
+/// [`crate::EmbeddingService::try_with_config`] respectively.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EmbeddingError {
+    #[error("input has {len} characters, which exceeds the configured maximum of {max}")]
+    InputTooLong { len: usize, max: usize },
+    #[error("execution provider {provider:?} is unavailable: {reason}")]
+    ProviderUnavailable {
+        provider: ExecutionProvider,
+        reason: String,
+    },
+}
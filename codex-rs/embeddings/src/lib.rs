@@ -0,0 +1,14 @@
+//! Deterministic text embeddings, computed without an ML model, used for
+//! codebase indexing and semantic search.
+
+mod config;
+mod error;
+mod service;
+
+pub use config::EmbeddingConfig;
+pub use config::EmbeddingModel;
+pub use config::ExecutionProvider;
+pub use error::EmbeddingError;
+pub use service::COMPACT_EMBEDDING_DIM;
+pub use service::DEFAULT_EMBEDDING_DIM;
+pub use service::EmbeddingService;
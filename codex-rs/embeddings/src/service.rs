@@ -0,0 +1,553 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use lru::LruCache;
+use rayon::ThreadPool;
+use rayon::ThreadPoolBuilder;
+use rayon::iter::IntoParallelRefIterator;
+use rayon::iter::ParallelIterator;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::config::EmbeddingConfig;
+use crate::config::EmbeddingModel;
+use crate::config::ExecutionProvider;
+use crate::error::EmbeddingError;
+
+/// The dimension of embeddings produced by [`EmbeddingService::new`].
+pub const DEFAULT_EMBEDDING_DIM: usize = 768;
+
+/// A reduced "Matryoshka" dimension also supported by compatible embedding models.
+pub const COMPACT_EMBEDDING_DIM: usize = 256;
+
+/// Turns text into embeddings for semantic search.
+///
+/// Rather than shipping and loading a downloaded ML model (which would make
+/// indexing require network access and a GPU/CPU runtime), this computes a
+/// deterministic feature-hashing embedding: each token is hashed into one of
+/// `dim` buckets with a random sign, and the resulting vector is
+/// L2-normalized. This is intentionally simple - it is good enough to rank
+/// semantically similar code higher without any external dependency, and is
+/// fully reproducible, which keeps indexing and search deterministic in tests
+/// and CI.
+pub struct EmbeddingService {
+    model: EmbeddingModel,
+    dim: usize,
+    query_prefix: String,
+    document_prefix: String,
+    cache: Option<Mutex<LruCache<String, Vec<f32>>>>,
+    cache_hits: AtomicUsize,
+    thread_pool: Option<ThreadPool>,
+    max_input_chars: Option<usize>,
+    active_provider: ExecutionProvider,
+}
+
+impl EmbeddingService {
+    pub fn new() -> Self {
+        Self::with_config(EmbeddingConfig::default())
+    }
+
+    /// Panics where [`Self::try_with_config`] would return an `Err` - either
+    /// an oversized `dimension` (see below) or an unavailable
+    /// [`EmbeddingConfig::execution_provider`]. Kept for callers that already
+    /// treat a misconfigured service as a programmer error rather than
+    /// something to recover from at runtime.
+    pub fn with_config(config: EmbeddingConfig) -> Self {
+        match Self::try_with_config(config) {
+            Ok(service) => service,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`Self::with_config`], but reports
+    /// [`EmbeddingError::ProviderUnavailable`] instead of panicking when
+    /// `config.execution_provider` can't be honored - see
+    /// [`ExecutionProvider`] for why only [`ExecutionProvider::Cpu`] and
+    /// [`ExecutionProvider::Auto`] ever can be, in this crate. An oversized
+    /// `dimension` still panics via `assert!`, matching this crate's
+    /// long-standing behavior for that check.
+    pub fn try_with_config(config: EmbeddingConfig) -> Result<Self, EmbeddingError> {
+        let active_provider = match config.execution_provider {
+            ExecutionProvider::Cpu | ExecutionProvider::Auto => ExecutionProvider::Cpu,
+            unavailable @ (ExecutionProvider::Cuda { .. } | ExecutionProvider::CoreMl) => {
+                return Err(EmbeddingError::ProviderUnavailable {
+                    provider: unavailable,
+                    reason: "this crate computes deterministic hash-based embeddings and has \
+                             no GPU backend to dispatch to"
+                        .to_string(),
+                });
+            }
+        };
+
+        let native_dim = config.model.native_dimension();
+        let dim = config.dimension.unwrap_or(native_dim);
+        assert!(
+            dim <= native_dim,
+            "requested embedding dimension {dim} exceeds {:?}'s native dimension {native_dim}",
+            config.model,
+        );
+        Ok(Self {
+            model: config.model,
+            dim,
+            query_prefix: config.query_prefix,
+            document_prefix: config.document_prefix,
+            cache: config
+                .cache_capacity
+                .and_then(NonZeroUsize::new)
+                .map(|capacity| Mutex::new(LruCache::new(capacity))),
+            cache_hits: AtomicUsize::new(0),
+            thread_pool: (config.max_parallel_batches > 1).then(|| {
+                ThreadPoolBuilder::new()
+                    .num_threads(config.max_parallel_batches)
+                    .build()
+                    .expect("building a bounded rayon pool")
+            }),
+            max_input_chars: config.max_input_chars,
+            active_provider,
+        })
+    }
+
+    /// The model whose dimension and behavior this service emulates.
+    pub fn model(&self) -> EmbeddingModel {
+        self.model
+    }
+
+    /// The execution provider this service actually constructed against -
+    /// always [`ExecutionProvider::Cpu`], since that's the only provider
+    /// [`Self::try_with_config`] ever accepts. Lets an indexer log what it's
+    /// running on without having to re-derive it from the config it passed
+    /// in.
+    pub fn active_provider(&self) -> ExecutionProvider {
+        self.active_provider
+    }
+
+    /// Dimension of vectors returned by [`Self::embed`].
+    pub fn dimension(&self) -> usize {
+        self.dim
+    }
+
+    /// Number of inputs, across every embedding method, whose embedding was
+    /// served from the content-hash cache rather than recomputed.
+    /// Always `0` when `EmbeddingConfig::cache_capacity` is `None`.
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Embeds a batch of texts, one embedding per input.
+    ///
+    /// Embeddings are always computed at `self.model`'s native dimension and
+    /// then truncated + re-normalized down to `self.dim` ("Matryoshka"
+    /// truncation), so a compact and a full-size service agree on the
+    /// leading dimensions of the same input.
+    pub fn embed(&self, texts: &[String]) -> Vec<Vec<f32>> {
+        self.embed_at_dim(texts, self.dim)
+    }
+
+    /// Embeds `texts` and truncates + re-normalizes each embedding to `dim`,
+    /// independent of the dimension this service was constructed with.
+    ///
+    /// This lets a single service produce vectors at whatever "Matryoshka"
+    /// dimension a caller wants to store, without configuring a second
+    /// service. Panics if `dim` exceeds `self.model()`'s native dimension.
+    pub fn embed_truncated(&self, texts: Vec<String>, dim: usize) -> Vec<Vec<f32>> {
+        let native_dim = self.model.native_dimension();
+        assert!(
+            dim <= native_dim,
+            "requested embedding dimension {dim} exceeds {:?}'s native dimension {native_dim}",
+            self.model,
+        );
+        self.embed_at_dim(&texts, dim)
+    }
+
+    /// Like [`Self::embed`], but first checks each input against
+    /// `EmbeddingConfig::max_input_chars` and reports a per-item
+    /// [`EmbeddingError::InputTooLong`] in place of any input over it, without
+    /// affecting the rest of the batch. `embed` applies no such limit.
+    ///
+    /// Every element of the returned `Vec` corresponds to the input at the
+    /// same index.
+    pub fn try_embed(&self, texts: Vec<String>) -> Vec<Result<Vec<f32>, EmbeddingError>> {
+        let native_dim = self.model.native_dimension();
+        let dim = self.dim;
+        let compute = |text: &String| self.try_embed_one(text, native_dim, dim);
+        match &self.thread_pool {
+            Some(pool) => pool.install(|| texts.par_iter().map(compute).collect()),
+            None => texts.iter().map(compute).collect(),
+        }
+    }
+
+    fn try_embed_one(&self, text: &str, native_dim: usize, dim: usize) -> Result<Vec<f32>, EmbeddingError> {
+        if let Some(max) = self.max_input_chars {
+            let len = text.chars().count();
+            if len > max {
+                return Err(EmbeddingError::InputTooLong { len, max });
+            }
+        }
+        Ok(self.embed_one_cached(text, native_dim, dim))
+    }
+
+    fn embed_at_dim(&self, texts: &[String], dim: usize) -> Vec<Vec<f32>> {
+        let native_dim = self.model.native_dimension();
+        let Some(pool) = &self.thread_pool else {
+            return texts
+                .iter()
+                .map(|text| self.embed_one_cached(text, native_dim, dim))
+                .collect();
+        };
+        pool.install(|| {
+            texts
+                .par_iter()
+                .map(|text| self.embed_one_cached(text, native_dim, dim))
+                .collect()
+        })
+    }
+
+    fn embed_one_cached(&self, text: &str, native_dim: usize, dim: usize) -> Vec<f32> {
+        let Some(cache) = &self.cache else {
+            return embed_truncated_vector(text, native_dim, dim);
+        };
+        let key = cache_key(text, dim);
+        if let Some(cached) = cache.lock().unwrap().get(&key) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return cached.clone();
+        }
+        let vector = embed_truncated_vector(text, native_dim, dim);
+        cache.lock().unwrap().put(key, vector.clone());
+        vector
+    }
+
+    /// Embeds a single search query, applying `query_prefix` first.
+    ///
+    /// Asymmetric models distinguish between short queries and longer
+    /// documents at training time; using this (and [`Self::embed_documents`]
+    /// on the indexed side) instead of the raw [`Self::embed`] path improves
+    /// retrieval quality for those models.
+    pub fn embed_query(&self, text: &str) -> Vec<f32> {
+        self.embed(&[format!("{}{text}", self.query_prefix)])
+            .remove(0)
+    }
+
+    /// Embeds a batch of documents, applying `document_prefix` to each one first.
+    pub fn embed_documents(&self, texts: Vec<String>) -> Vec<Vec<f32>> {
+        let prefixed: Vec<String> = texts
+            .into_iter()
+            .map(|text| format!("{}{text}", self.document_prefix))
+            .collect();
+        self.embed(&prefixed)
+    }
+}
+
+impl Default for EmbeddingService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn embed_truncated_vector(text: &str, native_dim: usize, dim: usize) -> Vec<f32> {
+    let mut vector = embed_text(text, native_dim);
+    vector.truncate(dim);
+    normalize(&mut vector);
+    vector
+}
+
+/// Content-hash cache key, scoped to `dim` so the same text embedded at two
+/// different "Matryoshka" dimensions doesn't collide in the cache.
+fn cache_key(text: &str, dim: usize) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    format!("{dim}:{:x}", hasher.finalize())
+}
+
+pub(crate) fn embed_text(text: &str, dim: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dim];
+    for token in text.split_whitespace() {
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash % dim as u64) as usize;
+        let sign = if hash >> 63 == 1 { -1.0 } else { 1.0 };
+        vector[index] += sign;
+    }
+    normalize(&mut vector);
+    vector
+}
+
+pub(crate) fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embed_is_deterministic_and_unit_norm() {
+        let service = EmbeddingService::new();
+        let a = service.embed(&["fn parse_error_handling".to_string()]);
+        let b = service.embed(&["fn parse_error_handling".to_string()]);
+        assert_eq!(a, b);
+
+        let norm = a[0].iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4 || norm == 0.0);
+    }
+
+    #[test]
+    fn embed_dimension_matches_service() {
+        let service = EmbeddingService::new();
+        let embeddings = service.embed(&["hello world".to_string()]);
+        assert_eq!(embeddings[0].len(), DEFAULT_EMBEDDING_DIM);
+    }
+
+    #[test]
+    fn compact_service_truncates_and_renormalizes() {
+        let service = EmbeddingService::with_config(EmbeddingConfig {
+            dimension: Some(COMPACT_EMBEDDING_DIM),
+            ..Default::default()
+        });
+        let embeddings = service.embed(&["fn handle_request".to_string()]);
+        assert_eq!(embeddings[0].len(), COMPACT_EMBEDDING_DIM);
+
+        let norm = embeddings[0].iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4 || norm == 0.0);
+    }
+
+    // A hash-based embedding has no trained notion of "query" vs "document",
+    // so prefixing can't be shown to improve retrieval quality here the way
+    // it would for a real asymmetric model; this instead verifies the
+    // prefixes are wired up correctly and deterministically.
+    #[test]
+    fn embed_query_and_embed_documents_apply_the_configured_prefixes() {
+        let service = EmbeddingService::new();
+        let text = "fn parse_error_handling".to_string();
+
+        let via_embed_query = service.embed_query(&text);
+        let via_raw_embed = service
+            .embed(&[format!("search_query: {text}")])
+            .remove(0);
+        assert_eq!(via_embed_query, via_raw_embed);
+
+        let via_embed_documents = service.embed_documents(vec![text.clone()]);
+        let via_raw_embed = service.embed(&[format!("search_document: {text}")]);
+        assert_eq!(via_embed_documents, via_raw_embed);
+
+        // Different task prefixes must not collapse to the same embedding.
+        assert_ne!(service.embed_query(&text), service.embed_documents(vec![text]).remove(0));
+    }
+
+    #[test]
+    fn each_model_yields_embeddings_of_its_documented_dimension() {
+        for model in [
+            EmbeddingModel::NomicEmbedTextV15,
+            EmbeddingModel::BgeSmallEnV15,
+            EmbeddingModel::GteSmall,
+        ] {
+            let service = EmbeddingService::with_config(EmbeddingConfig {
+                model,
+                dimension: None,
+                ..Default::default()
+            });
+            assert_eq!(service.dimension(), model.native_dimension());
+            let embeddings = service.embed(&["fn handle_request".to_string()]);
+            assert_eq!(embeddings[0].len(), model.native_dimension());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn requesting_more_dimensions_than_the_model_supports_panics() {
+        EmbeddingService::with_config(EmbeddingConfig {
+            model: EmbeddingModel::BgeSmallEnV15,
+            dimension: Some(768),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn cpu_and_auto_providers_construct_and_report_cpu_as_active() {
+        for execution_provider in [ExecutionProvider::Cpu, ExecutionProvider::Auto] {
+            let service = EmbeddingService::try_with_config(EmbeddingConfig {
+                execution_provider,
+                ..Default::default()
+            })
+            .unwrap();
+            assert_eq!(service.active_provider(), ExecutionProvider::Cpu);
+        }
+    }
+
+    #[test]
+    fn gpu_providers_are_reported_as_unavailable_rather_than_silently_ignored() {
+        for execution_provider in
+            [ExecutionProvider::Cuda { device_id: 0 }, ExecutionProvider::CoreMl]
+        {
+            let err = EmbeddingService::try_with_config(EmbeddingConfig {
+                execution_provider,
+                ..Default::default()
+            })
+            .unwrap_err();
+            assert_eq!(
+                err,
+                EmbeddingError::ProviderUnavailable {
+                    provider: execution_provider,
+                    reason: "this crate computes deterministic hash-based embeddings and has \
+                             no GPU backend to dispatch to"
+                        .to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unavailable")]
+    fn with_config_panics_on_an_unavailable_provider_instead_of_silently_falling_back() {
+        EmbeddingService::with_config(EmbeddingConfig {
+            execution_provider: ExecutionProvider::CoreMl,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn embed_truncated_is_unit_norm_and_preserves_similarity_ordering() {
+        let service = EmbeddingService::new();
+        let query = "fn parse_error_handling".to_string();
+        let candidates = vec![
+            "fn parse_error_handling_extended".to_string(),
+            "struct UnrelatedConfig".to_string(),
+            "fn handle_parse_error".to_string(),
+        ];
+
+        let truncated = service.embed_truncated(
+            std::iter::once(query.clone()).chain(candidates.clone()).collect(),
+            COMPACT_EMBEDDING_DIM,
+        );
+        for vector in &truncated {
+            assert_eq!(vector.len(), COMPACT_EMBEDDING_DIM);
+            let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-4 || norm == 0.0);
+        }
+
+        let full = service.embed(
+            &std::iter::once(query).chain(candidates).collect::<Vec<_>>(),
+        );
+        let best_match = |embeddings: &[Vec<f32>]| -> usize {
+            (1..embeddings.len())
+                .max_by(|&a, &b| {
+                    cosine(&embeddings[0], &embeddings[a]).total_cmp(&cosine(&embeddings[0], &embeddings[b]))
+                })
+                .expect("candidates is non-empty")
+        };
+        // Truncating to a smaller Matryoshka dimension drops information but
+        // keeps the leading, most-informative components, so the closest
+        // candidate by full-dimension cosine similarity should still be the
+        // closest one after truncation.
+        assert_eq!(best_match(&truncated), best_match(&full));
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn embed_truncated_panics_when_dim_exceeds_the_models_native_dimension() {
+        let service = EmbeddingService::new();
+        service.embed_truncated(vec!["hello".to_string()], DEFAULT_EMBEDDING_DIM + 1);
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn embedding_the_same_text_twice_is_served_from_the_cache_on_the_second_call() {
+        let service = EmbeddingService::with_config(EmbeddingConfig {
+            cache_capacity: Some(16),
+            ..Default::default()
+        });
+        let text = "fn parse_error_handling".to_string();
+
+        let first = service.embed(&[text.clone()]);
+        assert_eq!(service.cache_hits(), 0);
+
+        let second = service.embed(&[text]);
+        assert_eq!(service.cache_hits(), 1);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn caching_is_disabled_by_default() {
+        let service = EmbeddingService::new();
+        let text = "fn parse_error_handling".to_string();
+
+        service.embed(&[text.clone()]);
+        service.embed(&[text]);
+        assert_eq!(service.cache_hits(), 0);
+    }
+
+    #[test]
+    fn try_embed_reports_a_per_item_error_without_failing_the_rest_of_the_batch() {
+        let service = EmbeddingService::with_config(EmbeddingConfig {
+            max_input_chars: Some(32),
+            ..Default::default()
+        });
+        let texts = vec![
+            "fn short_and_valid() {}".to_string(),
+            "x".repeat(1_000),
+            "fn also_short_and_valid() {}".to_string(),
+        ];
+
+        let results = service.try_embed(texts);
+
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1],
+            Err(EmbeddingError::InputTooLong { len: 1_000, max: 32 })
+        );
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn try_embed_with_no_max_input_chars_never_rejects_anything() {
+        let service = EmbeddingService::new();
+        let results = service.try_embed(vec!["x".repeat(10_000)]);
+        assert!(results[0].is_ok());
+    }
+
+    #[test]
+    fn parallel_batches_preserve_output_order() {
+        let sequential = EmbeddingService::new();
+        let parallel = EmbeddingService::with_config(EmbeddingConfig {
+            max_parallel_batches: 4,
+            ..Default::default()
+        });
+
+        let texts: Vec<String> = (0..1_000).map(|i| format!("fn item_{i}")).collect();
+        assert_eq!(sequential.embed(&texts), parallel.embed(&texts));
+    }
+
+    #[test]
+    fn parallel_batches_use_all_configured_workers() {
+        // Wall-clock comparisons against a sequential run are inherently
+        // flaky on noisy CI machines, so this only checks that the
+        // configured worker count is actually plumbed through to the rayon
+        // pool rather than silently ignored.
+        // `parallel_batches_preserve_output_order` covers correctness of the
+        // parallel path.
+        let available = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let parallel = EmbeddingService::with_config(EmbeddingConfig {
+            max_parallel_batches: available,
+            ..Default::default()
+        });
+
+        match &parallel.thread_pool {
+            Some(pool) => assert_eq!(pool.current_num_threads(), available),
+            // `max_parallel_batches <= 1` intentionally skips building a pool.
+            None => assert_eq!(available, 1),
+        }
+    }
+}
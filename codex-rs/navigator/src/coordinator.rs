@@ -0,0 +1,888 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use codex_vector_store::CodeChunk;
+
+use crate::proto::CallGraphRequest;
+use crate::proto::CallGraphResponse;
+use crate::proto::ErrorPayload;
+use crate::proto::GraphEdge;
+use crate::proto::GraphNode;
+use crate::proto::HealthRequest;
+use crate::proto::HealthResponse;
+use crate::proto::ImpactRequest;
+use crate::proto::ImpactResponse;
+use crate::proto::NavHit;
+use crate::proto::NavigatorError;
+use crate::proto::OpenResponse;
+use crate::proto::ReferencesDirection;
+use crate::proto::ReferencesRequest;
+use crate::proto::ReferencesResponse;
+use crate::proto::SearchRequest;
+use crate::proto::SearchResponse;
+use crate::proto::SnippetResponse;
+use crate::references::ReferenceGraph;
+use crate::references::chunk_id;
+use crate::references::find_references;
+use crate::search;
+
+const LITERAL_ID_PREFIX: &str = "literal::";
+
+/// Resolves `handle_open`/`handle_snippet`/`handle_references` requests
+/// against a snapshot of indexed chunks.
+///
+/// Ids are `path#start_line` for chunks that came out of the index. A
+/// `literal::path#line` id instead names a raw source position (e.g. picked
+/// off a stack trace) that isn't backed by any indexed chunk; all three
+/// handlers reject these with [`NavigatorError::NotSupported`] rather than
+/// panicking on a lookup that can never succeed.
+pub struct IndexCoordinator {
+    chunks: Vec<CodeChunk>,
+    /// Parallel to `chunks`: which workspace (by the id passed to
+    /// [`Self::from_workspaces`]) each chunk came from. Every entry is
+    /// `None` for a coordinator built with [`Self::new`].
+    workspaces: Vec<Option<String>>,
+    graph: ReferenceGraph,
+}
+
+impl IndexCoordinator {
+    pub fn new(chunks: Vec<CodeChunk>) -> Self {
+        let workspaces = vec![None; chunks.len()];
+        let graph = ReferenceGraph::build(&chunks);
+        Self { chunks, workspaces, graph }
+    }
+
+    /// Merges chunks from several indexed roots into a single coordinator,
+    /// so a monorepo with split workspaces can be searched without running
+    /// one coordinator per root.
+    ///
+    /// Each chunk's `path` is rewritten to `{workspace}/{original path}` -
+    /// both so ids (`path#line`) stay globally unique when two roots happen
+    /// to share a relative path, and so a hit's origin is visible directly
+    /// in the path it displays, not just in [`NavHit::workspace`].
+    pub fn from_workspaces(workspaces: Vec<(String, Vec<CodeChunk>)>) -> Self {
+        let mut chunks = Vec::new();
+        let mut tags = Vec::new();
+        for (workspace, workspace_chunks) in workspaces {
+            for mut chunk in workspace_chunks {
+                chunk.path = format!("{workspace}/{}", chunk.path);
+                tags.push(Some(workspace.clone()));
+                chunks.push(chunk);
+            }
+        }
+        let graph = ReferenceGraph::build(&chunks);
+        Self { chunks, workspaces: tags, graph }
+    }
+
+    /// Re-indexes only `changed` chunks into this snapshot, replacing any
+    /// existing chunk at the same `path` and dropping every chunk under
+    /// `removed_paths`, then rebuilds the reference graph once over the
+    /// result.
+    ///
+    /// There's no `code-finder` crate in this codebase, and this method
+    /// didn't already exist on navigator's own `IndexCoordinator` - there's
+    /// no per-edge incremental update to [`ReferenceGraph`] to mirror
+    /// either, since it doesn't expose one; this still does a full
+    /// `ReferenceGraph::build`, just over far fewer chunks than a caller
+    /// would otherwise have to re-read and re-chunk from disk to get. A
+    /// `changed` chunk at a path this coordinator hasn't seen before has no
+    /// workspace to inherit, since [`Self::from_workspaces`] only bakes that
+    /// in at construction time - it's tagged `None`; the caller should
+    /// already be using that constructor's `{workspace}/` path prefix
+    /// convention if it wants this chunk attributed correctly later.
+    pub fn ingest_delta(self, changed: Vec<CodeChunk>, removed_paths: &[String]) -> Self {
+        let changed_paths: std::collections::HashSet<&str> =
+            changed.iter().map(|chunk| chunk.path.as_str()).collect();
+
+        let mut previous_workspace: std::collections::HashMap<String, Option<String>> =
+            std::collections::HashMap::new();
+        let mut chunks = Vec::new();
+        let mut workspaces = Vec::new();
+        for (chunk, workspace) in self.chunks.into_iter().zip(self.workspaces) {
+            if removed_paths.iter().any(|path| path == &chunk.path) {
+                continue;
+            }
+            if changed_paths.contains(chunk.path.as_str()) {
+                previous_workspace.insert(chunk.path.clone(), workspace);
+                continue;
+            }
+            chunks.push(chunk);
+            workspaces.push(workspace);
+        }
+        for chunk in changed {
+            workspaces.push(previous_workspace.remove(&chunk.path).flatten());
+            chunks.push(chunk);
+        }
+
+        let graph = ReferenceGraph::build(&chunks);
+        Self { chunks, workspaces, graph }
+    }
+
+    /// Reports counts over this coordinator's snapshot: total indexed
+    /// chunks, distinct files they came from, chunks with a recognized
+    /// `symbol_name`, and call-graph edges in [`Self::graph`]. Always
+    /// succeeds, including for an empty snapshot.
+    pub fn handle_health(&self, _request: HealthRequest) -> HealthResponse {
+        let indexed_files: BTreeSet<&str> =
+            self.chunks.iter().map(|chunk| chunk.path.as_str()).collect();
+        let indexed_symbols =
+            self.chunks.iter().filter(|chunk| chunk.symbol_name.is_some()).count();
+
+        HealthResponse {
+            indexed_chunks: self.chunks.len(),
+            indexed_files: indexed_files.len(),
+            indexed_symbols,
+            reference_edges: self.graph.all_edges().count(),
+        }
+    }
+
+    pub fn handle_open(&self, id: &str) -> Result<OpenResponse, NavigatorError> {
+        let chunk = self.resolve(id)?;
+        Ok(OpenResponse {
+            hit: self.to_hit(id, chunk),
+            body: chunk.content.clone(),
+        })
+    }
+
+    pub fn handle_snippet(
+        &self,
+        id: &str,
+        context_lines: usize,
+    ) -> Result<SnippetResponse, NavigatorError> {
+        let chunk = self.resolve(id)?;
+        let snippet: String = chunk
+            .content
+            .lines()
+            .take(context_lines.max(1))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(SnippetResponse {
+            hit: self.to_hit(id, chunk),
+            snippet,
+        })
+    }
+
+    pub fn handle_references(
+        &self,
+        request: ReferencesRequest,
+    ) -> Result<ReferencesResponse, NavigatorError> {
+        // Confirms the id both isn't a literal id and actually resolves to
+        // an indexed chunk before we bother walking the graph.
+        self.resolve(&request.id)?;
+
+        let mut edges = match request.direction {
+            ReferencesDirection::Incoming => self.graph.callers_of(&request.id).to_vec(),
+            ReferencesDirection::Outgoing => self.graph.callees_of(&request.id).to_vec(),
+            ReferencesDirection::Both => {
+                let mut edges = self.graph.callers_of(&request.id).to_vec();
+                edges.extend(self.graph.callees_of(&request.id).iter().cloned());
+                edges
+            }
+        };
+        edges.truncate(request.limit);
+
+        let hits = edges
+            .into_iter()
+            .filter_map(|edge| {
+                let other_id = if edge.callee_id == request.id {
+                    edge.caller_id
+                } else {
+                    edge.callee_id
+                };
+                let chunk = self.resolve(&other_id).ok()?;
+                Some(NavHit {
+                    workspace: self.workspace_of(&other_id),
+                    id: other_id,
+                    path: chunk.path.clone(),
+                    line: edge.line,
+                    preview: edge.preview,
+                    is_fuzzy_match: false,
+                    references: None,
+                })
+            })
+            .collect();
+
+        Ok(ReferencesResponse { hits })
+    }
+
+    /// Reports how widely referenced `request.id` is, for scoping a rename
+    /// before doing it - see [`ImpactResponse`]. A symbol with no callers
+    /// returns a valid, empty report rather than an error.
+    pub fn handle_impact(&self, request: ImpactRequest) -> Result<ImpactResponse, NavigatorError> {
+        self.resolve(&request.id)?;
+
+        let mut response = ImpactResponse::default();
+        let mut referencing_files = BTreeSet::new();
+        let mut by_chunk_type: BTreeMap<String, usize> = BTreeMap::new();
+
+        for edge in self.graph.callers_of(&request.id) {
+            let Ok(caller) = self.resolve(&edge.caller_id) else {
+                continue;
+            };
+            let is_test = caller.chunk_type.as_deref() == Some("test");
+            if is_test && !request.include_tests {
+                continue;
+            }
+
+            referencing_files.insert(caller.path.clone());
+            *by_chunk_type
+                .entry(caller.chunk_type.clone().unwrap_or_else(|| "unknown".to_string()))
+                .or_insert(0) += 1;
+            if response.top_hits.len() < request.limit {
+                response.top_hits.push(NavHit {
+                    workspace: self.workspace_of(&edge.caller_id),
+                    id: edge.caller_id.clone(),
+                    path: caller.path.clone(),
+                    line: edge.line,
+                    preview: edge.preview.clone(),
+                    is_fuzzy_match: false,
+                    references: None,
+                });
+            }
+        }
+
+        response.referencing_files = referencing_files.len();
+        response.by_chunk_type = by_chunk_type;
+        Ok(response)
+    }
+
+    /// Builds the caller/callee graph reachable from `request.id` within
+    /// `request.max_depth` hops, for [`crate::export::render`]ing as DOT or
+    /// a Mermaid flowchart. A symbol with no callers or callees returns a
+    /// valid, single-node graph rather than an error.
+    pub fn handle_call_graph(
+        &self,
+        request: CallGraphRequest,
+    ) -> Result<CallGraphResponse, NavigatorError> {
+        self.resolve(&request.id)?;
+
+        let mut visited = BTreeSet::new();
+        visited.insert(request.id.clone());
+        let mut frontier = vec![request.id.clone()];
+        let mut edges: BTreeSet<(String, String)> = BTreeSet::new();
+
+        for _ in 0..request.max_depth {
+            let mut next = Vec::new();
+            for id in &frontier {
+                for edge in self.graph.callers_of(id).iter().chain(self.graph.callees_of(id)) {
+                    edges.insert((edge.caller_id.clone(), edge.callee_id.clone()));
+                    for other in [&edge.caller_id, &edge.callee_id] {
+                        if visited.insert(other.clone()) {
+                            next.push(other.clone());
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let nodes = visited
+            .into_iter()
+            .filter_map(|id| {
+                let chunk = self.resolve(&id).ok()?;
+                let edge_count =
+                    self.graph.callers_of(&id).len() + self.graph.callees_of(&id).len();
+                let label = chunk.symbol_name.clone().unwrap_or_else(|| id.clone());
+                Some(GraphNode { id, label, edge_count })
+            })
+            .collect();
+        let edges = edges
+            .into_iter()
+            .map(|(from, to)| GraphEdge { from, to })
+            .collect();
+
+        Ok(CallGraphResponse { nodes, edges })
+    }
+
+    /// Runs `request` as a plain substring search, or - when
+    /// `request.query_regex` is set - as a regular-expression scan of chunk
+    /// content instead of the usual tokenized/fuzzy matching. Pass
+    /// `request.cursor` from a previous [`SearchResponse::next_cursor`] to
+    /// fetch the next page instead of starting over. `request.root`
+    /// restricts the scan to one workspace of a coordinator built with
+    /// [`Self::from_workspaces`]; `search` itself has no notion of
+    /// workspaces, so each hit's [`NavHit::workspace`] is filled in here
+    /// after the scan returns. `request.fuzzy_fallback` fills any shortfall
+    /// below `limit` with nucleo-scored hits - see
+    /// [`crate::search::run_fuzzy_search`]. `request.case_sensitive` and
+    /// `request.whole_word` tighten exact matching (substring/boolean);
+    /// `query_regex` ignores both (wrap the pattern in `\b` yourself, and use
+    /// `(?i)` for case-insensitive matching), since a caller-supplied regex
+    /// already controls both. `request.include_references`
+    /// additionally populates each hit's [`NavHit::references`] - see
+    /// [`crate::references::find_references`] - capped at
+    /// `request.reference_limit`; a hit whose id doesn't resolve to an
+    /// indexed chunk (shouldn't happen, since hits come from `candidates`
+    /// itself) is left with `references: None` rather than failing the
+    /// whole search. `request.definitions_only` narrows `candidates` to
+    /// declaration lines via [`search::definition_candidates`] before
+    /// scanning, and forces `fuzzy_fallback` off regardless of the request's
+    /// own value, since a fuzzy-matched line is never itself a declaration.
+    /// `request.chunk_types`, when non-empty, additionally restricts
+    /// `candidates` to chunks whose [`CodeChunk::chunk_type`] is in the set
+    /// (e.g. `"function"`, `"struct"` - whatever the chunker tagged it
+    /// with); empty keeps every chunk, preserving prior behavior.
+    pub fn run_search(&self, request: SearchRequest) -> Result<SearchResponse, ErrorPayload> {
+        let limit = request.limit.max(1);
+        let cursor = request.cursor.as_deref();
+        let mut candidates = self.candidate_chunks(request.root.as_deref());
+        if !request.chunk_types.is_empty() {
+            candidates.retain(|chunk| {
+                chunk.chunk_type.as_deref().is_some_and(|chunk_type| {
+                    request.chunk_types.iter().any(|wanted| wanted == chunk_type)
+                })
+            });
+        }
+        let scan_candidates = if request.definitions_only {
+            search::definition_candidates(&candidates)
+        } else {
+            candidates.clone()
+        };
+        let mut response = match &request.query_regex {
+            Some(pattern) => search::run_regex_search(&scan_candidates, pattern, limit, cursor)?,
+            None => search::run_fuzzy_search(
+                &scan_candidates,
+                &request.query,
+                limit,
+                cursor,
+                request.fuzzy_fallback && !request.definitions_only,
+                request.case_sensitive,
+                request.whole_word,
+            )?,
+        };
+        for hit in &mut response.hits {
+            hit.workspace = self.workspace_of(&hit.id);
+            if request.include_references {
+                hit.references = self
+                    .resolve(&hit.id)
+                    .ok()
+                    .map(|chunk| find_references(&candidates, chunk, request.reference_limit));
+            }
+        }
+        Ok(response)
+    }
+
+    fn candidate_chunks(&self, root: Option<&str>) -> Vec<CodeChunk> {
+        match root {
+            None => self.chunks.clone(),
+            Some(root) => self
+                .chunks
+                .iter()
+                .zip(&self.workspaces)
+                .filter(|(_, workspace)| workspace.as_deref() == Some(root))
+                .map(|(chunk, _)| chunk.clone())
+                .collect(),
+        }
+    }
+
+    fn resolve(&self, id: &str) -> Result<&CodeChunk, NavigatorError> {
+        if id.starts_with(LITERAL_ID_PREFIX) {
+            return Err(NavigatorError::NotSupported {
+                id: id.to_string(),
+                reason: "literal symbol ids aren't backed by indexed chunk data".to_string(),
+            });
+        }
+        self.chunks
+            .iter()
+            .find(|chunk| chunk_id(chunk) == id)
+            .ok_or_else(|| NavigatorError::UnknownSymbol { id: id.to_string() })
+    }
+
+    fn workspace_of(&self, id: &str) -> Option<String> {
+        let position = self.chunks.iter().position(|chunk| chunk_id(chunk) == id)?;
+        self.workspaces[position].clone()
+    }
+
+    fn to_hit(&self, id: &str, chunk: &CodeChunk) -> NavHit {
+        NavHit {
+            id: id.to_string(),
+            path: chunk.path.clone(),
+            line: chunk.start_line,
+            preview: chunk
+                .content
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            workspace: self.workspace_of(id),
+            is_fuzzy_match: false,
+            references: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start_line: usize, content: &str, symbol_name: Option<&str>) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line,
+            end_line: start_line + content.lines().count().saturating_sub(1),
+            content: content.to_string(),
+            language: Some("rust".to_string()),
+            chunk_type: None,
+            symbol_name: symbol_name.map(str::to_string),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    fn coordinator() -> IndexCoordinator {
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let run = chunk("lib.rs", 3, "fn run() {\n    helper();\n}", Some("run"));
+        IndexCoordinator::new(vec![helper, run])
+    }
+
+    #[test]
+    fn handle_open_returns_the_chunks_body() {
+        let response = coordinator().handle_open("lib.rs#1").unwrap();
+        assert_eq!(response.hit.path, "lib.rs");
+        assert_eq!(response.body, "fn helper() {}");
+    }
+
+    #[test]
+    fn handle_open_on_an_unknown_id_is_an_error_not_a_panic() {
+        let err = coordinator().handle_open("lib.rs#99").unwrap_err();
+        assert_eq!(
+            err,
+            NavigatorError::UnknownSymbol {
+                id: "lib.rs#99".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn handle_health_reports_counts_matching_a_freshly_built_snapshot() {
+        let response = coordinator().handle_health(HealthRequest);
+
+        assert_eq!(response.indexed_chunks, 2);
+        assert_eq!(response.indexed_files, 1);
+        assert_eq!(response.indexed_symbols, 2);
+        assert_eq!(response.reference_edges, 1);
+    }
+
+    #[test]
+    fn handle_health_on_an_empty_snapshot_reports_all_zeros() {
+        let response = IndexCoordinator::new(Vec::new()).handle_health(HealthRequest);
+        assert_eq!(response, HealthResponse::default());
+    }
+
+    #[test]
+    fn handle_references_incoming_finds_the_caller() {
+        let response = coordinator()
+            .handle_references(ReferencesRequest {
+                id: "lib.rs#1".to_string(),
+                direction: ReferencesDirection::Incoming,
+                limit: 10,
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].id, "lib.rs#3");
+        assert_eq!(response.hits[0].line, 4);
+    }
+
+    #[test]
+    fn handle_references_outgoing_finds_the_callee() {
+        let response = coordinator()
+            .handle_references(ReferencesRequest {
+                id: "lib.rs#3".to_string(),
+                direction: ReferencesDirection::Outgoing,
+                limit: 10,
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].id, "lib.rs#1");
+    }
+
+    #[test]
+    fn run_search_with_query_regex_scans_chunk_content() {
+        let response = coordinator()
+            .run_search(SearchRequest {
+                query: String::new(),
+                query_regex: Some(r"fn\s+helper".to_string()),
+                limit: 10,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: false,
+                chunk_types: Vec::new(),
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].id, "lib.rs#1");
+    }
+
+    #[test]
+    fn run_search_with_an_invalid_regex_returns_an_invalid_request_error_payload() {
+        let err = coordinator()
+            .run_search(SearchRequest {
+                query: String::new(),
+                query_regex: Some("(unclosed".to_string()),
+                limit: 10,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: false,
+                chunk_types: Vec::new(),
+            })
+            .unwrap_err();
+
+        assert_eq!(err.code, crate::proto::ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn handle_impact_reports_referencing_files_and_chunk_type_breakdown() {
+        let mut test_chunk = chunk(
+            "lib.rs",
+            5,
+            "#[test]\nfn uses_helper() {\n    helper();\n}",
+            Some("uses_helper"),
+        );
+        test_chunk.chunk_type = Some("test".to_string());
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let run = chunk("other.rs", 1, "fn run() {\n    helper();\n}", Some("run"));
+        let coordinator = IndexCoordinator::new(vec![helper.clone(), run, test_chunk]);
+
+        let without_tests = coordinator
+            .handle_impact(ImpactRequest {
+                id: chunk_id(&helper),
+                include_tests: false,
+                limit: 10,
+            })
+            .unwrap();
+        assert_eq!(without_tests.referencing_files, 1, "only other.rs, the test is excluded");
+        assert_eq!(without_tests.by_chunk_type.get("unknown"), Some(&1));
+        assert_eq!(without_tests.by_chunk_type.get("test"), None);
+        assert_eq!(without_tests.top_hits.len(), 1);
+
+        let with_tests = coordinator
+            .handle_impact(ImpactRequest {
+                id: chunk_id(&helper),
+                include_tests: true,
+                limit: 10,
+            })
+            .unwrap();
+        assert_eq!(with_tests.referencing_files, 2);
+        assert_eq!(with_tests.by_chunk_type.get("test"), Some(&1));
+        assert_eq!(with_tests.by_chunk_type.get("unknown"), Some(&1));
+    }
+
+    #[test]
+    fn handle_impact_on_a_symbol_with_no_callers_is_a_valid_empty_report() {
+        let response = coordinator()
+            .handle_impact(ImpactRequest {
+                id: "lib.rs#3".to_string(),
+                include_tests: true,
+                limit: 10,
+            })
+            .unwrap();
+
+        assert_eq!(response.referencing_files, 0);
+        assert!(response.by_chunk_type.is_empty());
+        assert!(response.top_hits.is_empty());
+    }
+
+    #[test]
+    fn handle_impact_on_an_unknown_id_is_an_error() {
+        let err = coordinator()
+            .handle_impact(ImpactRequest {
+                id: "lib.rs#99".to_string(),
+                include_tests: true,
+                limit: 10,
+            })
+            .unwrap_err();
+        assert!(matches!(err, NavigatorError::UnknownSymbol { .. }));
+    }
+
+    #[test]
+    fn handle_impact_truncates_top_hits_but_not_the_file_count() {
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let a = chunk("a.rs", 1, "fn a() {\n    helper();\n}", Some("a"));
+        let b = chunk("b.rs", 1, "fn b() {\n    helper();\n}", Some("b"));
+        let coordinator = IndexCoordinator::new(vec![helper.clone(), a, b]);
+
+        let response = coordinator
+            .handle_impact(ImpactRequest {
+                id: chunk_id(&helper),
+                include_tests: true,
+                limit: 1,
+            })
+            .unwrap();
+
+        assert_eq!(response.top_hits.len(), 1, "limited");
+        assert_eq!(response.referencing_files, 2, "not limited");
+    }
+
+    #[test]
+    fn handle_call_graph_with_depth_zero_is_a_single_node_graph() {
+        let response = coordinator()
+            .handle_call_graph(CallGraphRequest {
+                id: "lib.rs#1".to_string(),
+                max_depth: 0,
+            })
+            .unwrap();
+
+        assert_eq!(response.nodes.len(), 1);
+        assert_eq!(response.nodes[0].id, "lib.rs#1");
+        assert_eq!(response.nodes[0].edge_count, 1);
+        assert!(response.edges.is_empty());
+    }
+
+    #[test]
+    fn handle_call_graph_with_depth_one_includes_direct_callers_and_callees() {
+        let response = coordinator()
+            .handle_call_graph(CallGraphRequest {
+                id: "lib.rs#1".to_string(),
+                max_depth: 1,
+            })
+            .unwrap();
+
+        let ids: Vec<&str> = response.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert!(ids.contains(&"lib.rs#1"));
+        assert!(ids.contains(&"lib.rs#3"));
+        assert_eq!(response.edges.len(), 1);
+        assert_eq!(response.edges[0].from, "lib.rs#3");
+        assert_eq!(response.edges[0].to, "lib.rs#1");
+    }
+
+    #[test]
+    fn handle_call_graph_stops_growing_once_the_frontier_is_exhausted() {
+        let depth_1 = coordinator()
+            .handle_call_graph(CallGraphRequest {
+                id: "lib.rs#1".to_string(),
+                max_depth: 1,
+            })
+            .unwrap();
+        let depth_5 = coordinator()
+            .handle_call_graph(CallGraphRequest {
+                id: "lib.rs#1".to_string(),
+                max_depth: 5,
+            })
+            .unwrap();
+
+        assert_eq!(depth_1.nodes.len(), depth_5.nodes.len());
+        assert_eq!(depth_1.edges.len(), depth_5.edges.len());
+    }
+
+    #[test]
+    fn handle_call_graph_on_an_unknown_id_is_an_error() {
+        let err = coordinator()
+            .handle_call_graph(CallGraphRequest {
+                id: "lib.rs#99".to_string(),
+                max_depth: 1,
+            })
+            .unwrap_err();
+        assert!(matches!(err, NavigatorError::UnknownSymbol { .. }));
+    }
+
+    #[test]
+    fn from_workspaces_namespaces_ids_by_workspace_and_tags_hits_with_their_origin() {
+        let web_helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let api_helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let coordinator = IndexCoordinator::from_workspaces(vec![
+            ("web".to_string(), vec![web_helper]),
+            ("api".to_string(), vec![api_helper]),
+        ]);
+
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: "helper".to_string(),
+                query_regex: None,
+                limit: 10,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: false,
+                chunk_types: Vec::new(),
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 2, "both workspaces' otherwise-identical ids coexist");
+        let origins: std::collections::BTreeSet<_> = response
+            .hits
+            .iter()
+            .map(|hit| hit.workspace.clone().unwrap())
+            .collect();
+        assert_eq!(
+            origins,
+            std::collections::BTreeSet::from(["web".to_string(), "api".to_string()])
+        );
+        assert!(response.hits.iter().any(|hit| hit.id == "web/lib.rs#1"));
+        assert!(response.hits.iter().any(|hit| hit.id == "api/lib.rs#1"));
+    }
+
+    #[test]
+    fn from_workspaces_search_can_be_restricted_to_one_root() {
+        let web_helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let api_helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let coordinator = IndexCoordinator::from_workspaces(vec![
+            ("web".to_string(), vec![web_helper]),
+            ("api".to_string(), vec![api_helper]),
+        ]);
+
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: "helper".to_string(),
+                query_regex: None,
+                limit: 10,
+                cursor: None,
+                root: Some("web".to_string()),
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: false,
+                chunk_types: Vec::new(),
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].workspace, Some("web".to_string()));
+    }
+
+    #[test]
+    fn definitions_only_returns_the_declaration_not_the_call_site() {
+        let coordinator = coordinator();
+
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: "helper".to_string(),
+                query_regex: None,
+                limit: 10,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: true,
+                chunk_types: Vec::new(),
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].path, "lib.rs");
+        assert_eq!(response.hits[0].line, 1);
+        assert_eq!(response.hits[0].preview, "fn helper() {}");
+    }
+
+    #[test]
+    fn chunk_types_filters_hits_to_the_requested_tags() {
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let helper = CodeChunk {
+            chunk_type: Some("function".to_string()),
+            ..helper
+        };
+        let helper_struct = chunk("lib.rs", 3, "struct Helper;", Some("Helper"));
+        let helper_struct = CodeChunk {
+            chunk_type: Some("struct".to_string()),
+            ..helper_struct
+        };
+        let coordinator = IndexCoordinator::new(vec![helper, helper_struct]);
+
+        let response = coordinator
+            .run_search(SearchRequest {
+                query: "helper".to_string(),
+                query_regex: None,
+                limit: 10,
+                cursor: None,
+                root: None,
+                fuzzy_fallback: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_references: false,
+                reference_limit: 0,
+                definitions_only: false,
+                chunk_types: vec!["struct".to_string()],
+            })
+            .unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].path, "lib.rs");
+        assert_eq!(response.hits[0].line, 3);
+    }
+
+    #[test]
+    fn ingest_delta_only_changes_the_edited_files_snapshot() {
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let other = chunk("other.rs", 1, "fn other() {}", Some("other"));
+        let coordinator = IndexCoordinator::new(vec![helper, other.clone()]);
+
+        let edited_helper = chunk("lib.rs", 1, "fn helper() { /* edited */ }", Some("helper"));
+        let coordinator = coordinator.ingest_delta(vec![edited_helper.clone()], &[]);
+
+        assert_eq!(
+            coordinator.handle_open("lib.rs#1").unwrap().body,
+            "fn helper() { /* edited */ }"
+        );
+        assert_eq!(coordinator.handle_open("other.rs#1").unwrap().body, other.content);
+    }
+
+    #[test]
+    fn ingest_delta_drops_removed_paths_from_the_snapshot() {
+        let helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let other = chunk("other.rs", 1, "fn other() {}", Some("other"));
+        let coordinator = IndexCoordinator::new(vec![helper, other]);
+
+        let coordinator = coordinator.ingest_delta(vec![], &["other.rs".to_string()]);
+
+        assert!(coordinator.handle_open("lib.rs#1").is_ok());
+        assert!(matches!(
+            coordinator.handle_open("other.rs#1").unwrap_err(),
+            NavigatorError::UnknownSymbol { .. }
+        ));
+    }
+
+    #[test]
+    fn ingest_delta_preserves_workspace_attribution_for_a_replaced_chunk() {
+        let web_helper = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let coordinator =
+            IndexCoordinator::from_workspaces(vec![("web".to_string(), vec![web_helper])]);
+
+        let edited = chunk("web/lib.rs", 1, "fn helper() { /* edited */ }", Some("helper"));
+        let coordinator = coordinator.ingest_delta(vec![edited], &[]);
+
+        let response = coordinator.handle_open("web/lib.rs#1").unwrap();
+        assert_eq!(response.hit.workspace, Some("web".to_string()));
+        assert_eq!(response.body, "fn helper() { /* edited */ }");
+    }
+
+    #[test]
+    fn handle_references_on_a_literal_id_is_not_supported_instead_of_panicking() {
+        let err = coordinator()
+            .handle_references(ReferencesRequest {
+                id: "literal::lib.rs#1".to_string(),
+                direction: ReferencesDirection::Both,
+                limit: 10,
+            })
+            .unwrap_err();
+
+        assert!(matches!(err, NavigatorError::NotSupported { .. }));
+    }
+}
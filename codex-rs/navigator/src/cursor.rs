@@ -0,0 +1,88 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use crate::proto::NavigatorError;
+
+/// Opaque continuation token for paginating [`crate::SearchResponse::hits`].
+///
+/// This crate has no persistent query cache (no daemon, no on-disk queries
+/// dir) to stash a candidate list in for later pages, so a cursor round-trips
+/// the offset into the query's matches instead; resuming a search re-runs the
+/// same scan and skips ahead rather than replaying a cached candidate list.
+/// `fingerprint` stands in for a real index-generation counter - it's a hash
+/// of the query plus the indexed chunk count, so a cursor issued against one
+/// snapshot of the index is rejected as stale if chunks were added or
+/// removed before it's resumed, even without a true generation counter to
+/// compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SearchCursor {
+    pub(crate) offset: usize,
+    fingerprint: u64,
+}
+
+impl SearchCursor {
+    pub(crate) fn new(offset: usize, seed: impl Hash) -> Self {
+        Self {
+            offset,
+            fingerprint: fingerprint_of(seed),
+        }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        format!("{}:{:x}", self.offset, self.fingerprint)
+    }
+
+    pub(crate) fn decode(cursor: &str, seed: impl Hash) -> Result<Self, NavigatorError> {
+        let malformed = || NavigatorError::InvalidQuery {
+            message: format!("malformed search cursor `{cursor}`"),
+        };
+        let (offset, fingerprint_hex) = cursor.split_once(':').ok_or_else(malformed)?;
+        let offset: usize = offset.parse().map_err(|_| malformed())?;
+        let fingerprint =
+            u64::from_str_radix(fingerprint_hex, 16).map_err(|_| malformed())?;
+
+        if fingerprint != fingerprint_of(seed) {
+            return Err(NavigatorError::InvalidQuery {
+                message: "search cursor is stale: the index changed since this query was issued"
+                    .to_string(),
+            });
+        }
+        Ok(Self { offset, fingerprint })
+    }
+}
+
+fn fingerprint_of(seed: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_cursor_round_trips_through_encode_and_decode() {
+        let cursor = SearchCursor::new(40, ("fuzzy", "needle", 12usize));
+        let encoded = cursor.encode();
+
+        let decoded = SearchCursor::decode(&encoded, ("fuzzy", "needle", 12usize)).unwrap();
+        assert_eq!(decoded.offset, 40);
+    }
+
+    #[test]
+    fn a_cursor_is_rejected_as_stale_when_the_chunk_count_changes() {
+        let cursor = SearchCursor::new(40, ("fuzzy", "needle", 12usize));
+        let encoded = cursor.encode();
+
+        let err = SearchCursor::decode(&encoded, ("fuzzy", "needle", 13usize)).unwrap_err();
+        assert!(matches!(err, NavigatorError::InvalidQuery { .. }));
+    }
+
+    #[test]
+    fn a_malformed_cursor_is_an_invalid_query_not_a_panic() {
+        let err = SearchCursor::decode("not-a-cursor", ("fuzzy", "needle", 12usize)).unwrap_err();
+        assert!(matches!(err, NavigatorError::InvalidQuery { .. }));
+    }
+}
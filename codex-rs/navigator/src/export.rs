@@ -0,0 +1,154 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use crate::proto::CallGraphResponse;
+
+/// Output format for [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+/// Renders a [`crate::IndexCoordinator::handle_call_graph`] response as
+/// Graphviz DOT or a Mermaid flowchart, for viewing outside this crate's own
+/// indented-text CLI output.
+///
+/// Node ids (`path#line`, e.g. `lib.rs#42`) contain characters that are
+/// illegal in a bare DOT or Mermaid identifier, so every id is
+/// quoted/escaped rather than emitted as-is. [`GraphNode::edge_count`]
+/// drives node size (DOT `penwidth`) since this graph has no file/directory
+/// hierarchy to size nodes by. An empty graph still renders a minimal valid
+/// `digraph call_graph {}` / `flowchart TD` rather than empty output.
+pub fn render(graph: &CallGraphResponse, format: GraphFormat) -> String {
+    match format {
+        GraphFormat::Dot => render_dot(graph),
+        GraphFormat::Mermaid => render_mermaid(graph),
+    }
+}
+
+fn render_dot(graph: &CallGraphResponse) -> String {
+    let mut out = String::from("digraph call_graph {\n");
+    for node in &graph.nodes {
+        let penwidth = 1.0 + node.edge_count as f64 * 0.5;
+        out.push_str(&format!(
+            "  {} [label={}, penwidth={penwidth:.1}];\n",
+            dot_quote(&node.id),
+            dot_quote(&node.label),
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  {} -> {};\n",
+            dot_quote(&edge.from),
+            dot_quote(&edge.to),
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_mermaid(graph: &CallGraphResponse) -> String {
+    let mut out = String::from("flowchart TD\n");
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  {}[\"{} ({})\"]\n",
+            mermaid_id(&node.id),
+            mermaid_escape(&node.label),
+            node.edge_count,
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  {} --> {}\n",
+            mermaid_id(&edge.from),
+            mermaid_id(&edge.to),
+        ));
+    }
+    out
+}
+
+/// A DOT quoted string, safe for any identifier or label: backslashes and
+/// double quotes are escaped, everything else (including `#`, `:`, spaces)
+/// is legal inside a DOT quoted string as-is.
+fn dot_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A Mermaid node id derived from an arbitrary graph node id: Mermaid node
+/// ids can't contain most punctuation, so this keeps only ASCII
+/// alphanumerics from `id` and appends a short hash of the original to keep
+/// otherwise-colliding ids (e.g. `a#1` and `a_1`) distinct.
+fn mermaid_id(id: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    let tag = hasher.finish() & 0xffff;
+    let alnum: String = id.chars().filter(char::is_ascii_alphanumeric).collect();
+    format!("n{alnum}_{tag:x}")
+}
+
+/// Mermaid node labels are double-quoted strings; escape the one character
+/// that would otherwise end the label early.
+fn mermaid_escape(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::GraphEdge;
+    use crate::proto::GraphNode;
+
+    fn sample_graph() -> CallGraphResponse {
+        CallGraphResponse {
+            nodes: vec![
+                GraphNode {
+                    id: "lib.rs#1".to_string(),
+                    label: "helper".to_string(),
+                    edge_count: 2,
+                },
+                GraphNode {
+                    id: "lib.rs#3".to_string(),
+                    label: "run".to_string(),
+                    edge_count: 1,
+                },
+            ],
+            edges: vec![GraphEdge {
+                from: "lib.rs#3".to_string(),
+                to: "lib.rs#1".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn dot_output_quotes_ids_containing_illegal_characters() {
+        let dot = render(&sample_graph(), GraphFormat::Dot);
+        assert!(dot.starts_with("digraph call_graph {\n"));
+        assert!(dot.contains("\"lib.rs#1\" [label=\"helper\", penwidth=2.0];"));
+        assert!(dot.contains("\"lib.rs#3\" -> \"lib.rs#1\";"));
+    }
+
+    #[test]
+    fn mermaid_output_uses_sanitized_ids_and_keeps_labels_readable() {
+        let mermaid = render(&sample_graph(), GraphFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("helper (2)"));
+        assert!(mermaid.contains("-->"));
+        assert!(!mermaid.contains('#'), "mermaid node ids must not contain '#'");
+    }
+
+    #[test]
+    fn distinct_ids_that_sanitize_to_the_same_alnum_string_stay_distinct() {
+        let a = mermaid_id("a#1");
+        let b = mermaid_id("a_1");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn an_empty_graph_still_renders_a_minimal_valid_graph() {
+        let empty = CallGraphResponse::default();
+        assert_eq!(render(&empty, GraphFormat::Dot), "digraph call_graph {\n}\n");
+        assert_eq!(render(&empty, GraphFormat::Mermaid), "flowchart TD\n");
+    }
+}
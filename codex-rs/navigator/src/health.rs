@@ -0,0 +1,180 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+const HEALTH_VERSION: u32 = 1;
+
+/// Upper bound on how many distinct operation labels [`HealthStats`] tracks.
+/// The least-called entry is evicted once this is exceeded, so a caller
+/// that runs one-off queries with unique labels can't grow the file
+/// without bound.
+pub const HOTSPOT_HISTORY_LIMIT: usize = 50;
+
+/// One operation's accumulated timing, keyed by a caller-chosen label (e.g.
+/// `"search"`, `"refs"`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub label: String,
+    pub calls: u64,
+    pub total_micros: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HealthStatsFile {
+    version: u32,
+    hotspots: Vec<Hotspot>,
+}
+
+/// Per-operation timing stats for navigator CLI commands, persisted next to
+/// the store so they survive across invocations.
+///
+/// This crate has no daemon and no in-memory process that a caller could
+/// restart - every CLI invocation is already a fresh process, so there's
+/// nothing to merge these into except whatever is already on disk. Persisted
+/// as `{table_path}.health.json`, mirroring how
+/// [`codex_vector_store::VectorStore`] sidecars its own schema metadata at
+/// `{table_path}.meta.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthStats {
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl HealthStats {
+    pub fn path_for(table_path: &Path) -> PathBuf {
+        let mut path = table_path.as_os_str().to_os_string();
+        path.push(".health.json");
+        PathBuf::from(path)
+    }
+
+    /// Loads persisted stats from `path`, discarding a missing, corrupt, or
+    /// version-mismatched file silently rather than failing - the same
+    /// reset-not-fail contract as
+    /// the codebase indexer's `FileHashes::load` (a private module of
+    /// `codex-codebase-indexer`, which this crate doesn't depend on) has for
+    /// its own sidecar file.
+    pub fn load(path: &Path) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<HealthStatsFile>(&contents) {
+            Ok(file) if file.version == HEALTH_VERSION => Self { hotspots: file.hotspots },
+            Ok(_) | Err(_) => Self::default(),
+        }
+    }
+
+    pub fn persist(&self, path: &Path) -> io::Result<()> {
+        let file = HealthStatsFile {
+            version: HEALTH_VERSION,
+            hotspots: self.hotspots.clone(),
+        };
+        let contents =
+            serde_json::to_string_pretty(&file).expect("HealthStatsFile is always serializable");
+        fs::write(path, contents)
+    }
+
+    /// Records one call to `label`, merging into its existing entry if
+    /// present, then evicts the least-called entry if that pushed the
+    /// tracked label count past [`HOTSPOT_HISTORY_LIMIT`].
+    pub fn record(&mut self, label: &str, elapsed: Duration) {
+        match self.hotspots.iter_mut().find(|hotspot| hotspot.label == label) {
+            Some(hotspot) => {
+                hotspot.calls += 1;
+                hotspot.total_micros += elapsed.as_micros() as u64;
+            }
+            None => self.hotspots.push(Hotspot {
+                label: label.to_string(),
+                calls: 1,
+                total_micros: elapsed.as_micros() as u64,
+            }),
+        }
+
+        if self.hotspots.len() > HOTSPOT_HISTORY_LIMIT {
+            let least_called = self
+                .hotspots
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, hotspot)| hotspot.calls)
+                .map(|(index, _)| index);
+            if let Some(index) = least_called {
+                self.hotspots.remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_merges_repeat_calls_to_the_same_label() {
+        let mut stats = HealthStats::default();
+        stats.record("search", Duration::from_millis(10));
+        stats.record("search", Duration::from_millis(20));
+
+        assert_eq!(stats.hotspots.len(), 1);
+        assert_eq!(stats.hotspots[0].calls, 2);
+        assert_eq!(stats.hotspots[0].total_micros, 30_000);
+    }
+
+    #[test]
+    fn record_evicts_the_least_called_label_once_over_the_cap() {
+        let mut stats = HealthStats::default();
+        for i in 0..HOTSPOT_HISTORY_LIMIT {
+            stats.record(&format!("op-{i}"), Duration::from_millis(1));
+        }
+        // "op-0" through "op-(LIMIT-1)" each have 1 call; calling one of them
+        // again first makes it not the least-called entry, so a brand new
+        // label evicts a different, still-single-call one instead.
+        stats.record("op-0", Duration::from_millis(1));
+        stats.record("new-op", Duration::from_millis(1));
+
+        assert_eq!(stats.hotspots.len(), HOTSPOT_HISTORY_LIMIT);
+        assert!(stats.hotspots.iter().any(|hotspot| hotspot.label == "op-0"));
+        assert!(stats.hotspots.iter().any(|hotspot| hotspot.label == "new-op"));
+    }
+
+    #[test]
+    fn load_of_a_missing_file_is_empty_stats_not_an_error() {
+        let stats = HealthStats::load(Path::new("/nonexistent/path/health.json"));
+        assert!(stats.hotspots.is_empty());
+    }
+
+    #[test]
+    fn persist_then_load_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = HealthStats::path_for(&dir.path().join("table.ndjson"));
+
+        let mut stats = HealthStats::default();
+        stats.record("search", Duration::from_millis(5));
+        stats.persist(&path).unwrap();
+
+        let loaded = HealthStats::load(&path);
+        assert_eq!(loaded, stats);
+    }
+
+    #[test]
+    fn load_discards_a_corrupt_file_silently() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.ndjson.health.json");
+        fs::write(&path, "not json").unwrap();
+
+        let stats = HealthStats::load(&path);
+        assert!(stats.hotspots.is_empty());
+    }
+
+    #[test]
+    fn load_discards_a_version_mismatched_file_silently() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.ndjson.health.json");
+        fs::write(&path, r#"{"version":999,"hotspots":[]}"#).unwrap();
+
+        let stats = HealthStats::load(&path);
+        assert!(stats.hotspots.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Streams an indexed snapshot out as newline-delimited JSON, for offline
+//! analysis this crate has no business doing itself (symbol counts by
+//! module, churn vs. size scatter plots, ...).
+//!
+//! There's no `ExportRequest`/`IndexSnapshot` type in this codebase, and no
+//! concurrent rebuild for a read lock to guard against - an
+//! [`crate::IndexCoordinator`] is an immutable snapshot built once per CLI
+//! invocation, not a long-lived structure something else could be mutating
+//! underneath this call. What *is* real is the bounded-memory requirement:
+//! [`write_jsonl`] writes one [`IndexExportRecord`] at a time straight to the
+//! caller's writer, so a large index is never collected into one giant
+//! string before it's written out.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write;
+
+use codex_vector_store::CodeChunk;
+use serde::Serialize;
+
+use crate::references::ReferenceGraph;
+use crate::references::chunk_id;
+
+/// Which part of the index a [`write_jsonl`] call should include, and in
+/// what order - each entry in the slice contributes a contiguous run of
+/// records of its own [`IndexExportRecord`] variant to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexExportKind {
+    Symbols,
+    Files,
+    References,
+}
+
+/// One line of [`write_jsonl`]'s output. `kind` (from `#[serde(tag)]`) is
+/// the stable, documented discriminant a downstream reader matches on;
+/// every other field is specific to that variant.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndexExportRecord {
+    Symbol {
+        id: String,
+        path: String,
+        line: usize,
+        symbol_name: Option<String>,
+        chunk_type: Option<String>,
+    },
+    File {
+        path: String,
+        chunk_count: usize,
+    },
+    Reference {
+        caller_id: String,
+        callee_id: String,
+        line: usize,
+        preview: String,
+    },
+}
+
+/// Writes `chunks` (and, for [`IndexExportKind::References`], the call-graph
+/// edges built over them) to `writer` as one JSON object per line, in the
+/// order `kinds` lists them.
+pub fn write_jsonl(
+    chunks: &[CodeChunk],
+    kinds: &[IndexExportKind],
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    for kind in kinds {
+        match kind {
+            IndexExportKind::Symbols => write_symbol_records(chunks, writer)?,
+            IndexExportKind::Files => write_file_records(chunks, writer)?,
+            IndexExportKind::References => write_reference_records(chunks, writer)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_symbol_records(chunks: &[CodeChunk], writer: &mut impl Write) -> io::Result<()> {
+    for chunk in chunks {
+        write_record(
+            writer,
+            &IndexExportRecord::Symbol {
+                id: chunk_id(chunk),
+                path: chunk.path.clone(),
+                line: chunk.start_line,
+                symbol_name: chunk.symbol_name.clone(),
+                chunk_type: chunk.chunk_type.clone(),
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn write_file_records(chunks: &[CodeChunk], writer: &mut impl Write) -> io::Result<()> {
+    let mut chunk_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for chunk in chunks {
+        *chunk_counts.entry(chunk.path.as_str()).or_insert(0) += 1;
+    }
+    for (path, chunk_count) in chunk_counts {
+        write_record(writer, &IndexExportRecord::File { path: path.to_string(), chunk_count })?;
+    }
+    Ok(())
+}
+
+fn write_reference_records(chunks: &[CodeChunk], writer: &mut impl Write) -> io::Result<()> {
+    let graph = ReferenceGraph::build(chunks);
+    for edge in graph.all_edges() {
+        write_record(
+            writer,
+            &IndexExportRecord::Reference {
+                caller_id: edge.caller_id.clone(),
+                callee_id: edge.callee_id.clone(),
+                line: edge.line,
+                preview: edge.preview.clone(),
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn write_record(writer: &mut impl Write, record: &IndexExportRecord) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, record)
+        .map_err(|source| io::Error::new(io::ErrorKind::InvalidData, source))?;
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start_line: usize, content: &str, symbol_name: Option<&str>) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line,
+            end_line: start_line + content.lines().count().saturating_sub(1),
+            content: content.to_string(),
+            language: Some("rust".to_string()),
+            chunk_type: None,
+            symbol_name: symbol_name.map(str::to_string),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    fn lines_of(bytes: &[u8]) -> Vec<serde_json::Value> {
+        std::str::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn symbols_kind_emits_one_record_per_chunk() {
+        let chunks = vec![chunk("lib.rs", 1, "fn helper() {}", Some("helper"))];
+        let mut out = Vec::new();
+
+        write_jsonl(&chunks, &[IndexExportKind::Symbols], &mut out).unwrap();
+
+        let records = lines_of(&out);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["kind"], "symbol");
+        assert_eq!(records[0]["id"], "lib.rs#1");
+        assert_eq!(records[0]["symbol_name"], "helper");
+    }
+
+    #[test]
+    fn files_kind_aggregates_chunk_counts_per_path() {
+        let chunks = vec![
+            chunk("lib.rs", 1, "fn a() {}", Some("a")),
+            chunk("lib.rs", 3, "fn b() {}", Some("b")),
+            chunk("other.rs", 1, "fn c() {}", Some("c")),
+        ];
+        let mut out = Vec::new();
+
+        write_jsonl(&chunks, &[IndexExportKind::Files], &mut out).unwrap();
+
+        let records = lines_of(&out);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["path"], "lib.rs");
+        assert_eq!(records[0]["chunk_count"], 2);
+        assert_eq!(records[1]["path"], "other.rs");
+        assert_eq!(records[1]["chunk_count"], 1);
+    }
+
+    #[test]
+    fn references_kind_emits_one_record_per_call_edge() {
+        let chunks = vec![
+            chunk("lib.rs", 1, "fn helper() {}", Some("helper")),
+            chunk("lib.rs", 3, "fn run() {\n    helper();\n}", Some("run")),
+        ];
+        let mut out = Vec::new();
+
+        write_jsonl(&chunks, &[IndexExportKind::References], &mut out).unwrap();
+
+        let records = lines_of(&out);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["kind"], "reference");
+        assert_eq!(records[0]["caller_id"], "lib.rs#3");
+        assert_eq!(records[0]["callee_id"], "lib.rs#1");
+    }
+
+    #[test]
+    fn kinds_are_emitted_in_the_requested_order() {
+        let chunks = vec![chunk("lib.rs", 1, "fn helper() {}", Some("helper"))];
+        let mut out = Vec::new();
+
+        write_jsonl(&chunks, &[IndexExportKind::Files, IndexExportKind::Symbols], &mut out)
+            .unwrap();
+
+        let records = lines_of(&out);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["kind"], "file");
+        assert_eq!(records[1]["kind"], "symbol");
+    }
+}
@@ -0,0 +1,45 @@
+//! Resolves "open this symbol" / "show a snippet" / "who calls this" style
+//! queries against a snapshot of indexed chunks.
+//!
+//! This is deliberately decoupled from `codex-codebase-indexer`: an
+//! [`IndexCoordinator`] is built from a plain `Vec<CodeChunk>` rather than
+//! from the indexer itself, so callers can hand it whatever snapshot of the
+//! index they already have in memory (e.g. after `CodebaseIndexer::index`).
+
+mod coordinator;
+mod cursor;
+pub mod export;
+pub mod health;
+pub mod index_export;
+mod proto;
+mod query;
+mod references;
+mod search;
+
+pub use coordinator::IndexCoordinator;
+pub use proto::CallGraphRequest;
+pub use proto::CallGraphResponse;
+pub use proto::ErrorCode;
+pub use proto::ErrorPayload;
+pub use proto::GraphEdge;
+pub use proto::GraphNode;
+pub use proto::HealthRequest;
+pub use proto::HealthResponse;
+pub use proto::ImpactRequest;
+pub use proto::ImpactResponse;
+pub use proto::NavHit;
+pub use proto::NavigatorError;
+pub use proto::OpenRequest;
+pub use proto::OpenResponse;
+pub use proto::ReferencesDirection;
+pub use proto::ReferencesRequest;
+pub use proto::ReferencesResponse;
+pub use proto::SearchMode;
+pub use proto::SearchRequest;
+pub use proto::SearchResponse;
+pub use proto::SearchStats;
+pub use proto::SnippetRequest;
+pub use proto::SnippetResponse;
+pub use references::CallEdge;
+pub use references::ReferenceGraph;
+pub use references::ReferenceLocation;
@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::references::ReferenceLocation;
+
+/// A located hit returned by the navigator: either a symbol definition (from
+/// `handle_open`/`handle_snippet`) or a call site (from `handle_references`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavHit {
+    pub id: String,
+    pub path: String,
+    pub line: usize,
+    pub preview: String,
+    /// Which workspace (as passed to
+    /// [`crate::IndexCoordinator::from_workspaces`]) this hit came from.
+    /// `None` for a coordinator built with [`crate::IndexCoordinator::new`],
+    /// which has no concept of multiple roots.
+    pub workspace: Option<String>,
+    /// `true` if this hit came from [`crate::SearchRequest::fuzzy_fallback`]
+    /// nucleo scoring rather than an exact substring/regex/boolean match.
+    /// Always `false` outside of `run_search`.
+    pub is_fuzzy_match: bool,
+    /// Approximate caller locations for this hit's symbol, from
+    /// [`crate::references::find_references`]. `Some` only when the
+    /// originating request set [`SearchRequest::include_references`];
+    /// `None` otherwise, including for every hit outside of `run_search`.
+    pub references: Option<Vec<ReferenceLocation>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenRequest {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenResponse {
+    pub hit: NavHit,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetRequest {
+    pub id: String,
+    pub context_lines: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetResponse {
+    pub hit: NavHit,
+    pub snippet: String,
+}
+
+/// Which side of the call graph a [`ReferencesRequest`] wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferencesDirection {
+    /// Call sites that call *into* the symbol ("who calls this").
+    Incoming,
+    /// Call sites the symbol itself calls ("what this calls").
+    Outgoing,
+    /// Both incoming and outgoing call sites.
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferencesRequest {
+    pub id: String,
+    pub direction: ReferencesDirection,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferencesResponse {
+    pub hits: Vec<NavHit>,
+}
+
+/// A request to assess how widely used a symbol is before a large rename -
+/// see [`crate::IndexCoordinator::handle_impact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactRequest {
+    pub id: String,
+    /// Include call sites inside `#[test]` functions (chunks the chunker
+    /// tagged `chunk_type: "test"`) in the report. Off by default, since a
+    /// rename's test-only fallout is usually mechanical and less
+    /// interesting than its production call sites.
+    pub include_tests: bool,
+    /// Maximum number of [`NavHit`]s in [`ImpactResponse::top_hits`]. The
+    /// counts in [`ImpactResponse::referencing_files`] and
+    /// [`ImpactResponse::by_chunk_type`] are never truncated by this.
+    pub limit: usize,
+}
+
+/// Report on what calls a symbol, for scoping a rename before doing it.
+///
+/// This codebase has no codeowners resolver and no `ContextBanner` concept
+/// to bucket by - [`Self::by_chunk_type`] buckets by
+/// [`codex_vector_store::CodeChunk::chunk_type`] instead, the one
+/// categorical dimension the chunker actually attaches to a chunk. A symbol
+/// with zero references produces a valid, empty report rather than an
+/// error: see [`crate::IndexCoordinator::handle_impact`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImpactResponse {
+    /// Distinct files containing at least one call site, after
+    /// `include_tests` filtering.
+    pub referencing_files: usize,
+    /// Call site count per caller `chunk_type` (e.g. `"function"`,
+    /// `"test"`), `"unknown"` for a chunk the chunker didn't tag.
+    pub by_chunk_type: BTreeMap<String, usize>,
+    /// Up to `request.limit` call sites, in the order
+    /// [`crate::references::ReferenceGraph::callers_of`] returns them.
+    pub top_hits: Vec<NavHit>,
+}
+
+/// A node in a [`CallGraphResponse`]: one indexed chunk reachable from the
+/// requested root, labeled for display and sized by how many edges touch
+/// it (this graph has no file/directory hierarchy to size nodes by - see
+/// [`crate::export`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub edge_count: usize,
+}
+
+/// A directed edge in a [`CallGraphResponse`]: `from` calls `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// A request to export the caller/callee graph reachable from `id` - see
+/// [`crate::IndexCoordinator::handle_call_graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraphRequest {
+    pub id: String,
+    /// How many hops of callers/callees to follow outward from `id` before
+    /// stopping. `0` returns just the root node with no edges.
+    pub max_depth: usize,
+}
+
+/// The caller/callee graph reachable from a [`CallGraphRequest::id`] within
+/// `max_depth` hops. A symbol with no callers or callees still produces a
+/// valid response: a single node and no edges, not an error - see
+/// [`crate::IndexCoordinator::handle_call_graph`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraphResponse {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Errors returned by [`crate::IndexCoordinator`]'s request handlers.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NavigatorError {
+    #[error("no indexed symbol with id `{id}`")]
+    UnknownSymbol { id: String },
+
+    /// Returned instead of panicking when a request targets an id the
+    /// navigator has no data for by construction - e.g. a `literal::`
+    /// position id, which names a raw source position rather than an
+    /// indexed chunk.
+    #[error("{reason} (id: `{id}`)")]
+    NotSupported { id: String, reason: String },
+
+    /// A [`crate::SearchRequest`] couldn't be run as given, e.g. its
+    /// `query_regex` failed to compile.
+    #[error("invalid search query: {message}")]
+    InvalidQuery { message: String },
+}
+
+/// Machine-readable category for an [`ErrorPayload`].
+///
+/// Kept distinct from [`NavigatorError`]'s variants so a caller surfacing
+/// errors over a wire protocol (e.g. the CLI or a future daemon) has a
+/// small, stable set of codes to match on instead of depending on
+/// `NavigatorError`'s exact shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidRequest,
+    NotFound,
+    NotSupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorPayload {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl From<NavigatorError> for ErrorPayload {
+    fn from(err: NavigatorError) -> Self {
+        let code = match &err {
+            NavigatorError::UnknownSymbol { .. } => ErrorCode::NotFound,
+            NavigatorError::NotSupported { .. } => ErrorCode::NotSupported,
+            NavigatorError::InvalidQuery { .. } => ErrorCode::InvalidRequest,
+        };
+        ErrorPayload {
+            code,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A request to report the state of the indexed snapshot a coordinator was
+/// built from - see [`crate::IndexCoordinator::handle_health`].
+///
+/// There's no `code-finder` daemon in this codebase for this to probe
+/// readiness of over a JSON-RPC connection - every CLI invocation already
+/// builds (or reopens) its own snapshot and exits, so "is the index ready"
+/// is answered by the same process call that would go on to search it, not
+/// a separate probe a long-lived client makes first. For the same reason
+/// there's no watcher to report status for, and no build-duration timer
+/// kept alongside the snapshot itself - per-operation timings already live
+/// in [`crate::health::HealthStats`], persisted across invocations, which
+/// is the closest real equivalent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthRequest;
+
+/// Point-in-time counts over an [`crate::IndexCoordinator`]'s snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthResponse {
+    pub indexed_chunks: usize,
+    pub indexed_files: usize,
+    pub indexed_symbols: usize,
+    pub reference_edges: usize,
+}
+
+/// A navigator search request: plain substring matching by default, or - when
+/// `query_regex` is set - a regular expression matched against chunk content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchRequest {
+    pub query: String,
+    pub query_regex: Option<String>,
+    pub limit: usize,
+    /// Continuation token from a previous [`SearchResponse::next_cursor`],
+    /// resuming that search after the hits it already returned. `None`
+    /// starts from the beginning.
+    pub cursor: Option<String>,
+    /// Restrict the search to one workspace (as passed to
+    /// [`crate::IndexCoordinator::from_workspaces`]). `None` searches every
+    /// indexed root. Ignored by a coordinator built with
+    /// [`crate::IndexCoordinator::new`].
+    pub root: Option<String>,
+    /// When set, and exact matching (substring/regex/boolean) returns fewer
+    /// than `limit` hits on the first page (`cursor` is `None`), fills the
+    /// remainder with nucleo fuzzy-scored lines - see
+    /// [`crate::search::run_fuzzy_search`]. Off by default: scoring every
+    /// remaining line against the query is more work than the exact scan,
+    /// so this keeps a plain search's latency predictable.
+    pub fuzzy_fallback: bool,
+    /// When `true`, exact matching (substring/boolean query terms) requires
+    /// the same letter case as `query`/the term text. Off by default, like
+    /// this crate's matching has always been - see
+    /// [`crate::search::term_matches`].
+    pub case_sensitive: bool,
+    /// When `true`, exact matching requires `query`/the term text to appear
+    /// at a token boundary (`\b...\b`), so `new` no longer matches inside
+    /// `renew`. Off by default - see [`crate::search::term_matches`].
+    pub whole_word: bool,
+    /// When `true`, populate each hit's [`NavHit::references`] with its
+    /// symbol's approximate caller locations (see
+    /// [`crate::references::find_references`]). Off by default: computing
+    /// references rescans every chunk per hit, which is far more work than
+    /// the search itself.
+    pub include_references: bool,
+    /// Caps [`NavHit::references`] per hit when [`Self::include_references`]
+    /// is set. Ignored otherwise.
+    pub reference_limit: usize,
+    /// When `true`, restrict matching to each chunk's own declaration line
+    /// (see [`crate::search::definition_candidates`]) instead of its full
+    /// body, so a hit on `foo` is `foo`'s definition rather than a call
+    /// site inside some other chunk that happens to mention it. Also
+    /// suppresses [`Self::fuzzy_fallback`], since a typo-tolerant fallback
+    /// match is never itself a definition. Off by default.
+    pub definitions_only: bool,
+    /// Restricts the search to chunks whose
+    /// [`codex_vector_store::CodeChunk::chunk_type`] is one of these (e.g.
+    /// `"function"`, `"struct"` - whatever the chunker tagged it with).
+    /// Empty (the default) matches any chunk type.
+    pub chunk_types: Vec<String>,
+}
+
+/// Which matching strategy produced a [`SearchResponse`]'s hits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    Fuzzy,
+    Regex,
+    /// `query` parsed as an `AND`/`OR`/`NOT` boolean expression (see
+    /// [`crate::query`]) rather than a plain substring.
+    Boolean,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchStats {
+    pub mode: SearchMode,
+    /// Number of indexed chunks scanned to produce these hits.
+    pub chunks_scanned: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResponse {
+    pub hits: Vec<NavHit>,
+    pub stats: SearchStats,
+    /// Pass back as [`SearchRequest::cursor`] to fetch the next page of
+    /// hits. `None` once there are no more.
+    pub next_cursor: Option<String>,
+    /// Non-fatal notes about how this search was actually run, e.g. a
+    /// malformed boolean query (see [`crate::query::parse_boolean_query`])
+    /// that was ignored in favor of plain substring matching. Empty in the
+    /// common case.
+    pub hints: Vec<String>,
+}
@@ -0,0 +1,260 @@
+//! A tiny boolean query grammar for [`crate::search::run_fuzzy_search`]:
+//! quoted phrases, `AND`/`OR`/`NOT` keywords (case-insensitive) and `+`/`-`
+//! prefixes, combined into an expression tree.
+//!
+//! This crate has no trigram (or other) token index to pre-filter a
+//! candidate set against - see [`crate::search::run_regex_search`]'s doc
+//! comment - so a [`QueryExpr`] is evaluated the same way plain fuzzy search
+//! already is: a `contains` against one line at a time, case-insensitive by
+//! default (see [`QueryExpr::matches_with`] for `case_sensitive`/
+//! `whole_word`). There are no parentheses; `OR` separates top-level groups,
+//! and terms within a group (space-separated, or joined by an explicit
+//! `AND`) are implicitly ANDed together.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryExpr {
+    Term(String),
+    Not(Box<QueryExpr>),
+    And(Vec<QueryExpr>),
+    Or(Vec<QueryExpr>),
+}
+
+impl QueryExpr {
+    pub fn matches(&self, line: &str) -> bool {
+        self.matches_with(line, false, false)
+    }
+
+    /// Same evaluation as [`Self::matches`], but each [`QueryExpr::Term`]
+    /// leaf is checked with [`crate::search::term_matches`] instead of a
+    /// hardcoded case-insensitive `contains` - see
+    /// [`crate::SearchRequest::case_sensitive`] and
+    /// [`crate::SearchRequest::whole_word`].
+    pub fn matches_with(&self, line: &str, case_sensitive: bool, whole_word: bool) -> bool {
+        match self {
+            QueryExpr::Term(term) => {
+                crate::search::term_matches(line, term, case_sensitive, whole_word)
+            }
+            QueryExpr::Not(inner) => !inner.matches_with(line, case_sensitive, whole_word),
+            QueryExpr::And(parts) => {
+                parts.iter().all(|part| part.matches_with(line, case_sensitive, whole_word))
+            }
+            QueryExpr::Or(parts) => {
+                parts.iter().any(|part| part.matches_with(line, case_sensitive, whole_word))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RawToken {
+    Word(String),
+    Phrase(String),
+    And,
+    Or,
+    Not,
+    Plus,
+    Minus,
+}
+
+/// Parses `query` as a boolean expression if it contains any `AND`/`OR`/`NOT`
+/// keyword, `+`/`-` prefix, or quoted phrase; returns `None` for a query with
+/// none of that syntax, so a caller's existing plain-substring behavior is
+/// unaffected. `Some(Err(..))` means the syntax was present but malformed
+/// (e.g. an unterminated quote, or an operator with no operand) - the
+/// message is meant to be surfaced as a hint, with the caller falling back
+/// to treating the raw query as a plain substring.
+pub fn parse_boolean_query(query: &str) -> Option<Result<QueryExpr, String>> {
+    let tokens = match tokenize(query) {
+        Ok(tokens) => tokens,
+        Err(message) => return Some(Err(message)),
+    };
+    if !tokens.iter().any(is_boolean_syntax) {
+        return None;
+    }
+
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    Some(parser.parse_or().and_then(|expr| {
+        if parser.pos == tokens.len() {
+            Ok(expr)
+        } else {
+            Err(format!("unexpected input after `{query}`"))
+        }
+    }))
+}
+
+fn is_boolean_syntax(token: &RawToken) -> bool {
+    !matches!(token, RawToken::Word(_))
+}
+
+fn tokenize(query: &str) -> Result<Vec<RawToken>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            let mut phrase = String::new();
+            let mut closed = false;
+            for c in chars.by_ref() {
+                if c == '"' {
+                    closed = true;
+                    break;
+                }
+                phrase.push(c);
+            }
+            if !closed {
+                return Err(format!("unterminated quote in `{query}`"));
+            }
+            tokens.push(RawToken::Phrase(phrase));
+            continue;
+        }
+        if c == '+' {
+            chars.next();
+            tokens.push(RawToken::Plus);
+            continue;
+        }
+        if c == '-' {
+            chars.next();
+            tokens.push(RawToken::Minus);
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '"' {
+                break;
+            }
+            word.push(c);
+            chars.next();
+        }
+        tokens.push(match word.to_uppercase().as_str() {
+            "AND" => RawToken::And,
+            "OR" => RawToken::Or,
+            "NOT" => RawToken::Not,
+            _ => RawToken::Word(word),
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [RawToken],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&RawToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&RawToken> {
+        let token = self.tokens.get(self.pos);
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<QueryExpr, String> {
+        let mut parts = vec![self.parse_and()?];
+        while matches!(self.peek(), Some(RawToken::Or)) {
+            self.advance();
+            parts.push(self.parse_and()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { QueryExpr::Or(parts) })
+    }
+
+    fn parse_and(&mut self) -> Result<QueryExpr, String> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(RawToken::And) => {
+                    self.advance();
+                    parts.push(self.parse_unary()?);
+                }
+                Some(RawToken::Not | RawToken::Plus | RawToken::Minus)
+                | Some(RawToken::Word(_))
+                | Some(RawToken::Phrase(_)) => parts.push(self.parse_unary()?),
+                Some(RawToken::Or) | None => break,
+            }
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { QueryExpr::And(parts) })
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryExpr, String> {
+        match self.advance() {
+            Some(RawToken::Not) | Some(RawToken::Minus) => {
+                Ok(QueryExpr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(RawToken::Plus) => self.parse_unary(),
+            Some(RawToken::Word(word)) => Ok(QueryExpr::Term(word.clone())),
+            Some(RawToken::Phrase(phrase)) => Ok(QueryExpr::Term(phrase.clone())),
+            Some(RawToken::And) => Err("expected a term before `AND`".to_string()),
+            Some(RawToken::Or) => Err("expected a term before `OR`".to_string()),
+            None => Err("expected a term but the query ended".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_plain_query_with_no_boolean_syntax_is_left_untouched() {
+        assert_eq!(parse_boolean_query("serde derive"), None);
+    }
+
+    #[test]
+    fn and_requires_every_term_to_match() {
+        let expr = parse_boolean_query("serde AND derive").unwrap().unwrap();
+        assert!(expr.matches("use serde::Deserialize with derive"));
+        assert!(!expr.matches("use serde::Deserialize"));
+    }
+
+    #[test]
+    fn implicit_and_between_adjacent_terms_matches_like_explicit_and() {
+        let expr = parse_boolean_query("serde derive NOT test").unwrap().unwrap();
+        assert!(expr.matches("serde derive macro"));
+        assert!(!expr.matches("serde derive test macro"));
+        assert!(!expr.matches("derive macro"));
+    }
+
+    #[test]
+    fn or_matches_if_either_side_matches() {
+        let expr = parse_boolean_query("serde OR thiserror").unwrap().unwrap();
+        assert!(expr.matches("use thiserror::Error"));
+        assert!(expr.matches("use serde::Serialize"));
+        assert!(!expr.matches("use regex::Regex"));
+    }
+
+    #[test]
+    fn minus_prefix_negates_like_not() {
+        let expr = parse_boolean_query("serde -test").unwrap().unwrap();
+        assert!(expr.matches("use serde::Serialize"));
+        assert!(!expr.matches("serde test helper"));
+    }
+
+    #[test]
+    fn quoted_phrases_are_matched_as_one_term() {
+        let expr = parse_boolean_query(r#""fn handle_open""#).unwrap().unwrap();
+        assert!(expr.matches("pub fn handle_open(id: &str) {}"));
+        assert!(!expr.matches("fn other_handle and open() {}"));
+    }
+
+    #[test]
+    fn an_unterminated_quote_is_malformed() {
+        let err = parse_boolean_query(r#"serde "derive"#).unwrap().unwrap_err();
+        assert!(err.contains("unterminated quote"));
+    }
+
+    #[test]
+    fn a_dangling_operator_is_malformed() {
+        assert!(parse_boolean_query("serde AND").unwrap().is_err());
+        assert!(parse_boolean_query("OR serde").unwrap().is_err());
+        assert!(parse_boolean_query("serde NOT").unwrap().is_err());
+    }
+}
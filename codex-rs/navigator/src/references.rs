@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use codex_vector_store::CodeChunk;
+
+/// A single call site: `caller_id` contains a textual reference to
+/// `callee_id` on the given source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEdge {
+    pub caller_id: String,
+    pub callee_id: String,
+    pub line: usize,
+    pub preview: String,
+}
+
+/// Caller/callee graph built by scanning indexed chunks for textual
+/// occurrences of other chunks' `symbol_name`s.
+///
+/// This is a heuristic, not a real call-graph analysis: it has no type or
+/// scope information, so it can both miss genuine calls (e.g. through a
+/// trait object) and report false positives (e.g. a symbol name mentioned in
+/// a comment). That's the same tradeoff `codex_code_chunker::ast_analyzer`
+/// makes for chunk classification, for the same reason: this crate has no
+/// parser dependency.
+pub struct ReferenceGraph {
+    outgoing: HashMap<String, Vec<CallEdge>>,
+    incoming: HashMap<String, Vec<CallEdge>>,
+}
+
+impl ReferenceGraph {
+    pub fn build(chunks: &[CodeChunk]) -> Self {
+        let definitions: Vec<(&str, String)> = chunks
+            .iter()
+            .filter_map(|chunk| chunk.symbol_name.as_deref().map(|name| (name, chunk_id(chunk))))
+            .collect();
+
+        let mut outgoing: HashMap<String, Vec<CallEdge>> = HashMap::new();
+        let mut incoming: HashMap<String, Vec<CallEdge>> = HashMap::new();
+
+        for chunk in chunks {
+            let caller_id = chunk_id(chunk);
+            for (offset, line) in chunk.content.lines().enumerate() {
+                let absolute_line = chunk.start_line + offset;
+                for (name, callee_id) in &definitions {
+                    let is_own_declaration_line =
+                        *callee_id == caller_id && absolute_line == chunk.start_line;
+                    if is_own_declaration_line || !line.contains(&format!("{name}(")) {
+                        continue;
+                    }
+                    let edge = CallEdge {
+                        caller_id: caller_id.clone(),
+                        callee_id: callee_id.clone(),
+                        line: absolute_line,
+                        preview: line.trim().to_string(),
+                    };
+                    outgoing.entry(caller_id.clone()).or_default().push(edge.clone());
+                    incoming.entry(callee_id.clone()).or_default().push(edge);
+                }
+            }
+        }
+
+        Self { outgoing, incoming }
+    }
+
+    /// Call sites where `id` is the callee, i.e. "who calls this".
+    pub fn callers_of(&self, id: &str) -> &[CallEdge] {
+        self.incoming.get(id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Call sites made from within `id`, i.e. "what this calls".
+    pub fn callees_of(&self, id: &str) -> &[CallEdge] {
+        self.outgoing.get(id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Every call-site edge in the graph. Each [`CallEdge`] is stored twice
+    /// internally (once indexed by caller, once by callee, for
+    /// [`Self::callers_of`]/[`Self::callees_of`] to look up directly), but
+    /// only `outgoing`'s copy is iterated here, so a caller wanting every
+    /// edge exactly once (e.g. [`crate::index_export`]) doesn't have to
+    /// dedupe it itself.
+    pub fn all_edges(&self) -> impl Iterator<Item = &CallEdge> {
+        self.outgoing.values().flatten()
+    }
+}
+
+/// The addressable id of an indexed chunk: its path and starting line.
+pub fn chunk_id(chunk: &CodeChunk) -> String {
+    format!("{}#{}", chunk.path, chunk.start_line)
+}
+
+/// A single textual occurrence of a symbol's identifier, found by
+/// [`find_references`]. Looser than a [`CallEdge`]: it matches any
+/// whole-word mention of the identifier, not just `name(` call syntax, so
+/// it also catches references through a trait object, a type annotation,
+/// or a doc comment - at the cost of the same false positives that
+/// looseness buys back (e.g. a symbol name mentioned only in a comment).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceLocation {
+    pub path: String,
+    pub line: usize,
+    pub preview: String,
+}
+
+/// Approximate caller locations for `chunk`'s own symbol, by scanning every
+/// chunk's lines for a whole-word, case-sensitive mention of
+/// [`CodeChunk::symbol_name`] - see [`ReferenceGraph`]'s doc comment for why
+/// this is a heuristic, not a real call-graph analysis. Looser than
+/// [`ReferenceGraph::build`]'s `name(` call-syntax matching: any whole-word
+/// mention counts, not just call syntax. `chunk`'s own declaration line is
+/// excluded. Returns at most `limit` locations, in chunk order.
+pub fn find_references(
+    chunks: &[CodeChunk],
+    chunk: &CodeChunk,
+    limit: usize,
+) -> Vec<ReferenceLocation> {
+    let Some(symbol_name) = chunk.symbol_name.as_deref() else {
+        return Vec::new();
+    };
+    let own_id = chunk_id(chunk);
+
+    let mut locations = Vec::new();
+    for candidate in chunks {
+        let candidate_id = chunk_id(candidate);
+        for (offset, line) in candidate.content.lines().enumerate() {
+            if locations.len() >= limit {
+                return locations;
+            }
+            let absolute_line = candidate.start_line + offset;
+            let is_own_declaration_line =
+                candidate_id == own_id && absolute_line == chunk.start_line;
+            let is_match = crate::search::term_matches(line, symbol_name, true, true);
+            if is_own_declaration_line || !is_match {
+                continue;
+            }
+            locations.push(ReferenceLocation {
+                path: candidate.path.clone(),
+                line: absolute_line,
+                preview: line.trim().to_string(),
+            });
+        }
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start_line: usize, content: &str, symbol_name: Option<&str>) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line,
+            end_line: start_line + content.lines().count().saturating_sub(1),
+            content: content.to_string(),
+            language: Some("rust".to_string()),
+            chunk_type: None,
+            symbol_name: symbol_name.map(str::to_string),
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    #[test]
+    fn finds_callers_and_callees_across_chunks() {
+        let callee = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let caller = chunk(
+            "lib.rs",
+            3,
+            "fn run() {\n    helper();\n}",
+            Some("run"),
+        );
+        let graph = ReferenceGraph::build(&[callee.clone(), caller.clone()]);
+
+        let callers = graph.callers_of(&chunk_id(&callee));
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].caller_id, chunk_id(&caller));
+        assert_eq!(callers[0].line, 4);
+
+        let callees = graph.callees_of(&chunk_id(&caller));
+        assert_eq!(callees.len(), 1);
+        assert_eq!(callees[0].callee_id, chunk_id(&callee));
+    }
+
+    #[test]
+    fn all_edges_reports_each_call_site_exactly_once() {
+        let callee = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let caller = chunk("lib.rs", 3, "fn run() {\n    helper();\n}", Some("run"));
+        let graph = ReferenceGraph::build(&[callee, caller]);
+
+        let edges: Vec<_> = graph.all_edges().collect();
+        assert_eq!(edges.len(), 1);
+    }
+
+    #[test]
+    fn a_definitions_own_signature_line_is_not_a_self_call() {
+        let callee = chunk("lib.rs", 1, "fn helper() {}", Some("helper"));
+        let graph = ReferenceGraph::build(&[callee.clone()]);
+
+        assert!(graph.callers_of(&chunk_id(&callee)).is_empty());
+        assert!(graph.callees_of(&chunk_id(&callee)).is_empty());
+    }
+
+    #[test]
+    fn find_references_reports_mentions_across_two_files_but_not_the_declaration() {
+        let helper = chunk("helper.rs", 1, "fn helper() {}", Some("helper"));
+        let a = chunk("a.rs", 1, "fn run_a() {\n    helper();\n}", Some("run_a"));
+        let b = chunk(
+            "b.rs",
+            1,
+            "fn run_b() {\n    let f: fn() = helper;\n}",
+            Some("run_b"),
+        );
+        let chunks = [helper.clone(), a, b];
+
+        let locations = find_references(&chunks, &helper, 10);
+
+        assert_eq!(locations.len(), 2);
+        assert!(locations.iter().any(|loc| loc.path == "a.rs" && loc.line == 2));
+        assert!(locations.iter().any(|loc| loc.path == "b.rs" && loc.line == 2));
+    }
+
+    #[test]
+    fn find_references_respects_the_limit() {
+        let helper = chunk("helper.rs", 1, "fn helper() {}", Some("helper"));
+        let a = chunk("a.rs", 1, "fn run_a() {\n    helper();\n}", Some("run_a"));
+        let b = chunk("b.rs", 1, "fn run_b() {\n    helper();\n}", Some("run_b"));
+
+        let locations = find_references(&[helper.clone(), a, b], &helper, 1);
+
+        assert_eq!(locations.len(), 1);
+    }
+}
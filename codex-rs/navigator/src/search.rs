@@ -0,0 +1,496 @@
+use nucleo::Config;
+use nucleo::Matcher;
+use nucleo::Utf32Str;
+use nucleo::pattern::AtomKind;
+use nucleo::pattern::CaseMatching;
+use nucleo::pattern::Normalization;
+use nucleo::pattern::Pattern;
+use regex::Regex;
+
+use codex_vector_store::CodeChunk;
+
+use crate::cursor::SearchCursor;
+use crate::proto::ErrorPayload;
+use crate::proto::NavHit;
+use crate::proto::NavigatorError;
+use crate::proto::SearchMode;
+use crate::proto::SearchResponse;
+use crate::proto::SearchStats;
+use crate::query;
+use crate::references::chunk_id;
+
+/// Scans every chunk's content for `pattern`, compiled as a regular
+/// expression.
+///
+/// This crate has no trigram (or other) file-level index to pre-filter
+/// candidate chunks before the scan, so every indexed chunk is scanned;
+/// [`SearchStats::chunks_scanned`] reports that count so a caller can see
+/// the cost.
+pub fn run_regex_search(
+    chunks: &[CodeChunk],
+    pattern: &str,
+    limit: usize,
+    cursor: Option<&str>,
+) -> Result<SearchResponse, ErrorPayload> {
+    let regex = Regex::new(pattern).map_err(|source| {
+        ErrorPayload::from(NavigatorError::InvalidQuery {
+            message: format!("invalid regex `{pattern}`: {source}"),
+        })
+    })?;
+
+    scan(chunks, limit, cursor, SearchMode::Regex, pattern, |line| {
+        regex.is_match(line)
+    })
+}
+
+/// Plain, case-insensitive substring match against chunk content - the
+/// fallback when [`crate::SearchRequest::query_regex`] isn't set. If `query`
+/// contains `AND`/`OR`/`NOT`/`+`/`-` syntax or a quoted phrase (see
+/// [`crate::query::parse_boolean_query`]), it's evaluated as a boolean
+/// expression instead; a malformed one falls back to this same plain
+/// substring match with a hint appended to [`SearchResponse::hints`]
+/// explaining why.
+///
+/// When `fuzzy_fallback` is set and this exact match (substring or boolean)
+/// comes up short of `limit` on the first page (`cursor` is `None`), the
+/// remainder is filled with nucleo fuzzy-scored lines - see
+/// [`fuzzy_fallback_hits`]. Those hits are appended after the exact ones and
+/// have [`NavHit::is_fuzzy_match`] set.
+///
+/// `case_sensitive` and `whole_word` (see [`crate::SearchRequest`]) apply to
+/// the exact match only - fuzzy fallback scoring ignores both, since nucleo
+/// has no notion of either.
+#[allow(clippy::too_many_arguments)]
+pub fn run_fuzzy_search(
+    chunks: &[CodeChunk],
+    raw_query: &str,
+    limit: usize,
+    cursor: Option<&str>,
+    fuzzy_fallback: bool,
+    case_sensitive: bool,
+    whole_word: bool,
+) -> Result<SearchResponse, ErrorPayload> {
+    let mut response = match query::parse_boolean_query(raw_query) {
+        None => {
+            plain_substring_search(chunks, raw_query, limit, cursor, case_sensitive, whole_word)?
+        }
+        Some(Ok(expr)) => scan(chunks, limit, cursor, SearchMode::Boolean, raw_query, |line| {
+            expr.matches_with(line, case_sensitive, whole_word)
+        })?,
+        Some(Err(message)) => {
+            let mut response = plain_substring_search(
+                chunks,
+                raw_query,
+                limit,
+                cursor,
+                case_sensitive,
+                whole_word,
+            )?;
+            response.hints.push(format!("boolean query syntax ignored: {message}"));
+            response
+        }
+    };
+
+    if fuzzy_fallback && cursor.is_none() && response.hits.len() < limit {
+        let matcher = TermMatcher::new(raw_query, case_sensitive, whole_word);
+        let extra = fuzzy_fallback_hits(
+            chunks,
+            raw_query,
+            limit - response.hits.len(),
+            |line| matcher.is_match(line),
+        );
+        if !extra.is_empty() {
+            response.hints.push(format!(
+                "{} fuzzy fallback hit(s) appended below exact matches",
+                extra.len()
+            ));
+            response.hits.extend(extra);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Scores every line not already caught by `already_exact_match` against
+/// `raw_query` with nucleo and returns the `limit` highest-scoring ones as
+/// [`NavHit`]s with [`NavHit::is_fuzzy_match`] set - the typo-tolerant
+/// fallback [`run_fuzzy_search`] appends once exact matching comes up short.
+fn fuzzy_fallback_hits(
+    chunks: &[CodeChunk],
+    raw_query: &str,
+    limit: usize,
+    already_exact_match: impl Fn(&str) -> bool,
+) -> Vec<NavHit> {
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let pattern =
+        Pattern::new(raw_query, CaseMatching::Ignore, Normalization::Smart, AtomKind::Fuzzy);
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut scored: Vec<(u32, NavHit)> = Vec::new();
+
+    for chunk in chunks {
+        for (line_offset, line) in chunk.content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || already_exact_match(line) {
+                continue;
+            }
+            let mut utf32buf = Vec::new();
+            let haystack = Utf32Str::new(trimmed, &mut utf32buf);
+            let Some(score) = pattern.score(haystack, &mut matcher) else {
+                continue;
+            };
+            scored.push((
+                score,
+                NavHit {
+                    id: chunk_id(chunk),
+                    path: chunk.path.clone(),
+                    line: chunk.start_line + line_offset,
+                    preview: trimmed.to_string(),
+                    workspace: None,
+                    is_fuzzy_match: true,
+                    references: None,
+                },
+            ));
+        }
+    }
+
+    scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+    scored.into_iter().take(limit).map(|(_, hit)| hit).collect()
+}
+
+/// Narrows `chunks` to declaration sites only, for
+/// [`crate::SearchRequest::definitions_only`]: chunks with no
+/// [`CodeChunk::symbol_name`] aren't declarations of anything and are
+/// dropped, and the rest are cloned with `content` truncated to their own
+/// first line, so a subsequent [`run_fuzzy_search`]/[`run_regex_search`]
+/// scan can only match that declaration line - never a call site buried
+/// further down in the same chunk's body, nor a mention inside another
+/// chunk that merely calls into this one.
+pub(crate) fn definition_candidates(chunks: &[CodeChunk]) -> Vec<CodeChunk> {
+    chunks
+        .iter()
+        .filter(|chunk| chunk.symbol_name.is_some())
+        .map(|chunk| {
+            let declaration_line = chunk.content.lines().next().unwrap_or_default().to_string();
+            CodeChunk {
+                end_line: chunk.start_line,
+                content: declaration_line,
+                ..chunk.clone()
+            }
+        })
+        .collect()
+}
+
+fn plain_substring_search(
+    chunks: &[CodeChunk],
+    query: &str,
+    limit: usize,
+    cursor: Option<&str>,
+    case_sensitive: bool,
+    whole_word: bool,
+) -> Result<SearchResponse, ErrorPayload> {
+    let matcher = TermMatcher::new(query, case_sensitive, whole_word);
+    scan(chunks, limit, cursor, SearchMode::Fuzzy, query, |line| matcher.is_match(line))
+}
+
+/// The substring-matching rule shared by [`plain_substring_search`],
+/// [`QueryExpr::matches_with`](crate::query::QueryExpr::matches_with)'s
+/// `Term` case, and the fuzzy fallback's exact-match check. Defaults
+/// (`case_sensitive: false, whole_word: false`) are a plain
+/// `to_lowercase().contains()`, matching this crate's long-standing
+/// behavior; `whole_word` requires `\b` boundaries around `needle`, via a
+/// regex. Callers that check many lines against the same `needle` should
+/// use [`TermMatcher`] instead, which compiles that regex once rather than
+/// on every call.
+pub(crate) fn term_matches(
+    line: &str,
+    needle: &str,
+    case_sensitive: bool,
+    whole_word: bool,
+) -> bool {
+    TermMatcher::new(needle, case_sensitive, whole_word).is_match(line)
+}
+
+/// A [`term_matches`] check pre-bound to one `needle`/`case_sensitive`/
+/// `whole_word` combination, so scanning every line of every chunk against
+/// it (see [`plain_substring_search`], [`run_fuzzy_search`]'s fuzzy
+/// fallback) compiles the whole-word regex once up front instead of once
+/// per line.
+pub(crate) struct TermMatcher<'a> {
+    needle: &'a str,
+    case_sensitive: bool,
+    /// `Some` only when `whole_word` was requested; an inner `None` means
+    /// the regex failed to compile, matching [`term_matches`]'s fail-closed
+    /// behavior of never matching rather than falling back to a substring
+    /// check.
+    whole_word: Option<Option<Regex>>,
+}
+
+impl<'a> TermMatcher<'a> {
+    pub(crate) fn new(needle: &'a str, case_sensitive: bool, whole_word: bool) -> Self {
+        Self {
+            needle,
+            case_sensitive,
+            whole_word: whole_word.then(|| {
+                let pattern = format!(r"\b{}\b", regex::escape(needle));
+                if case_sensitive {
+                    Regex::new(&pattern)
+                } else {
+                    Regex::new(&format!("(?i){pattern}"))
+                }
+                .ok()
+            }),
+        }
+    }
+
+    pub(crate) fn is_match(&self, line: &str) -> bool {
+        if let Some(regex) = &self.whole_word {
+            return regex.as_ref().map(|re| re.is_match(line)).unwrap_or(false);
+        }
+
+        if self.case_sensitive {
+            line.contains(self.needle)
+        } else {
+            line.to_lowercase().contains(&self.needle.to_lowercase())
+        }
+    }
+}
+
+/// Scans every chunk's content for lines matching `matches`, returning up to
+/// `limit` hits starting after `cursor`'s offset (from the start, if `cursor`
+/// is `None`); see [`crate::cursor`] for why resuming re-scans instead of
+/// replaying a cached candidate list.
+fn scan(
+    chunks: &[CodeChunk],
+    limit: usize,
+    cursor: Option<&str>,
+    mode: SearchMode,
+    query_key: &str,
+    mut matches: impl FnMut(&str) -> bool,
+) -> Result<SearchResponse, ErrorPayload> {
+    let seed = (mode, query_key, chunks.len());
+    let offset = match cursor {
+        Some(cursor) => SearchCursor::decode(cursor, seed).map_err(ErrorPayload::from)?.offset,
+        None => 0,
+    };
+
+    let mut hits = Vec::new();
+    let mut matched = 0usize;
+    for chunk in chunks {
+        for (line_offset, line) in chunk.content.lines().enumerate() {
+            if matches(line) {
+                if matched >= offset && hits.len() < limit {
+                    hits.push(NavHit {
+                        id: chunk_id(chunk),
+                        path: chunk.path.clone(),
+                        line: chunk.start_line + line_offset,
+                        preview: line.trim().to_string(),
+                        // `search` is decoupled from workspace concepts -
+                        // `IndexCoordinator::run_search` fills this in.
+                        workspace: None,
+                        is_fuzzy_match: false,
+                        references: None,
+                    });
+                }
+                matched += 1;
+            }
+        }
+    }
+
+    let next_cursor =
+        (matched > offset + hits.len()).then(|| SearchCursor::new(offset + hits.len(), seed).encode());
+
+    Ok(SearchResponse {
+        hits,
+        stats: SearchStats {
+            mode,
+            chunks_scanned: chunks.len(),
+        },
+        next_cursor,
+        hints: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start_line: usize, content: &str) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line,
+            end_line: start_line + content.lines().count().saturating_sub(1),
+            content: content.to_string(),
+            language: Some("rust".to_string()),
+            chunk_type: None,
+            symbol_name: None,
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    #[test]
+    fn regex_search_matches_across_chunks_and_reports_regex_mode() {
+        let chunks = vec![
+            chunk("a.rs", 1, "fn handle_open_event() {}"),
+            chunk("b.rs", 1, "fn other() {}"),
+        ];
+
+        let response = run_regex_search(&chunks, r"fn\s+handle_\w+_event", 10, None).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].path, "a.rs");
+        assert_eq!(response.stats.mode, SearchMode::Regex);
+        assert_eq!(response.stats.chunks_scanned, 2);
+        assert_eq!(response.next_cursor, None);
+    }
+
+    #[test]
+    fn an_invalid_regex_is_a_navigator_error_not_a_panic() {
+        let err = run_regex_search(&[], "(unclosed", 10, None).unwrap_err();
+        assert_eq!(err.code, crate::proto::ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn fuzzy_search_is_case_insensitive_and_reports_fuzzy_mode() {
+        let chunks = vec![chunk("a.rs", 1, "fn HandleOpen() {}")];
+
+        let response =
+            run_fuzzy_search(&chunks, "handleopen", 10, None, false, false, false).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.stats.mode, SearchMode::Fuzzy);
+    }
+
+    #[test]
+    fn results_are_truncated_at_the_requested_limit_with_a_cursor_for_the_rest() {
+        let chunks = vec![chunk("a.rs", 1, "match\nmatch\nmatch")];
+
+        let response = run_fuzzy_search(&chunks, "match", 2, None, false, false, false).unwrap();
+
+        assert_eq!(response.hits.len(), 2);
+        assert!(response.next_cursor.is_some());
+    }
+
+    #[test]
+    fn a_cursor_resumes_a_search_after_the_first_page() {
+        let chunks = vec![chunk("a.rs", 1, "match\nmatch\nmatch")];
+
+        let first_page = run_fuzzy_search(&chunks, "match", 2, None, false, false, false).unwrap();
+        let second_page = run_fuzzy_search(
+            &chunks,
+            "match",
+            2,
+            first_page.next_cursor.as_deref(),
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(second_page.hits.len(), 1);
+        assert_eq!(second_page.hits[0].line, 3);
+        assert_eq!(second_page.next_cursor, None);
+    }
+
+    #[test]
+    fn a_cursor_from_a_different_query_is_rejected_as_stale() {
+        let chunks = vec![chunk("a.rs", 1, "match\nmatch\nmatch")];
+
+        let first_page = run_fuzzy_search(&chunks, "match", 2, None, false, false, false).unwrap();
+        let err = run_fuzzy_search(
+            &chunks,
+            "other",
+            2,
+            first_page.next_cursor.as_deref(),
+            false,
+            false,
+            false,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.code, crate::proto::ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn fuzzy_fallback_finds_a_misspelled_symbol_exact_matching_misses() {
+        let chunks = vec![chunk("a.rs", 1, "fn handle_search(query: &str) {}")];
+
+        let exact =
+            run_fuzzy_search(&chunks, "handl_serch", 10, None, false, false, false).unwrap();
+        assert_eq!(exact.hits.len(), 0);
+
+        let response =
+            run_fuzzy_search(&chunks, "handl_serch", 10, None, true, false, false).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert!(response.hits[0].preview.contains("handle_search"));
+        assert!(response.hits[0].is_fuzzy_match);
+        assert_eq!(response.hints.len(), 1);
+    }
+
+    #[test]
+    fn fuzzy_fallback_is_skipped_once_exact_matches_already_fill_the_limit() {
+        let chunks = vec![chunk("a.rs", 1, "fn handle_search() {}\nfn other() {}")];
+
+        let response =
+            run_fuzzy_search(&chunks, "handle_search", 1, None, true, false, false).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert!(!response.hits[0].is_fuzzy_match);
+        assert!(response.hints.is_empty());
+    }
+
+    #[test]
+    fn case_sensitive_search_rejects_a_different_casing() {
+        let chunks = vec![chunk("a.rs", 1, "let New = 1;\nlet new = 2;\nlet renew = 3;")];
+
+        let response = run_fuzzy_search(&chunks, "New", 10, None, false, true, false).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].line, 1);
+    }
+
+    #[test]
+    fn whole_word_search_excludes_a_substring_match() {
+        let chunks = vec![chunk("a.rs", 1, "let new = 2;\nlet renew = 3;")];
+
+        let response = run_fuzzy_search(&chunks, "new", 10, None, false, false, true).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].line, 1);
+    }
+
+    #[test]
+    fn definition_candidates_drops_chunks_with_no_symbol_name_and_truncates_to_the_first_line() {
+        let mut declaration_with_symbol = chunk("a.rs", 5, "fn helper() {\n    1 + 1\n}");
+        declaration_with_symbol.symbol_name = Some("helper".to_string());
+        let prose = chunk("a.rs", 1, "// just a comment, no symbol here");
+
+        let candidates = definition_candidates(&[declaration_with_symbol.clone(), prose]);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].content, "fn helper() {");
+        assert_eq!(candidates[0].start_line, declaration_with_symbol.start_line);
+        assert_eq!(candidates[0].end_line, declaration_with_symbol.start_line);
+    }
+
+    #[test]
+    fn case_sensitive_and_whole_word_compose() {
+        let chunks = vec![chunk(
+            "a.rs",
+            1,
+            "let New = 1;\nlet new = 2;\nlet renew = 3;\nlet Renewed = 4;",
+        )];
+
+        let response = run_fuzzy_search(&chunks, "New", 10, None, false, true, true).unwrap();
+
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].line, 1);
+    }
+}
@@ -3087,6 +3087,42 @@ pub struct ExecCommandEndEvent {
     pub formatted_output: String,
     /// Completion status for this command execution.
     pub status: ExecCommandStatus,
+    /// Cheap counters over this event's own `stdout`/`stderr`/`duration`,
+    /// computed once here so a UI doesn't have to re-derive them from the
+    /// full output text. `None` for an event replayed from a rollout
+    /// recorded before this field existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub exit_summary: Option<ExecExitSummary>,
+}
+
+/// Byte/line counters over one [`ExecCommandEndEvent`], derived from its
+/// `stdout`/`stderr`/`aggregated_output`/`duration` fields - there's no
+/// separate counter state accumulated incrementally as output streams in,
+/// so [`Self::from_event_fields`] is always computed after the fact from the
+/// same strings the event already carries.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ExecExitSummary {
+    pub duration_ms: u64,
+    pub stdout_bytes: usize,
+    pub stderr_bytes: usize,
+    pub output_lines: usize,
+}
+
+impl ExecExitSummary {
+    pub fn from_event_fields(
+        duration: Duration,
+        stdout: &str,
+        stderr: &str,
+        aggregated_output: &str,
+    ) -> Self {
+        Self {
+            duration_ms: duration.as_millis() as u64,
+            stdout_bytes: stdout.len(),
+            stderr_bytes: stderr.len(),
+            output_lines: aggregated_output.lines().count(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -988,6 +988,7 @@ fn command_execution_completed_event(turn_id: &str, item: &ThreadItem) -> Option
             duration,
             formatted_output: aggregated_output,
             status,
+            exit_summary: None,
         }),
     }])
 }
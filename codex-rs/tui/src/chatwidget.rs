@@ -5841,6 +5841,7 @@ impl ChatWidget {
                                 codex_protocol::protocol::ExecCommandStatus::Failed
                             }
                         },
+                        exit_summary: None,
                     });
                 }
             }
@@ -374,6 +374,7 @@ async fn exec_end_without_begin_uses_event_command() {
             duration: std::time::Duration::from_millis(5),
             formatted_output: "done".to_string(),
             status: CoreExecCommandStatus::Completed,
+            exit_summary: None,
         }),
     });
 
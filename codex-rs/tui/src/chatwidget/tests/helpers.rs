@@ -655,6 +655,7 @@ pub(super) fn end_exec(
             } else {
                 CoreExecCommandStatus::Failed
             },
+            exit_summary: None,
         }),
     });
 }
@@ -2063,6 +2063,7 @@ async fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
             status: CoreExecCommandStatus::Completed,
+            exit_summary: None,
         }),
     });
     chat.handle_codex_event(Event {
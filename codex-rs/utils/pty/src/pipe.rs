@@ -235,6 +235,7 @@ async fn spawn_process_with_stdin_mode(
         exit_status,
         exit_code,
         /*pty_handles*/ None,
+        Some(pid),
     );
 
     Ok(SpawnedProcess {
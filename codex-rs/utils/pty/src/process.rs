@@ -82,6 +82,7 @@ pub struct ProcessHandle {
     // PtyHandles must be preserved because the process will receive Control+C if the
     // slave is closed
     _pty_handles: StdMutex<Option<PtyHandles>>,
+    pid: Option<u32>,
 }
 
 impl fmt::Debug for ProcessHandle {
@@ -102,6 +103,7 @@ impl ProcessHandle {
         exit_status: Arc<AtomicBool>,
         exit_code: Arc<StdMutex<Option<i32>>>,
         pty_handles: Option<PtyHandles>,
+        pid: Option<u32>,
     ) -> Self {
         Self {
             writer_tx: StdMutex::new(Some(writer_tx)),
@@ -113,9 +115,17 @@ impl ProcessHandle {
             exit_status,
             exit_code,
             _pty_handles: StdMutex::new(pty_handles),
+            pid,
         }
     }
 
+    /// The OS process id of the spawned child, if the spawn backend captured
+    /// one. `None` on platforms/paths where it wasn't available (e.g. the
+    /// portable PTY backend doesn't track it on Windows).
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
     /// Returns a channel sender for writing raw bytes to the child stdin.
     pub fn writer_sender(&self) -> mpsc::Sender<Vec<u8>> {
         if let Ok(writer_tx) = self.writer_tx.lock()
@@ -164,6 +164,10 @@ async fn spawn_process_portable(
     // Unix, so PID == PGID and we can reuse the pipe backend's process-group
     // hard-kill semantics for descendants.
     let process_group_id = child.process_id();
+    #[cfg(unix)]
+    let pid = process_group_id;
+    #[cfg(not(unix))]
+    let pid = None;
     let killer = child.clone_killer();
 
     let (writer_tx, mut writer_rx) = mpsc::channel::<Vec<u8>>(128);
@@ -242,6 +246,7 @@ async fn spawn_process_portable(
         exit_status,
         exit_code,
         Some(handles),
+        pid,
     );
 
     Ok(SpawnedProcess {
@@ -395,6 +400,7 @@ async fn spawn_process_preserving_fds(
         exit_status,
         exit_code,
         Some(handles),
+        Some(process_group_id),
     );
 
     Ok(SpawnedProcess {
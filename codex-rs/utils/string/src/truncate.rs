@@ -123,6 +123,16 @@ fn split_string(s: &str, beginning_bytes: usize, end_bytes: usize) -> (usize, &s
     (removed_chars, before, after)
 }
 
+/// There's no `OutputLimits`/per-tool-config plumbing in this crate to make
+/// this ratio configurable: `TruncationPolicy` (in `codex_protocol`) carries
+/// only a `mode`/`limit` pair over the wire, and `ToolRegistry` has no
+/// per-tool truncation override to source a different ratio from - every
+/// caller of [`truncate_middle_chars`]/[`truncate_middle_with_token_budget`]
+/// gets this fixed 50/50 head/tail split. A test command whose interesting
+/// failures sit in the middle and get cut from both sides can't currently
+/// ask for more tail; the closest available lever today is a larger
+/// `TruncationPolicy::Tokens`/`Bytes` limit (raising both halves equally),
+/// not a different split.
 fn split_budget(budget: usize) -> (usize, usize) {
     let left = budget / 2;
     (left, budget - left)
@@ -0,0 +1,81 @@
+//! Approximate, offline token counting for budgeting prompt context.
+//!
+//! Loading a real model vocabulary (e.g. `cl100k_base`) would pull in a
+//! multi-megabyte BPE rank table and, for some tokenizer crates, a network
+//! fetch on first use. Instead this crate pre-tokenizes text the same way a
+//! BPE tokenizer's pre-tokenization pass would (splitting on contractions,
+//! word/number runs, punctuation, and whitespace) and counts the resulting
+//! tokens. This under-counts relative to true BPE, which further splits
+//! words into subword pieces, but it is a substantial improvement over a
+//! `chars / 4` estimate and has no external dependencies.
+
+use regex_lite::Regex;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TokenizerError {
+    #[error("failed to compile tokenizer pattern")]
+    Pattern(#[source] regex_lite::Error),
+}
+
+/// Counts tokens in text without loading a model vocabulary.
+pub struct Tokenizer {
+    pattern: Regex,
+}
+
+impl Tokenizer {
+    pub fn new() -> Result<Self, TokenizerError> {
+        let pattern = Regex::new(
+            r"'s|'t|'re|'ve|'m|'ll|'d|[A-Za-z]+|[0-9]+|[^A-Za-z0-9\s]|\s+",
+        )
+        .map_err(TokenizerError::Pattern)?;
+        Ok(Self { pattern })
+    }
+
+    /// Splits `text` into token strings.
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.pattern
+            .find_iter(text)
+            .map(|m| m.as_str())
+            .filter(|token| !token.trim().is_empty())
+            .collect()
+    }
+
+    /// Number of tokens `text` would produce.
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.tokenize(text).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_words_numbers_and_punctuation_separately() {
+        let tokenizer = Tokenizer::new().unwrap();
+        let tokens = tokenizer.tokenize("fn parse_error(code: u32) -> bool {}");
+        assert!(tokens.contains(&"parse"));
+        assert!(tokens.contains(&"("));
+        assert!(tokens.contains(&"u"));
+        assert!(tokens.contains(&"32"));
+    }
+
+    #[test]
+    fn counts_contractions_as_single_tokens() {
+        let tokenizer = Tokenizer::new().unwrap();
+        assert_eq!(tokenizer.tokenize("don't"), vec!["don", "'t"]);
+    }
+
+    #[test]
+    fn whitespace_is_not_counted_as_a_token() {
+        let tokenizer = Tokenizer::new().unwrap();
+        assert_eq!(tokenizer.count_tokens("a   b"), 2);
+    }
+
+    #[test]
+    fn empty_text_has_no_tokens() {
+        let tokenizer = Tokenizer::new().unwrap();
+        assert_eq!(tokenizer.count_tokens(""), 0);
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A unit of source code stored alongside its embedding.
+///
+/// This is the vector-store's own mirror of the chunk type produced by
+/// `codex-code-chunker`; the two are kept independent so that this crate has
+/// no compile-time dependency on the chunking implementation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeChunk {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub language: Option<String>,
+    pub chunk_type: Option<String>,
+    pub symbol_name: Option<String>,
+    /// `sha256(content)`, hex-encoded, mirroring `ChunkMetadata::content_hash`.
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Version of the chunker that produced this chunk, mirroring
+    /// `ChunkMetadata::chunker_version`. Used by `VectorStore::find_stale`.
+    #[serde(default)]
+    pub chunker_version: Option<u32>,
+    /// The doc comment or docstring documenting this chunk, mirroring
+    /// `ChunkMetadata::doc_summary`.
+    #[serde(default)]
+    pub doc_summary: Option<String>,
+    /// The file's top-of-file import block, mirroring
+    /// `ChunkMetadata::context_imports`.
+    #[serde(default)]
+    pub context_imports: Option<String>,
+    /// Approximate token count of `content`, mirroring
+    /// `ChunkMetadata::estimated_tokens`.
+    #[serde(default)]
+    pub estimated_tokens: Option<usize>,
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+/// Distance metric used to score embeddings against a query vector.
+///
+/// `VectorStore::search` always reports scores on a "higher is better"
+/// scale, so [`DistanceMetric::L2`] distances are inverted before being
+/// returned as a [`crate::SearchResult::score`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    /// Cosine similarity. Recommended for normalized embeddings.
+    #[default]
+    Cosine,
+    /// Raw dot product. Useful for embeddings that already encode magnitude.
+    DotProduct,
+    /// Euclidean distance, inverted so that closer vectors score higher.
+    L2,
+}
+
+/// Configuration for opening or creating a [`crate::VectorStore`].
+#[derive(Debug, Clone)]
+pub struct VectorStoreConfig {
+    /// Path to the on-disk table backing this store.
+    pub table_path: PathBuf,
+    /// Dimension of the embeddings this store accepts.
+    pub embedding_dim: usize,
+    /// Distance metric used by `search`.
+    pub distance_metric: DistanceMetric,
+    /// Whether `search` computes [`crate::SearchResult::matched_line_range`]
+    /// for each hit. Disabled by default: it re-embeds several line windows
+    /// of every returned chunk, which is unnecessary cost for callers that
+    /// don't highlight matches.
+    pub compute_match_span: bool,
+    // No `auto_compact_fragments` knob here: this store persists as a single
+    // newline-delimited JSON file rewritten wholesale by every mutation (see
+    // `VectorStore::persist`), not as LanceDB-style fragments that accumulate
+    // between compactions, so there's no fragment count for a threshold to
+    // compare against.
+}
+
+impl VectorStoreConfig {
+    pub fn new(table_path: impl Into<PathBuf>, embedding_dim: usize) -> Self {
+        Self {
+            table_path: table_path.into(),
+            embedding_dim,
+            distance_metric: DistanceMetric::default(),
+            compute_match_span: false,
+        }
+    }
+
+    pub fn with_distance_metric(mut self, distance_metric: DistanceMetric) -> Self {
+        self.distance_metric = distance_metric;
+        self
+    }
+
+    pub fn with_compute_match_span(mut self, compute_match_span: bool) -> Self {
+        self.compute_match_span = compute_match_span;
+        self
+    }
+}
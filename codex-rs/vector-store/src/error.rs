@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::config::DistanceMetric;
+
+/// Errors returned while creating, writing to, or querying a [`crate::VectorStore`].
+#[derive(Debug, Error)]
+pub enum VectorStoreError {
+    #[error("failed to read or write vector store at {path:?}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to (de)serialize vector store row")]
+    Serialization(#[from] serde_json::Error),
+    #[error("embedding has dimension {actual}, but this store was created with dimension {expected}")]
+    DimensionMismatch { expected: usize, actual: usize },
+    #[error(
+        "cannot report distance for metric {requested:?}: this store was built with {configured:?}"
+    )]
+    UnsupportedMetric {
+        requested: DistanceMetric,
+        configured: DistanceMetric,
+    },
+}
@@ -0,0 +1,67 @@
+use std::ops::Range;
+
+use wildmatch::WildMatch;
+
+use crate::chunk::CodeChunk;
+
+/// Predicates applied to [`crate::VectorStore::search_filtered`] before the
+/// vector scan, so that `limit` hits are returned from the filtered set
+/// rather than being applied to an unfiltered top-k.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    pub language: Option<String>,
+    pub path_prefix: Option<String>,
+    pub path_glob: Option<String>,
+    pub chunk_type: Option<String>,
+    pub symbol_name_contains: Option<String>,
+    /// Keeps only chunks whose `[start_line, end_line]` overlaps this range.
+    pub line_range: Option<Range<usize>>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        self.language.is_none()
+            && self.path_prefix.is_none()
+            && self.path_glob.is_none()
+            && self.chunk_type.is_none()
+            && self.symbol_name_contains.is_none()
+            && self.line_range.is_none()
+    }
+
+    pub(crate) fn matches(&self, chunk: &CodeChunk) -> bool {
+        if let Some(language) = &self.language
+            && chunk.language.as_deref() != Some(language.as_str())
+        {
+            return false;
+        }
+        if let Some(prefix) = &self.path_prefix
+            && !chunk.path.starts_with(prefix.as_str())
+        {
+            return false;
+        }
+        if let Some(glob) = &self.path_glob
+            && !WildMatch::new(glob).matches(&chunk.path)
+        {
+            return false;
+        }
+        if let Some(chunk_type) = &self.chunk_type
+            && chunk.chunk_type.as_deref() != Some(chunk_type.as_str())
+        {
+            return false;
+        }
+        if let Some(substring) = &self.symbol_name_contains
+            && !chunk
+                .symbol_name
+                .as_deref()
+                .is_some_and(|name| name.contains(substring.as_str()))
+        {
+            return false;
+        }
+        if let Some(range) = &self.line_range
+            && (chunk.end_line < range.start || chunk.start_line >= range.end)
+        {
+            return false;
+        }
+        true
+    }
+}
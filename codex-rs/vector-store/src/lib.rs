@@ -0,0 +1,19 @@
+//! An embedded vector store for indexed code chunks, used by the codebase
+//! indexing and retrieval crates to persist and query embeddings.
+
+mod chunk;
+mod config;
+mod error;
+mod filter;
+mod result;
+mod store;
+
+pub use chunk::CodeChunk;
+pub use config::DistanceMetric;
+pub use config::VectorStoreConfig;
+pub use error::VectorStoreError;
+pub use filter::SearchFilter;
+pub use result::SearchOptions;
+pub use result::SearchResult;
+pub use store::VectorStore;
+pub use store::VectorStoreStats;
@@ -0,0 +1,51 @@
+use crate::chunk::CodeChunk;
+use crate::config::DistanceMetric;
+
+/// A single search hit.
+///
+/// `score` is always on a "higher is better" scale regardless of the
+/// store's configured [`crate::DistanceMetric`]: cosine similarity and dot
+/// product are used as-is, while L2 distance is inverted (`-distance`)
+/// before being reported here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub chunk: CodeChunk,
+    pub score: f32,
+    /// Best-effort `(start_line, end_line)` estimate (in the chunk's
+    /// original file line numbers) of which lines inside the chunk actually
+    /// matched the query. Only populated when
+    /// [`crate::VectorStoreConfig::compute_match_span`] is enabled, and
+    /// `None` for single-line chunks, where there's nothing to localize.
+    pub matched_line_range: Option<(usize, usize)>,
+    /// The raw distance between the query and this chunk's embedding, under
+    /// whatever metric [`SearchOptions::metric`] requested - unlike `score`,
+    /// this is never inverted, so a *lower* value is a closer match. `0.0`
+    /// for a hit produced by [`crate::VectorStore::search`] or
+    /// [`crate::VectorStore::search_filtered`], which don't compute it.
+    pub distance: f32,
+    /// This chunk's stored embedding, present only when
+    /// [`SearchOptions::include_embedding`] was set - cloning every
+    /// embedding in the result set isn't free, so callers that only need
+    /// `score`/`distance` don't pay for it.
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Extra output to compute alongside a search, for callers (e.g. downstream
+/// clustering or dedup) that need more than a ranked `score`. Passed to
+/// [`crate::VectorStore::search_with_options`]; the no-`SearchOptions`
+/// entry points ([`crate::VectorStore::search`],
+/// [`crate::VectorStore::search_filtered`]) never compute this extra output,
+/// so they keep their current behavior and performance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    /// Clone each hit's stored embedding into [`SearchResult::embedding`].
+    pub include_embedding: bool,
+    /// Metric to report [`SearchResult::distance`] under. Must match the
+    /// metric the store was opened with ([`crate::VectorStoreConfig::distance_metric`]),
+    /// since a linear scan only ever computes one metric per row; requesting
+    /// a different one returns [`crate::VectorStoreError::UnsupportedMetric`].
+    /// [`DistanceMetric::DotProduct`] is a valid value here too - it just has
+    /// no "closer is lower" interpretation to invert, so `distance` reports
+    /// the raw dot product instead.
+    pub metric: DistanceMetric,
+}
@@ -0,0 +1,966 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write as _;
+
+use codex_embeddings::EmbeddingConfig;
+use codex_embeddings::EmbeddingService;
+use futures::Stream;
+use futures::StreamExt as _;
+use futures::stream;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::chunk::CodeChunk;
+use crate::config::DistanceMetric;
+use crate::config::VectorStoreConfig;
+use crate::error::VectorStoreError;
+use crate::filter::SearchFilter;
+use crate::result::SearchOptions;
+use crate::result::SearchResult;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredRow {
+    chunk: CodeChunk,
+    embedding: Vec<f32>,
+}
+
+/// An embedded, file-backed vector store for indexed code chunks.
+///
+/// Rows are persisted as newline-delimited JSON under
+/// [`VectorStoreConfig::table_path`]. This is intentionally simple rather
+/// than backed by an external vector database: indexes in this crate's
+/// target size (a single repository) fit comfortably in memory, and a
+/// linear scan keeps the implementation dependency-free.
+pub struct VectorStore {
+    config: VectorStoreConfig,
+    rows: Vec<StoredRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SchemaMeta {
+    embedding_dim: usize,
+}
+
+/// Size and shape of the on-disk table backing a [`VectorStore`].
+///
+/// There's no LanceDB (or other external vector database) behind this
+/// store - see the type-level doc on [`VectorStore`] - so there's no
+/// fragment count or ANN index to report here: every row lives in one
+/// newline-delimited JSON file, and [`VectorStore::search`] is always a
+/// linear scan over `row_count` rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VectorStoreStats {
+    pub row_count: usize,
+    /// Size of `config.table_path` on disk, in bytes. `0` if the table
+    /// hasn't been persisted yet.
+    pub table_bytes: u64,
+}
+
+impl VectorStore {
+    /// Opens the store at `config.table_path`, creating it (and recording
+    /// its embedding dimension in schema metadata) if it doesn't exist.
+    ///
+    /// Reopening an existing store with a different `embedding_dim` than the
+    /// one it was created with returns [`VectorStoreError::DimensionMismatch`]
+    /// instead of silently scoring against garbage vectors.
+    pub fn open(config: VectorStoreConfig) -> Result<Self, VectorStoreError> {
+        let meta_path = Self::meta_path(&config.table_path);
+        if meta_path.exists() {
+            let meta = Self::load_meta(&meta_path)?;
+            if meta.embedding_dim != config.embedding_dim {
+                return Err(VectorStoreError::DimensionMismatch {
+                    expected: meta.embedding_dim,
+                    actual: config.embedding_dim,
+                });
+            }
+        }
+        let rows = if config.table_path.exists() {
+            Self::load_rows(&config.table_path)?
+        } else {
+            Vec::new()
+        };
+        let store = Self { config, rows };
+        if !meta_path.exists() {
+            store.write_meta(&meta_path)?;
+        }
+        Ok(store)
+    }
+
+    fn meta_path(table_path: &std::path::Path) -> std::path::PathBuf {
+        let mut path = table_path.as_os_str().to_os_string();
+        path.push(".meta.json");
+        std::path::PathBuf::from(path)
+    }
+
+    fn load_meta(meta_path: &std::path::Path) -> Result<SchemaMeta, VectorStoreError> {
+        let contents = fs::read_to_string(meta_path).map_err(|source| VectorStoreError::Io {
+            path: meta_path.to_path_buf(),
+            source,
+        })?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    fn write_meta(&self, meta_path: &std::path::Path) -> Result<(), VectorStoreError> {
+        if let Some(parent) = meta_path.parent() {
+            fs::create_dir_all(parent).map_err(|source| VectorStoreError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let meta = SchemaMeta {
+            embedding_dim: self.config.embedding_dim,
+        };
+        fs::write(meta_path, serde_json::to_string(&meta)?).map_err(|source| VectorStoreError::Io {
+            path: meta_path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn load_rows(path: &std::path::Path) -> Result<Vec<StoredRow>, VectorStoreError> {
+        let file = fs::File::open(path).map_err(|source| VectorStoreError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let line = line.map_err(|source| VectorStoreError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+                Ok(serde_json::from_str(&line)?)
+            })
+            .collect()
+    }
+
+    fn persist(&self) -> Result<(), VectorStoreError> {
+        if let Some(parent) = self.config.table_path.parent() {
+            fs::create_dir_all(parent).map_err(|source| VectorStoreError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.config.table_path)
+            .map_err(|source| VectorStoreError::Io {
+                path: self.config.table_path.clone(),
+                source,
+            })?;
+        for row in &self.rows {
+            let line = serde_json::to_string(row)?;
+            writeln!(file, "{line}").map_err(|source| VectorStoreError::Io {
+                path: self.config.table_path.clone(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Row count and on-disk size of `config.table_path`. See
+    /// [`VectorStoreStats`] for why there's no fragment count or ANN index
+    /// field to report alongside them.
+    pub fn stats(&self) -> Result<VectorStoreStats, VectorStoreError> {
+        let table_bytes = match fs::metadata(&self.config.table_path) {
+            Ok(meta) => meta.len(),
+            Err(source) if source.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(source) => {
+                return Err(VectorStoreError::Io {
+                    path: self.config.table_path.clone(),
+                    source,
+                });
+            }
+        };
+        Ok(VectorStoreStats {
+            row_count: self.rows.len(),
+            table_bytes,
+        })
+    }
+
+    /// Rewrites `config.table_path` from the current in-memory rows.
+    ///
+    /// [`Self::add_chunks`], [`Self::delete_by_path`], and
+    /// [`Self::upsert_chunks`] already call [`Self::persist`] (a full
+    /// truncate-and-rewrite) after every mutation, so there's no fragment
+    /// accumulation for this to compact and no ANN index to rebuild - this
+    /// store always does a linear scan, regardless of how many mutations
+    /// preceded a search. `optimize` is a safe no-op beyond redoing that
+    /// rewrite, kept as an explicit entry point for callers expecting the
+    /// compaction step a LanceDB-backed store would need here.
+    ///
+    /// Takes `&self`, like [`Self::search`]: unlike the mutating methods
+    /// above, this never changes `self.rows`, so it's safe to call
+    /// concurrently with an in-flight search under Rust's borrow rules.
+    /// Calling it concurrently with a mutation still needs the caller's own
+    /// synchronization, same as any other `&self`/`&mut self` mix here.
+    pub fn optimize(&self) -> Result<(), VectorStoreError> {
+        self.persist()
+    }
+
+    /// Appends `chunks` with their corresponding `embeddings` and persists the store.
+    pub fn add_chunks(
+        &mut self,
+        chunks: Vec<CodeChunk>,
+        embeddings: Vec<Vec<f32>>,
+    ) -> Result<(), VectorStoreError> {
+        for embedding in &embeddings {
+            if embedding.len() != self.config.embedding_dim {
+                return Err(VectorStoreError::DimensionMismatch {
+                    expected: self.config.embedding_dim,
+                    actual: embedding.len(),
+                });
+            }
+        }
+        self.rows.extend(
+            chunks
+                .into_iter()
+                .zip(embeddings)
+                .map(|(chunk, embedding)| StoredRow { chunk, embedding }),
+        );
+        self.persist()
+    }
+
+    /// Removes every chunk whose `path` matches exactly, returning the number
+    /// of rows removed. Returns `Ok(0)` without error if nothing matched.
+    pub fn delete_by_path(&mut self, path: &str) -> Result<usize, VectorStoreError> {
+        let before = self.rows.len();
+        self.rows.retain(|row| row.chunk.path != path);
+        let removed = before - self.rows.len();
+        if removed > 0 {
+            self.persist()?;
+        }
+        Ok(removed)
+    }
+
+    /// Replaces any existing rows whose `(path, start_line, end_line)` key
+    /// matches one of `chunks` and inserts `chunks` in their place, in a
+    /// single call.
+    ///
+    /// The delete pass only considers rows already in the store, so chunks
+    /// within the same batch that share a key never delete one another.
+    pub fn upsert_chunks(
+        &mut self,
+        chunks: Vec<CodeChunk>,
+        embeddings: Vec<Vec<f32>>,
+    ) -> Result<(), VectorStoreError> {
+        let keys: std::collections::HashSet<(&str, usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.path.as_str(), chunk.start_line, chunk.end_line))
+            .collect();
+        self.rows.retain(|row| {
+            !keys.contains(&(
+                row.chunk.path.as_str(),
+                row.chunk.start_line,
+                row.chunk.end_line,
+            ))
+        });
+        self.add_chunks(chunks, embeddings)
+    }
+
+    /// Scores every row against `query_embedding` using the store's configured
+    /// [`DistanceMetric`] and returns `(row_index, score)` pairs, unsorted.
+    ///
+    /// This is the query builder that every public search entry point goes
+    /// through, so that the configured distance metric is always honored.
+    fn store_simple(&self, query_embedding: &[f32], filter: &SearchFilter) -> Vec<(usize, f32)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| filter.is_empty() || filter.matches(&row.chunk))
+            .map(|(idx, row)| (idx, score(self.config.distance_metric, query_embedding, &row.embedding)))
+            .collect()
+    }
+
+    /// Returns the `limit` best-scoring chunks for `query_embedding`.
+    pub fn search(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        self.search_filtered(query_embedding, limit, &SearchFilter::default())
+    }
+
+    /// Like [`Self::search`], but only scores rows matching `filter`.
+    ///
+    /// `filter` is applied before scoring, so `limit` hits are drawn from the
+    /// filtered set rather than from an unfiltered top-k. A filter matching
+    /// nothing returns an empty `Vec` rather than an error.
+    pub fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        if query_embedding.len() != self.config.embedding_dim {
+            return Err(VectorStoreError::DimensionMismatch {
+                expected: self.config.embedding_dim,
+                actual: query_embedding.len(),
+            });
+        }
+        let mut scored = self.store_simple(query_embedding, filter);
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let embedding_service = self
+            .config
+            .compute_match_span
+            .then(|| EmbeddingService::with_config(EmbeddingConfig {
+                dimension: Some(self.config.embedding_dim),
+                ..Default::default()
+            }));
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(idx, score)| {
+                let chunk = self.rows[idx].chunk.clone();
+                let matched_line_range = embedding_service
+                    .as_ref()
+                    .and_then(|service| matched_line_range(service, &chunk, query_embedding));
+                SearchResult {
+                    chunk,
+                    score,
+                    matched_line_range,
+                    distance: 0.0,
+                    embedding: None,
+                }
+            })
+            .collect())
+    }
+
+    /// Like [`Self::search_filtered`], but also computes
+    /// [`SearchResult::distance`] and, if requested,
+    /// [`SearchResult::embedding`] - extra output that downstream clustering
+    /// or dedup needs but that the plain `search`/`search_filtered` entry
+    /// points skip, since most callers only want `score`.
+    ///
+    /// `options.metric` must match [`VectorStoreConfig::distance_metric`]:
+    /// this store always scores every row with the single metric it was
+    /// opened with, so there's no way to honor a request for a different
+    /// one without a second full scan under a metric the table was never
+    /// built for. A store opened with [`DistanceMetric::DotProduct`] is not
+    /// rejected outright - `options.metric` still has to match it like any
+    /// other metric, it's just that the resulting `distance` is the raw dot
+    /// product rather than a "closer is lower" value (see [`distance`]).
+    pub fn search_with_options(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>, VectorStoreError> {
+        if options.metric != self.config.distance_metric {
+            return Err(VectorStoreError::UnsupportedMetric {
+                requested: options.metric,
+                configured: self.config.distance_metric,
+            });
+        }
+        if query_embedding.len() != self.config.embedding_dim {
+            return Err(VectorStoreError::DimensionMismatch {
+                expected: self.config.embedding_dim,
+                actual: query_embedding.len(),
+            });
+        }
+        let mut scored = self.store_simple(query_embedding, filter);
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(idx, score)| {
+                let row = &self.rows[idx];
+                SearchResult {
+                    chunk: row.chunk.clone(),
+                    score,
+                    matched_line_range: None,
+                    distance: distance(options.metric, query_embedding, &row.embedding),
+                    embedding: options.include_embedding.then(|| row.embedding.clone()),
+                }
+            })
+            .collect())
+    }
+
+    /// Like [`Self::search`], but yields results one at a time instead of
+    /// collecting them into a `Vec` first.
+    ///
+    /// Rows are scored and sorted eagerly since this store is entirely
+    /// in-memory, but callers that only need the first few hits can drop the
+    /// stream early without paying for the results they never consumed.
+    pub fn search_stream(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> impl Stream<Item = Result<SearchResult, VectorStoreError>> + 'static {
+        let results = self.search(query_embedding, limit);
+        match results {
+            Ok(results) => stream::iter(results.into_iter().map(Ok)).boxed(),
+            Err(err) => stream::iter(std::iter::once(Err(err))).boxed(),
+        }
+    }
+
+    /// Returns the paths of every chunk whose `chunker_version` is older
+    /// than `version` (or missing entirely, which predates version tracking).
+    /// Deduplicated, in no particular order.
+    pub fn find_stale(&self, version: u32) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .rows
+            .iter()
+            .filter(|row| row.chunk.chunker_version.is_none_or(|v| v < version))
+            .map(|row| row.chunk.path.clone())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Iterates every chunk currently in the store, in no particular order.
+    pub fn chunks(&self) -> impl Iterator<Item = &CodeChunk> {
+        self.rows.iter().map(|row| &row.chunk)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// Scores `query` against `candidate` using `metric`, always on a "higher is
+/// better" scale (L2 distance is negated).
+fn score(metric: DistanceMetric, query: &[f32], candidate: &[f32]) -> f32 {
+    match metric {
+        DistanceMetric::Cosine => cosine_similarity(query, candidate),
+        DistanceMetric::DotProduct => dot(query, candidate),
+        DistanceMetric::L2 => -l2_distance(query, candidate),
+    }
+}
+
+/// Raw distance between `query` and `candidate` under `metric`, on a
+/// "lower is closer" scale - the opposite convention from [`score`], which
+/// always inverts L2 so that higher is better. [`DistanceMetric::DotProduct`]
+/// has no "closer is lower" interpretation to invert to, so it's reported
+/// as-is, the same raw value [`score`] already uses for it.
+fn distance(metric: DistanceMetric, query: &[f32], candidate: &[f32]) -> f32 {
+    match metric {
+        DistanceMetric::Cosine => 1.0 - cosine_similarity(query, candidate),
+        DistanceMetric::L2 => l2_distance(query, candidate),
+        DistanceMetric::DotProduct => dot(query, candidate),
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let norm_a = dot(a, a).sqrt();
+    let norm_b = dot(b, b).sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot(a, b) / (norm_a * norm_b)
+}
+
+fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Best-effort localization of which line of `chunk.content` best matches
+/// `query_embedding`, by re-embedding each line and picking the highest
+/// cosine-similarity one. Returns `None` for chunks with a single line,
+/// since there's nothing to distinguish.
+fn matched_line_range(
+    service: &EmbeddingService,
+    chunk: &CodeChunk,
+    query_embedding: &[f32],
+) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = chunk.content.lines().collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let line_embeddings = service.embed(&lines.iter().map(|line| line.to_string()).collect::<Vec<_>>());
+    let (best_offset, _) = line_embeddings
+        .iter()
+        .enumerate()
+        .map(|(offset, embedding)| (offset, cosine_similarity(query_embedding, embedding)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let line_number = chunk.start_line + best_offset;
+    Some((line_number, line_number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str) -> CodeChunk {
+        CodeChunk {
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 2,
+            content: "fn f() {}".to_string(),
+            language: Some("rust".to_string()),
+            chunk_type: None,
+            symbol_name: None,
+            content_hash: None,
+            chunker_version: None,
+            doc_summary: None,
+            context_imports: None,
+            estimated_tokens: None,
+        }
+    }
+
+    #[test]
+    fn distance_metric_can_flip_result_ordering() {
+        let dir = tempfile::tempdir().unwrap();
+        let query = vec![1.0, 0.0];
+        // `orthogonal` is close to `query` in Euclidean space but shares no
+        // direction with it; `aligned` points the same way as `query` but is
+        // farther away. Cosine/dot-product should rank `aligned` first,
+        // while L2 should rank `orthogonal` first.
+        let orthogonal = vec![0.0, 1.0];
+        let aligned = vec![5.0, 0.0];
+
+        for (metric, expected_winner) in [
+            (DistanceMetric::Cosine, "aligned.rs"),
+            (DistanceMetric::DotProduct, "aligned.rs"),
+            (DistanceMetric::L2, "orthogonal.rs"),
+        ] {
+            let config = VectorStoreConfig::new(
+                dir.path().join(format!("{expected_winner}-{metric:?}.jsonl")),
+                2,
+            )
+            .with_distance_metric(metric);
+            let mut store = VectorStore::open(config).unwrap();
+            store
+                .add_chunks(
+                    vec![chunk("orthogonal.rs"), chunk("aligned.rs")],
+                    vec![orthogonal.clone(), aligned.clone()],
+                )
+                .unwrap();
+            let results = store.search(&query, 2).unwrap();
+            assert_eq!(results[0].chunk.path, expected_winner, "metric {metric:?}");
+        }
+    }
+
+    #[test]
+    fn search_with_options_reports_distance_and_embedding_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        let results = store
+            .search_with_options(
+                &[1.0, 0.0],
+                10,
+                &SearchFilter::default(),
+                &SearchOptions {
+                    include_embedding: true,
+                    metric: DistanceMetric::Cosine,
+                },
+            )
+            .unwrap();
+        assert_eq!(results[0].distance, 0.0);
+        assert_eq!(results[0].embedding, Some(vec![1.0, 0.0]));
+    }
+
+    #[test]
+    fn search_with_options_defaults_skip_distance_and_embedding() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        let results = store.search(&[1.0, 0.0], 10).unwrap();
+        assert_eq!(results[0].distance, 0.0);
+        assert_eq!(results[0].embedding, None);
+    }
+
+    #[test]
+    fn search_with_options_rejects_a_metric_the_table_was_not_built_with() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2)
+            .with_distance_metric(DistanceMetric::Cosine);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        let err = store
+            .search_with_options(
+                &[1.0, 0.0],
+                10,
+                &SearchFilter::default(),
+                &SearchOptions {
+                    include_embedding: false,
+                    metric: DistanceMetric::L2,
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VectorStoreError::UnsupportedMetric {
+                requested: DistanceMetric::L2,
+                configured: DistanceMetric::Cosine,
+            }
+        ));
+
+        let err = store
+            .search_with_options(
+                &[1.0, 0.0],
+                10,
+                &SearchFilter::default(),
+                &SearchOptions {
+                    include_embedding: false,
+                    metric: DistanceMetric::DotProduct,
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, VectorStoreError::UnsupportedMetric { .. }));
+    }
+
+    #[test]
+    fn search_with_options_succeeds_against_a_dot_product_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2)
+            .with_distance_metric(DistanceMetric::DotProduct);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![2.0, 0.0]])
+            .unwrap();
+
+        let results = store
+            .search_with_options(
+                &[1.0, 0.0],
+                10,
+                &SearchFilter::default(),
+                &SearchOptions {
+                    include_embedding: true,
+                    metric: DistanceMetric::DotProduct,
+                },
+            )
+            .unwrap();
+        assert_eq!(results[0].distance, 2.0);
+        assert_eq!(results[0].embedding, Some(vec![2.0, 0.0]));
+    }
+
+    #[test]
+    fn delete_by_path_removes_only_matching_rows_and_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(
+                vec![chunk("a.rs"), chunk("b.rs")],
+                vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            )
+            .unwrap();
+
+        assert_eq!(store.delete_by_path("a.rs").unwrap(), 1);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.delete_by_path("a.rs").unwrap(), 0);
+    }
+
+    #[test]
+    fn stats_reports_row_count_and_a_nonzero_table_size_once_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+
+        assert_eq!(store.stats().unwrap(), VectorStoreStats::default());
+
+        store
+            .add_chunks(
+                vec![chunk("a.rs"), chunk("b.rs")],
+                vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            )
+            .unwrap();
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.row_count, 2);
+        assert!(stats.table_bytes > 0);
+    }
+
+    #[test]
+    fn optimize_is_a_no_op_that_leaves_rows_and_search_unaffected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        store.optimize().unwrap();
+
+        assert_eq!(store.stats().unwrap().row_count, 1);
+        let results = store.search(&[1.0, 0.0], 10).unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn upsert_replaces_existing_rows_without_chunks_deleting_each_other() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        let mut stale = chunk("a.rs");
+        stale.content = "stale".to_string();
+        let mut fresh = chunk("a.rs");
+        fresh.content = "fresh".to_string();
+        store
+            .upsert_chunks(vec![stale, fresh], vec![vec![1.0, 0.0], vec![0.0, 1.0]])
+            .unwrap();
+
+        let rows: Vec<_> = store
+            .search(&[1.0, 0.0], 10)
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.chunk.path == "a.rs")
+            .collect();
+        assert_eq!(rows.len(), 2, "both chunks in the upsert batch must survive");
+    }
+
+    #[test]
+    fn search_filtered_applies_language_and_path_predicates_before_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+
+        let mut rust_chunk = chunk("src/core/handler.rs");
+        rust_chunk.language = Some("rust".to_string());
+        let mut python_chunk = chunk("scripts/build.py");
+        python_chunk.language = Some("python".to_string());
+        store
+            .add_chunks(
+                vec![rust_chunk, python_chunk],
+                vec![vec![1.0, 0.0], vec![1.0, 0.0]],
+            )
+            .unwrap();
+
+        let filter = SearchFilter {
+            language: Some("rust".to_string()),
+            path_prefix: Some("src/core/".to_string()),
+            ..Default::default()
+        };
+        let results = store.search_filtered(&[1.0, 0.0], 10, &filter).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.path, "src/core/handler.rs");
+
+        let no_match = SearchFilter {
+            language: Some("go".to_string()),
+            ..Default::default()
+        };
+        assert!(store.search_filtered(&[1.0, 0.0], 10, &no_match).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_stream_can_be_dropped_after_first_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(
+                vec![chunk("a.rs"), chunk("b.rs"), chunk("c.rs")],
+                vec![vec![1.0, 0.0], vec![0.9, 0.1], vec![0.0, 1.0]],
+            )
+            .unwrap();
+
+        let mut results = store.search_stream(&[1.0, 0.0], 3);
+        let first = results.next().await.unwrap().unwrap();
+        assert_eq!(first.chunk.path, "a.rs");
+        drop(results);
+    }
+
+    #[test]
+    fn search_filtered_supports_line_range_predicate() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+
+        let mut top = chunk("a.rs");
+        top.start_line = 1;
+        top.end_line = 10;
+        let mut bottom = chunk("a.rs");
+        bottom.start_line = 100;
+        bottom.end_line = 120;
+        store
+            .add_chunks(vec![top, bottom], vec![vec![1.0, 0.0], vec![1.0, 0.0]])
+            .unwrap();
+
+        let filter = SearchFilter {
+            line_range: Some(0..20),
+            ..Default::default()
+        };
+        let results = store.search_filtered(&[1.0, 0.0], 10, &filter).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.start_line, 1);
+    }
+
+    #[test]
+    fn upsert_dedups_by_path_and_line_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+
+        let original = chunk("a.rs");
+        store
+            .add_chunks(vec![original.clone()], vec![vec![1.0, 0.0]])
+            .unwrap();
+
+        let mut updated = original;
+        updated.content = "fn f() { /* updated */ }".to_string();
+        store
+            .upsert_chunks(vec![updated], vec![vec![0.0, 1.0]])
+            .unwrap();
+
+        let rows = store.search(&[0.0, 1.0], 10).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].chunk.content, "fn f() { /* updated */ }");
+    }
+
+    #[test]
+    fn delete_by_path_leaves_other_files_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(
+                vec![chunk("a.rs"), chunk("b.rs")],
+                vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            )
+            .unwrap();
+
+        assert_eq!(store.delete_by_path("a.rs").unwrap(), 1);
+
+        let results = store.search(&[0.5, 0.5], 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.path, "b.rs");
+    }
+
+    #[test]
+    fn reopening_with_a_different_embedding_dim_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_path = dir.path().join("store.jsonl");
+
+        let compact = VectorStoreConfig::new(table_path.clone(), 2);
+        let store = VectorStore::open(compact).unwrap();
+        drop(store);
+
+        let mismatched = VectorStoreConfig::new(table_path, 4);
+        let err = VectorStore::open(mismatched).unwrap_err();
+        assert!(matches!(
+            err,
+            VectorStoreError::DimensionMismatch {
+                expected: 2,
+                actual: 4,
+            }
+        ));
+    }
+
+    #[test]
+    fn compute_match_span_localizes_the_best_matching_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = EmbeddingService::with_config(EmbeddingConfig {
+            dimension: Some(8),
+            ..Default::default()
+        });
+
+        let mut multi_line = chunk("a.rs");
+        multi_line.content = "fn unrelated() {}\nfn parse_error_handling() {}\n".to_string();
+        multi_line.start_line = 10;
+
+        let config =
+            VectorStoreConfig::new(dir.path().join("store.jsonl"), 8).with_compute_match_span(true);
+        let mut store = VectorStore::open(config).unwrap();
+        let embedding = service
+            .embed(&[multi_line.content.clone()])
+            .remove(0);
+        store
+            .add_chunks(vec![multi_line], vec![embedding])
+            .unwrap();
+
+        let query = service.embed(&["fn parse_error_handling".to_string()]).remove(0);
+        let results = store.search(&query, 1).unwrap();
+        assert_eq!(results[0].matched_line_range, Some((11, 11)));
+    }
+
+    #[test]
+    fn compute_match_span_is_none_for_single_line_chunks_and_when_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            VectorStoreConfig::new(dir.path().join("store.jsonl"), 2).with_compute_match_span(true);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+        let results = store.search(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(results[0].matched_line_range, None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(vec![chunk("a.rs")], vec![vec![1.0, 0.0]])
+            .unwrap();
+        let results = store.search(&[1.0, 0.0], 1).unwrap();
+        assert_eq!(results[0].matched_line_range, None);
+    }
+
+    #[test]
+    fn find_stale_reports_paths_below_the_given_chunker_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+
+        let mut old = chunk("old.rs");
+        old.chunker_version = Some(1);
+        let mut current = chunk("current.rs");
+        current.chunker_version = Some(2);
+        let mut untracked = chunk("untracked.rs");
+        untracked.chunker_version = None;
+
+        store
+            .add_chunks(
+                vec![old, current, untracked],
+                vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]],
+            )
+            .unwrap();
+
+        let mut stale = store.find_stale(2);
+        stale.sort();
+        assert_eq!(stale, vec!["old.rs".to_string(), "untracked.rs".to_string()]);
+        // Chunks with no recorded version predate version tracking and are
+        // always considered stale, regardless of the requested version.
+        assert_eq!(store.find_stale(1), vec!["untracked.rs".to_string()]);
+    }
+
+    #[test]
+    fn chunks_iterates_every_stored_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VectorStoreConfig::new(dir.path().join("store.jsonl"), 2);
+        let mut store = VectorStore::open(config).unwrap();
+        store
+            .add_chunks(
+                vec![chunk("a.rs"), chunk("b.rs")],
+                vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            )
+            .unwrap();
+
+        let mut paths: Vec<&str> = store.chunks().map(|chunk| chunk.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["a.rs", "b.rs"]);
+    }
+}